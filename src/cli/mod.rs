@@ -1,5 +1,6 @@
 #![forbid(unsafe_code)]
 
+use std::collections::{HashMap, HashSet};
 use std::ffi::OsString;
 use std::fmt::Write as _;
 use std::path::Path;
@@ -11,10 +12,17 @@ use anyhow::Context as _;
 use clap::{CommandFactory as _, Parser, Subcommand};
 
 use crate::config;
-use crate::core::discovery;
+use crate::core::discovery::{self, GlobalWorktreeEntry};
 use crate::core::git::Git;
+use crate::core::hooks::{HookKind, HookOutput};
+use crate::core::naming::{RepoId, parse_origin_url};
+use crate::core::process;
+use crate::core::query::StatusQuery;
 use crate::core::status::{self, StatusCollector, StatusCollectorOptions, WorktreeStatus};
-use crate::core::worktree::{Worktree, WorktreeManager};
+use crate::core::tree;
+use crate::core::worktree::{
+    self, CleanCandidate, CleanReason, ReviewKind, Worktree, WorktreeManager, detached_label,
+};
 use crate::mux::Mux as _;
 use crate::mux::zellij::ZellijMux;
 use crate::output::table::Table;
@@ -46,8 +54,16 @@ pub enum Commands {
     #[command(alias = "rm")]
     Remove(RemoveArgs),
     Status(StatusArgs),
-    Prune,
+    Prune(PruneArgs),
+    Lock(LockArgs),
+    Unlock(UnlockArgs),
+    Move(MoveArgs),
+    Relayout(RelayoutArgs),
+    Repair(RepairArgs),
+    Clean(CleanArgs),
     Config(ConfigArgs),
+    Cache(CacheArgs),
+    Repo(RepoArgs),
     Completion(CompletionArgs),
     Tmux(MuxArgs),
     Zellij(MuxArgs),
@@ -55,6 +71,7 @@ pub enum Commands {
     Version,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 pub struct AddArgs {
     /// Create new branch
@@ -66,6 +83,27 @@ pub struct AddArgs {
     /// Overwrite existing directory
     #[arg(short = 'f', long = "force")]
     pub force: bool,
+    /// Skip post_add hooks
+    #[arg(long = "no-hooks")]
+    pub no_hooks: bool,
+    /// Skip copying/linking worktree.copy_files and worktree.link_files
+    #[arg(long = "no-copy")]
+    pub no_copy: bool,
+    /// Move uncommitted changes (including untracked files) of the current worktree into the new one
+    #[arg(long)]
+    pub carry: bool,
+    /// Check out GitHub pull request N (refs/pull/N/head) as branch pr/N
+    #[arg(long = "pr", value_name = "N", conflicts_with_all = ["mr", "branch", "interactive"])]
+    pub pr: Option<u64>,
+    /// Check out GitLab merge request N (refs/merge-requests/N/head) as branch mr/N
+    #[arg(long = "mr", value_name = "N", conflicts_with_all = ["branch", "interactive"])]
+    pub mr: Option<u64>,
+    /// Remote to fetch --pr/--mr from (default: worktree.review_remote)
+    #[arg(long = "remote", value_name = "NAME")]
+    pub remote: Option<String>,
+    /// Create a detached worktree at a commit, tag or other ref (no branch)
+    #[arg(long = "detach", value_name = "REV", conflicts_with_all = ["branch", "interactive", "pr", "mr", "carry"])]
+    pub detach: Option<String>,
     /// Branch name
     pub branch_name: Option<String>,
     /// Optional path
@@ -83,6 +121,9 @@ pub struct ListArgs {
     /// Show all worktrees from the configured base directory
     #[arg(short = 'g', long = "global")]
     pub global: bool,
+    /// Group worktrees by host, owner and repository
+    #[arg(long = "tree")]
+    pub tree: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -93,6 +134,9 @@ pub struct GetArgs {
     /// Output null-terminated path
     #[arg(short = '0', long = "null")]
     pub null_terminate: bool,
+    /// Only consider worktrees matching a status query (see `status --filter`)
+    #[arg(long = "filter")]
+    pub filter: Option<String>,
     /// Pattern
     pub pattern: Option<String>,
 }
@@ -108,7 +152,7 @@ pub struct ExecArgs {
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 pub struct RemoveArgs {
-    /// Force delete even if dirty
+    /// Force delete even if dirty or locked
     #[arg(short = 'f', long = "force")]
     pub force: bool,
     /// Show deletion targets only
@@ -123,10 +167,99 @@ pub struct RemoveArgs {
     /// Force delete the branch even if not merged
     #[arg(long = "force-delete-branch")]
     pub force_delete_branch: bool,
+    /// Skip pre_remove/post_remove hooks
+    #[arg(long = "no-hooks")]
+    pub no_hooks: bool,
+    /// Only consider worktrees matching a status query (see `status --filter`)
+    #[arg(long = "filter")]
+    pub filter: Option<String>,
+    /// Pattern
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct PruneArgs {
+    /// Also prune locked worktrees whose directories are gone
+    #[arg(short = 'f', long = "force")]
+    pub force: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct LockArgs {
+    /// Reason recorded with the lock
+    #[arg(long = "reason")]
+    pub reason: Option<String>,
     /// Pattern
     pub pattern: Option<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct UnlockArgs {
+    /// Pattern
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct MoveArgs {
+    /// Pattern
+    pub pattern: String,
+    /// Destination path (defaults to the path rendered from worktree.naming_template)
+    pub new_path: Option<String>,
+}
+
+#[derive(Debug, Parser)]
+pub struct RelayoutArgs {
+    /// Show planned moves only
+    #[arg(short = 'd', long = "dry-run")]
+    pub dry_run: bool,
+    /// Re-layout worktrees of every repository found in the base directory
+    #[arg(short = 'g', long = "global")]
+    pub global: bool,
+}
+
+#[derive(Debug, Parser)]
+pub struct RepairArgs {
+    /// Only list orphaned worktrees (exits non-zero when any are found)
+    #[arg(long = "check")]
+    pub check: bool,
+    /// Repository the orphans belong to (repeatable; main checkouts in the base directory are tried automatically)
+    #[arg(long = "repo")]
+    pub repos: Vec<String>,
+    /// Delete orphaned directories that cannot be repaired
+    #[arg(long = "delete")]
+    pub delete: bool,
+    /// Do not ask for confirmation
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Parser)]
+pub struct CleanArgs {
+    /// Remove worktrees whose branch is merged into the default branch or whose upstream is gone
+    #[arg(long = "merged")]
+    pub merged: bool,
+    /// Show what would be removed only
+    #[arg(short = 'd', long = "dry-run")]
+    pub dry_run: bool,
+    /// Clean every repository found in the base directory
+    #[arg(short = 'g', long = "global")]
+    pub global: bool,
+    /// Also remove dirty, unpushed or locked worktrees; branches with commits
+    /// that exist nowhere else are kept
+    #[arg(short = 'f', long = "force")]
+    pub force: bool,
+    /// Do not ask for confirmation
+    #[arg(short = 'y', long = "yes")]
+    pub yes: bool,
+    /// Skip pre_remove/post_remove hooks
+    #[arg(long = "no-hooks")]
+    pub no_hooks: bool,
+}
+
+/// Inactivity before a worktree counts as stale unless `--stale-days` says otherwise.
+const DEFAULT_STALE_DAYS: u64 = 14;
+
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Parser)]
 pub struct StatusArgs {
@@ -136,11 +269,18 @@ pub struct StatusArgs {
     /// Refresh interval in seconds for watch mode
     #[arg(short = 'i', long = "interval", default_value_t = 5)]
     pub interval_seconds: u64,
-    /// Filter by status (changed, up to date, inactive)
+    /// Filter query, e.g. `modified`, `repo:api ahead>0`, `stale AND NOT locked`,
+    /// `branch:~^feat/ OR activity<2d`
     #[arg(short = 'f', long = "filter", default_value = "")]
     pub filter: String,
-    /// Sort by field (branch, modified, activity)
-    #[arg(short = 's', long = "sort", default_value = "")]
+    /// Comma-separated sort keys (branch, changes, activity, status, ahead, behind,
+    /// base); prefix a key with `-` or `+` to sort it descending or ascending
+    #[arg(
+        short = 's',
+        long = "sort",
+        default_value = "",
+        allow_hyphen_values = true
+    )]
     pub sort: String,
     /// Output as JSON
     #[arg(long = "json")]
@@ -157,11 +297,12 @@ pub struct StatusArgs {
     /// Include running processes (slower)
     #[arg(long = "show-processes")]
     pub show_processes: bool,
-    /// Skip remote status check (faster)
+    /// Skip fetching remotes and the upstream ahead/behind check (faster); the
+    /// default-branch comparison uses local refs
     #[arg(long = "no-fetch")]
     pub no_fetch: bool,
     /// Days of inactivity before marking as stale
-    #[arg(long = "stale-days", default_value_t = 14)]
+    #[arg(long = "stale-days", default_value_t = DEFAULT_STALE_DAYS)]
     pub stale_days: u64,
 }
 
@@ -178,11 +319,50 @@ pub struct ConfigArgs {
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCmd {
-    List,
+    List(ConfigListArgs),
     Set(ConfigSetArgs),
     Get(ConfigGetArgs),
 }
 
+#[derive(Debug, Parser)]
+pub struct CacheArgs {
+    #[command(subcommand)]
+    pub cmd: CacheCmd,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum CacheCmd {
+    /// Delete the discovery index and the activity cache
+    Clear,
+}
+
+#[derive(Debug, Parser)]
+pub struct RepoArgs {
+    #[command(subcommand)]
+    pub cmd: RepoCmd,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum RepoCmd {
+    /// Register the main repository of a path (defaults to the current directory)
+    Add { path: Option<String> },
+    /// Unregister a repository by path or directory name
+    Remove { repo: String },
+    /// List registered repositories
+    List {
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Parser)]
+pub struct ConfigListArgs {
+    /// Show where each value comes from: the defaults, the user config, a
+    /// [[repos]] entry or the repository's .gwtui.toml
+    #[arg(long)]
+    pub origin: bool,
+}
+
 #[derive(Debug, Parser)]
 pub struct ConfigSetArgs {
     pub key: String,
@@ -419,8 +599,23 @@ async fn run(cli: Cli, raw_args: Vec<OsString>) -> anyhow::Result<ExitCode> {
             Ok(ExitCode::SUCCESS)
         }
         Some(Commands::Config(args)) => match args.cmd {
-            ConfigCmd::List => {
-                print!("{}", config::list_resolved_toml()?);
+            ConfigCmd::List(list) => {
+                let target = Git::from_cwd()
+                    .ok()
+                    .map(|git| worktree::repo_target(&git, true));
+                if !list.origin {
+                    print!("{}", config::list_resolved_toml(target.as_ref())?);
+                    return Ok(ExitCode::SUCCESS);
+                }
+                let lines: Vec<(String, config::Origin)> =
+                    config::list_with_origins(target.as_ref())?
+                        .into_iter()
+                        .map(|s| (format!("{} = {}", s.key, s.value), s.origin))
+                        .collect();
+                let width = lines.iter().map(|(l, _)| l.len()).max().unwrap_or(0);
+                for (line, origin) in lines {
+                    println!("{line:<width$}  # {origin}");
+                }
                 Ok(ExitCode::SUCCESS)
             }
             ConfigCmd::Set(set) => {
@@ -442,12 +637,20 @@ async fn run(cli: Cli, raw_args: Vec<OsString>) -> anyhow::Result<ExitCode> {
                 }
             }
         },
+        Some(Commands::Cache(args)) => cmd_cache(args).await,
+        Some(Commands::Repo(args)) => cmd_repo(args).await,
         Some(Commands::Add(args)) => cmd_add(args).await,
         Some(Commands::List(args)) => cmd_list(args).await,
         Some(Commands::Get(args)) => cmd_get(args).await,
         Some(Commands::Exec(args)) => cmd_exec(args, &raw_args).await,
         Some(Commands::Remove(args)) => cmd_remove(args).await,
-        Some(Commands::Prune) => cmd_prune().await,
+        Some(Commands::Prune(args)) => cmd_prune(args).await,
+        Some(Commands::Lock(args)) => cmd_lock(args).await,
+        Some(Commands::Unlock(args)) => cmd_unlock(args).await,
+        Some(Commands::Move(args)) => cmd_move(args).await,
+        Some(Commands::Relayout(args)) => cmd_relayout(args).await,
+        Some(Commands::Repair(args)) => cmd_repair(args).await,
+        Some(Commands::Clean(args)) => cmd_clean(args).await,
         Some(Commands::Status(args)) => cmd_status(args).await,
         Some(Commands::Tmux(args) | Commands::Zellij(args)) => cmd_tmux(args).await,
         Some(Commands::Task(args)) => cmd_task(args).await,
@@ -455,6 +658,120 @@ async fn run(cli: Cli, raw_args: Vec<OsString>) -> anyhow::Result<ExitCode> {
     }
 }
 
+async fn cmd_cache(args: CacheArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let Some(dir) = cfg.status.cache_path() else {
+        println!("Caching is disabled (status.cache_dir is empty)");
+        return Ok(ExitCode::SUCCESS);
+    };
+    match args.cmd {
+        CacheCmd::Clear => {
+            let removed = discovery::clear_caches(&dir)?;
+            if removed.is_empty() {
+                println!("Cache is already empty: {}", dir.display());
+            }
+            for path in removed {
+                println!("Removed {}", path.display());
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn cmd_repo(args: RepoArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let mut registry = cfg.discovery.repositories.clone();
+    match args.cmd {
+        RepoCmd::Add { path } => {
+            let dir = match path {
+                Some(p) => config::expand_path(&p)?,
+                None => std::env::current_dir()?,
+            };
+            let root = Git::from_dir(&dir)?.main_worktree_root()?;
+            let root = std::fs::canonicalize(&root).unwrap_or(root);
+            let display = root.to_string_lossy().to_string();
+            if discovery::registered_repositories(&cfg).contains(&root) {
+                println!("Already registered: {display}");
+                return Ok(ExitCode::SUCCESS);
+            }
+            registry.push(display.clone());
+            config::set_value_string("discovery.repositories", &serde_json::to_string(&registry)?)?;
+            println!("Registered {display}");
+        }
+        RepoCmd::Remove { repo } => {
+            let wanted = config::expand_path(&repo).ok();
+            let wanted = wanted.map(|p| std::fs::canonicalize(&p).unwrap_or(p));
+            let before = registry.len();
+            registry.retain(|r| {
+                let path = config::expand_path(r).unwrap_or_else(|_| PathBuf::from(r));
+                let same_path = wanted.as_ref().is_some_and(|w| {
+                    *w == path || std::fs::canonicalize(&path).is_ok_and(|p| p == *w)
+                });
+                let same_name = path
+                    .file_name()
+                    .is_some_and(|n| n.to_string_lossy() == repo);
+                !(same_path || same_name)
+            });
+            match before - registry.len() {
+                0 => anyhow::bail!(
+                    "repository '{repo}' is not registered - use 'gwtui repo list' to see registered repositories"
+                ),
+                1 => {}
+                n => anyhow::bail!(
+                    "'{repo}' matches {n} registered repositories - pass the full path"
+                ),
+            }
+            config::set_value_string("discovery.repositories", &serde_json::to_string(&registry)?)?;
+            println!("Unregistered {repo}");
+        }
+        RepoCmd::List { json } => {
+            #[derive(serde::Serialize)]
+            struct Entry {
+                path: String,
+                exists: bool,
+                worktrees: usize,
+            }
+            let entries: Vec<Entry> = registry
+                .iter()
+                .map(|r| {
+                    let path = config::expand_path(r).unwrap_or_else(|_| PathBuf::from(r));
+                    let worktrees = WorktreeManager::new(Git::new(path.clone()), cfg.clone())
+                        .list()
+                        .map(|w| w.len())
+                        .unwrap_or(0);
+                    Entry {
+                        path: r.clone(),
+                        exists: path.join(".git").exists() || path.join("HEAD").is_file(),
+                        worktrees,
+                    }
+                })
+                .collect();
+            if json {
+                let mut s = serde_json::to_string_pretty(&entries)?;
+                s.push('\n');
+                print!("{s}");
+                return Ok(ExitCode::SUCCESS);
+            }
+            if entries.is_empty() {
+                println!("No repositories registered - use 'gwtui repo add [path]'");
+            }
+            for e in entries {
+                let path = if cfg.ui.tilde_home {
+                    config::tilde_path(&e.path)
+                } else {
+                    e.path
+                };
+                if e.exists {
+                    println!("{path}  ({} worktrees)", e.worktrees);
+                } else {
+                    println!("{path}  (missing)");
+                }
+            }
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
 async fn load_cfg() -> anyhow::Result<crate::config::Config> {
     let cfg = tokio::task::spawn_blocking(|| -> anyhow::Result<crate::config::Config> {
         let (cfg, _doc, _paths) = config::load()?;
@@ -498,8 +815,27 @@ async fn cmd_add(args: AddArgs) -> anyhow::Result<ExitCode> {
     let git = Git::from_cwd()?;
     let wm = WorktreeManager::new(git.clone(), cfg.clone());
 
+    let review = match (args.pr, args.mr) {
+        (Some(n), _) => Some((ReviewKind::PullRequest, n)),
+        (None, Some(n)) => Some((ReviewKind::MergeRequest, n)),
+        (None, None) => None,
+    };
+
     let mut create_branch = args.branch;
-    let (branch, custom_path) = if args.interactive {
+    let (branch, custom_path) = if let Some(rev) = args.detach.clone() {
+        if args.path.is_some() {
+            anyhow::bail!("--detach takes at most one positional argument (the path)");
+        }
+        (rev, args.branch_name.map(std::path::PathBuf::from))
+    } else if let Some((kind, number)) = review {
+        // The branch comes from the review, so the only positional is the path.
+        if args.path.is_some() {
+            anyhow::bail!("--pr/--mr take at most one positional argument (the path)");
+        }
+        let branch = wm.fetch_review(kind, number, args.remote.as_deref())?;
+        println!("Fetched {} into '{branch}'", kind.remote_ref(number));
+        (branch, args.branch_name.map(std::path::PathBuf::from))
+    } else if args.interactive {
         if args.branch_name.is_some() || args.path.is_some() {
             anyhow::bail!("cannot specify branch name or path with -i flag");
         }
@@ -567,13 +903,48 @@ async fn cmd_add(args: AddArgs) -> anyhow::Result<ExitCode> {
         wm.validate_worktree_path(path)?;
     }
 
-    wm.add(&branch, custom_path.as_deref(), create_branch)?;
-    println!("Created worktree for branch '{branch}'");
+    let path = if args.detach.is_some() {
+        let path = wm.add_detached(&branch, custom_path.as_deref())?;
+        println!("Created detached worktree at '{branch}'");
+        path
+    } else {
+        let path = if args.carry {
+            wm.add_carrying_changes(&branch, custom_path.as_deref(), create_branch)?
+        } else {
+            wm.add(&branch, custom_path.as_deref(), create_branch)?
+        };
+        println!("Created worktree for branch '{branch}'");
+        path
+    };
+    if !args.no_copy {
+        let report = wm.copy_local_files(&path)?;
+        for f in &report.copied {
+            println!("Copied: {f}");
+        }
+        for f in &report.linked {
+            println!("Linked: {f}");
+        }
+        for (f, reason) in &report.skipped {
+            println!("Skipped: {f} ({reason})");
+        }
+    }
+    if !args.no_hooks {
+        wm.run_hooks(
+            HookKind::PostAdd,
+            &branch,
+            &path,
+            &wm.main_root(),
+            HookOutput::Inherit,
+        )?;
+    }
     Ok(ExitCode::SUCCESS)
 }
 
 async fn cmd_list(args: ListArgs) -> anyhow::Result<ExitCode> {
     let cfg = load_cfg().await?;
+    if args.tree {
+        return list_tree(&cfg, &args).await;
+    }
     let worktrees: Vec<Worktree> = if args.global {
         list_global_worktrees(&cfg)?
     } else {
@@ -598,37 +969,164 @@ async fn cmd_list(args: ListArgs) -> anyhow::Result<ExitCode> {
 }
 
 fn list_global_worktrees(cfg: &crate::config::Config) -> anyhow::Result<Vec<Worktree>> {
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
-    Ok(entries
+    Ok(global_entries(cfg)?
         .into_iter()
-        .map(|e| {
-            let branch = match &e.repository {
-                Some(repo) => format!("{repo}:{}", e.branch),
-                None => e.branch,
-            };
-            Worktree {
-                path: e.path,
-                branch,
-                commit_hash: e.commit_hash,
-                is_main: false,
-                created_at: "0001-01-01T00:00:00Z".to_owned(),
-            }
-        })
+        .map(|e| global_entry_worktree(e, true))
         .collect())
 }
 
+fn global_entries(cfg: &crate::config::Config) -> anyhow::Result<Vec<GlobalWorktreeEntry>> {
+    let report = discovery::discover_global_cached(cfg)?;
+    if !report.orphans.is_empty() {
+        eprintln!(
+            "Warning: {} orphaned worktree(s) found; run 'gwtui repair --check' for details",
+            report.orphans.len()
+        );
+    }
+    Ok(report.worktrees)
+}
+
+/// `qualify` prefixes the branch with the repository name (`repo:branch`).
+fn global_entry_worktree(e: GlobalWorktreeEntry, qualify: bool) -> Worktree {
+    let is_detached = e.branch == "HEAD";
+    let name = if is_detached {
+        detached_label(&e.commit_hash)
+    } else {
+        e.branch
+    };
+    let branch = match &e.repository {
+        Some(repo) if qualify => format!("{repo}:{name}"),
+        _ => name,
+    };
+    Worktree {
+        path: e.path,
+        branch,
+        commit_hash: e.commit_hash,
+        is_main: e.is_main,
+        created_at: "0001-01-01T00:00:00Z".to_owned(),
+        is_locked: e.is_locked,
+        lock_reason: e.lock_reason,
+        is_detached,
+        ..Worktree::default()
+    }
+}
+
+/// `list --tree`: worktrees grouped by host, owner and repository, with the
+/// number of worktrees, dirty worktrees and commits ahead/behind per group.
+async fn list_tree(cfg: &crate::config::Config, args: &ListArgs) -> anyhow::Result<ExitCode> {
+    let local = if args.global {
+        None
+    } else {
+        Git::from_cwd().ok()
+    };
+    let (worktrees, repo_ids): (Vec<Worktree>, HashMap<String, RepoId>) = match local {
+        Some(git) => {
+            let id = git
+                .get_repository_url()
+                .ok()
+                .and_then(|url| parse_origin_url(&url));
+            let worktrees = WorktreeManager::new(git, cfg.clone()).list()?;
+            let ids = worktrees
+                .iter()
+                .filter_map(|w| Some((w.path.clone(), id.clone()?)))
+                .collect();
+            (worktrees, ids)
+        }
+        None => {
+            let entries = global_entries(cfg)?;
+            let ids = entries
+                .iter()
+                .filter_map(|e| Some((e.path.clone(), parse_origin_url(&e.repository_url)?)))
+                .collect();
+            let worktrees = entries
+                .into_iter()
+                .map(|e| global_entry_worktree(e, false))
+                .collect();
+            (worktrees, ids)
+        }
+    };
+
+    let collector = status_collector(cfg, false, false, DEFAULT_STALE_DAYS);
+    let statuses = collector.collect_all(&worktrees).await?;
+    let groups = tree::build_tree(&statuses, |s| repo_ids.get(&s.path).cloned());
+
+    if args.json {
+        #[derive(serde::Serialize)]
+        struct Out<'a> {
+            groups: &'a [tree::RepoGroup],
+            worktrees: &'a [WorktreeStatus],
+        }
+        let mut s = serde_json::to_string_pretty(&Out {
+            groups: &groups,
+            worktrees: &statuses,
+        })?;
+        s.push('\n');
+        print!("{s}");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if statuses.is_empty() {
+        println!("No worktrees found");
+        return Ok(ExitCode::SUCCESS);
+    }
+    let mut t = Table::new(["NAME", "WORKTREES", "DIRTY", "AHEAD/BEHIND", "PATH"]);
+    for row in tree::flatten(&groups, &HashSet::new()) {
+        let indent = "  ".repeat(row.depth());
+        match row {
+            tree::TreeRow::Group { name, stats, .. } => t.row([
+                format!("{indent}{name}"),
+                stats.worktrees.to_string(),
+                stats.dirty.to_string(),
+                format!("↑{} ↓{}", stats.ahead, stats.behind),
+                String::new(),
+            ]),
+            tree::TreeRow::Worktree { index, .. } => {
+                let s = &statuses[index];
+                let wt = worktrees.iter().find(|w| w.path == s.path);
+                let marker = if wt.is_some_and(|w| w.is_main) && cfg.ui.icons {
+                    "● "
+                } else {
+                    "  "
+                };
+                let mut path = s.path.clone();
+                if cfg.ui.tilde_home {
+                    path = config::tilde_path(&path);
+                }
+                t.row([
+                    format!("{indent}{marker}{}", s.branch),
+                    String::new(),
+                    if status::count_total_changes(&s.git_status) > 0 {
+                        "*".to_owned()
+                    } else {
+                        String::new()
+                    },
+                    format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind),
+                    path,
+                ]);
+            }
+        }
+    }
+    t.print()?;
+    Ok(ExitCode::SUCCESS)
+}
+
 async fn cmd_get(args: GetArgs) -> anyhow::Result<ExitCode> {
     let cfg = load_cfg().await?;
 
+    let only = match &args.filter {
+        Some(q) => {
+            let global = args.global || Git::from_cwd().is_err();
+            Some(paths_matching_query(&cfg, global, q).await?)
+        }
+        None => None,
+    };
+    let pattern = args.pattern.as_deref();
     let out_path = if args.global {
-        get_global_worktree_path(&cfg, args.pattern.as_deref())?
+        get_global_worktree_path(&cfg, pattern, only.as_ref())?
     } else {
         match Git::from_cwd() {
-            Ok(git) => get_local_worktree_path(&cfg, &git, args.pattern.as_deref())?,
-            Err(_) => get_global_worktree_path(&cfg, args.pattern.as_deref())?,
+            Ok(git) => get_local_worktree_path(&cfg, &git, pattern, only.as_ref())?,
+            Err(_) => get_global_worktree_path(&cfg, pattern, only.as_ref())?,
         }
     };
 
@@ -650,11 +1148,11 @@ async fn cmd_exec(_args: ExecArgs, raw_args: &[OsString]) -> anyhow::Result<Exit
     let cfg = load_cfg().await?;
 
     let worktree_path = if parsed.global {
-        get_global_worktree_path(&cfg, parsed.pattern.as_deref())?
+        get_global_worktree_path(&cfg, parsed.pattern.as_deref(), None)?
     } else {
         match Git::from_cwd() {
-            Ok(git) => get_local_worktree_path(&cfg, &git, parsed.pattern.as_deref())?,
-            Err(_) => get_global_worktree_path(&cfg, parsed.pattern.as_deref())?,
+            Ok(git) => get_local_worktree_path(&cfg, &git, parsed.pattern.as_deref(), None)?,
+            Err(_) => get_global_worktree_path(&cfg, parsed.pattern.as_deref(), None)?,
         }
     };
 
@@ -680,8 +1178,15 @@ fn extract_subcommand_args(raw_args: &[OsString], subcommand: &str) -> Option<Ve
 async fn cmd_remove(args: RemoveArgs) -> anyhow::Result<ExitCode> {
     let cfg = load_cfg().await?;
 
+    let only = match &args.filter {
+        Some(q) => {
+            let global = args.global || Git::from_cwd().is_err();
+            Some(paths_matching_query(&cfg, global, q).await?)
+        }
+        None => None,
+    };
     if args.global {
-        remove_global(&cfg, &args)?;
+        remove_global(&cfg, &args, only.as_ref())?;
         return Ok(ExitCode::SUCCESS);
     }
 
@@ -689,25 +1194,403 @@ async fn cmd_remove(args: RemoveArgs) -> anyhow::Result<ExitCode> {
     match Git::from_cwd() {
         Ok(git) => {
             let wm = WorktreeManager::new(git, cfg.clone());
-            remove_local(&cfg, &wm, &args)?;
+            remove_local(&cfg, &wm, &args, only.as_ref())?;
         }
-        Err(_) => remove_global(&cfg, &args)?,
+        Err(_) => remove_global(&cfg, &args, only.as_ref())?,
     }
 
     Ok(ExitCode::SUCCESS)
 }
 
-async fn cmd_prune() -> anyhow::Result<ExitCode> {
+async fn cmd_prune(args: PruneArgs) -> anyhow::Result<ExitCode> {
     let cfg = load_cfg().await?;
     let git = Git::from_cwd()?;
     let wm = WorktreeManager::new(git, cfg);
-    wm.prune()?;
+    let locked = wm.prune(args.force)?;
     println!("Pruned stale worktree information");
+    for wt in &locked {
+        let reason = wt
+            .lock_reason
+            .as_deref()
+            .map(|r| format!(" ({r})"))
+            .unwrap_or_default();
+        if args.force {
+            println!("Unlocked and pruned: {}{reason}", wt.path);
+        } else {
+            println!("Skipped locked worktree: {}{reason}", wt.path);
+        }
+    }
+    if !args.force && !locked.is_empty() {
+        println!("Use 'gwtui prune --force' to prune locked worktrees");
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn cmd_lock(args: LockArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let git = Git::from_cwd()?;
+    let wm = WorktreeManager::new(git, cfg);
+    let candidates: Vec<Worktree> = wm
+        .list()?
+        .into_iter()
+        .filter(|wt| !wt.is_main && !wt.is_locked && matches_pattern(wt, args.pattern.as_deref()))
+        .collect();
+    if candidates.is_empty() {
+        anyhow::bail!("no unlocked worktree found to lock");
+    }
+    let path = resolve_one_worktree(candidates, "Select worktree to lock")?;
+    wm.lock(Path::new(&path), args.reason.as_deref())?;
+    println!("Locked worktree: {path}");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn cmd_unlock(args: UnlockArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let git = Git::from_cwd()?;
+    let wm = WorktreeManager::new(git, cfg);
+    let candidates: Vec<Worktree> = wm
+        .list()?
+        .into_iter()
+        .filter(|wt| wt.is_locked && matches_pattern(wt, args.pattern.as_deref()))
+        .collect();
+    if candidates.is_empty() {
+        anyhow::bail!("no locked worktree found to unlock");
+    }
+    let path = resolve_one_worktree(candidates, "Select worktree to unlock")?;
+    wm.unlock(Path::new(&path))?;
+    println!("Unlocked worktree: {path}");
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn cmd_move(args: MoveArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let git = Git::from_cwd()?;
+    let wm = WorktreeManager::new(git, cfg);
+    let candidates: Vec<Worktree> = wm
+        .list()?
+        .into_iter()
+        .filter(|wt| !wt.is_main && matches_pattern(wt, Some(&args.pattern)))
+        .collect();
+    if candidates.is_empty() {
+        anyhow::bail!("no worktree found matching pattern: {}", args.pattern);
+    }
+    let from = resolve_one_worktree(candidates.clone(), "Select worktree to move")?;
+    let to = if let Some(p) = args.new_path.as_deref() {
+        config::expand_path(p)?
+    } else {
+        let branch = candidates
+            .iter()
+            .find(|wt| wt.path == from)
+            .map(|wt| wt.branch.clone())
+            .unwrap_or_default();
+        if branch.is_empty() || branch == "HEAD" {
+            anyhow::bail!("detached worktree has no template path; pass a destination");
+        }
+        wm.expected_path(&branch)?
+    };
+
+    wm.move_worktree(Path::new(&from), &to)?;
+    println!("Moved worktree: {from} -> {}", to.display());
     Ok(ExitCode::SUCCESS)
 }
 
+async fn cmd_relayout(args: RelayoutArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+
+    let roots = repo_roots(&cfg, args.global)?;
+
+    let mut moved = 0usize;
+    let mut failed = 0usize;
+    let mut planned = 0usize;
+    for root in roots {
+        let wm = WorktreeManager::new(Git::new(root.clone()), cfg.clone());
+        let plan = match wm.plan_relayout() {
+            Ok(p) => p,
+            Err(e) => {
+                eprintln!("Error: failed to plan {}: {e}", root.display());
+                failed += 1;
+                continue;
+            }
+        };
+        for m in plan {
+            if let Some(reason) = &m.skip_reason {
+                println!("Skipping {}: {} ({reason})", m.branch, m.from.display());
+                continue;
+            }
+            planned += 1;
+            if args.dry_run {
+                println!(
+                    "Would move {}: {} -> {}",
+                    m.branch,
+                    m.from.display(),
+                    m.to.display()
+                );
+                continue;
+            }
+            match wm.move_worktree(&m.from, &m.to) {
+                Ok(()) => {
+                    moved += 1;
+                    println!(
+                        "Moved {}: {} -> {}",
+                        m.branch,
+                        m.from.display(),
+                        m.to.display()
+                    );
+                }
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("Error: failed to move {}: {e}", m.branch);
+                }
+            }
+        }
+    }
+
+    if args.dry_run {
+        println!("{planned} worktree(s) would be moved");
+    } else {
+        println!("Moved {moved} worktree(s)");
+    }
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Main worktree roots to operate on: the current repository, or with
+/// `global` every repository that has a worktree in the base directory.
+fn repo_roots(cfg: &crate::config::Config, global: bool) -> anyhow::Result<Vec<PathBuf>> {
+    if !global {
+        return Ok(vec![Git::from_cwd()?.main_worktree_root()?]);
+    }
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for e in entries {
+        if let Ok(root) = Git::new(PathBuf::from(&e.path)).main_worktree_root()
+            && !roots.contains(&root)
+        {
+            roots.push(root);
+        }
+    }
+    Ok(roots)
+}
+
+async fn cmd_clean(args: CleanArgs) -> anyhow::Result<ExitCode> {
+    if !args.merged {
+        anyhow::bail!("nothing to clean; pass --merged");
+    }
+    let cfg = load_cfg().await?;
+
+    let mut plan: Vec<(WorktreeManager, String, CleanCandidate)> = Vec::new();
+    for root in repo_roots(&cfg, args.global)? {
+        let wm = WorktreeManager::new(Git::new(root.clone()), cfg.clone());
+        let found = wm
+            .default_branch()
+            .and_then(|default| Ok((wm.clean_candidates(&default)?, default)));
+        match found {
+            Ok((candidates, default)) => {
+                for c in candidates {
+                    plan.push((wm.clone(), default.clone(), c));
+                }
+            }
+            Err(e) if args.global => eprintln!("Warning: skipping {}: {e}", root.display()),
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    if plan.is_empty() {
+        println!("No merged worktrees found");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut t = Table::new(["BRANCH", "PATH", "REASON", "CHANGES", "AHEAD", "ACTION"]);
+    for (_, default, c) in &plan {
+        let mut path = c.worktree.path.clone();
+        if cfg.ui.tilde_home {
+            path = config::tilde_path(&path);
+        }
+        let reason = match c.reason {
+            CleanReason::Merged => format!("merged into {default}"),
+            CleanReason::UpstreamGone => "upstream gone".to_owned(),
+        };
+        let action = match (&c.protected, args.force) {
+            (None, _) => "remove".to_owned(),
+            (Some(p), true) if c.branch_deletable => format!("remove ({p})"),
+            (Some(p), true) => format!("remove worktree, keep branch ({p})"),
+            (Some(p), false) => format!("keep: {p}"),
+        };
+        t.row([
+            c.worktree.branch.clone(),
+            path,
+            reason,
+            format_changes(&c.git_status),
+            c.git_status.ahead.to_string(),
+            action,
+        ]);
+    }
+    t.print()?;
+
+    let selected: Vec<&(WorktreeManager, String, CleanCandidate)> = plan
+        .iter()
+        .filter(|(_, _, c)| c.protected.is_none() || args.force)
+        .collect();
+    if args.dry_run {
+        println!("{} worktree(s) would be removed", selected.len());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if selected.is_empty() {
+        println!("Nothing to remove; use --force to remove protected worktrees");
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !args.yes
+        && !confirm(&format!(
+            "Remove {} worktree(s) and their branches?",
+            selected.len()
+        ))?
+    {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut failed = 0usize;
+    for (wm, _, c) in selected {
+        let remove_args = RemoveArgs {
+            force: args.force,
+            dry_run: false,
+            global: args.global,
+            // Deletable branches are proven merged, remote-contained or
+            // squash-merged, which `git branch -d` cannot see when HEAD or the
+            // upstream is elsewhere. --force only overrides the worktree-level
+            // protections, so any other branch is kept.
+            delete_branch: c.branch_deletable,
+            force_delete_branch: true,
+            no_hooks: args.no_hooks,
+            filter: None,
+            pattern: None,
+        };
+        let path = Path::new(&c.worktree.path);
+        if let Err(e) =
+            remove_worktree_with_hooks(wm, path, &c.worktree.branch, &c.worktree.path, &remove_args)
+        {
+            failed += 1;
+            eprintln!("Error: failed to remove {}: {e}", c.worktree.path);
+        }
+    }
+
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+async fn cmd_repair(args: RepairArgs) -> anyhow::Result<ExitCode> {
+    let cfg = load_cfg().await?;
+    let report = discovery::discover_global(&cfg)?;
+
+    if report.orphans.is_empty() {
+        println!("No orphaned worktrees found");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut candidates: Vec<PathBuf> = Vec::new();
+    for r in &args.repos {
+        candidates.push(config::expand_path(r)?);
+    }
+    if let Ok(root) = Git::from_cwd().and_then(|g| g.main_worktree_root()) {
+        candidates.push(root);
+    }
+    candidates.extend(report.main_repos.iter().cloned());
+
+    let plan: Vec<(&discovery::OrphanedWorktree, Option<PathBuf>)> = report
+        .orphans
+        .iter()
+        .map(|o| (o, discovery::find_repair_repo(o, &candidates)))
+        .collect();
+
+    if args.check {
+        let mut t = Table::new(["PATH", "GITDIR", "PROBLEM", "REPAIR WITH"]);
+        for (o, repo) in &plan {
+            let mut path = o.path.clone();
+            if cfg.ui.tilde_home {
+                path = config::tilde_path(&path);
+            }
+            t.row([
+                path,
+                o.gitdir.clone(),
+                o.reason.clone(),
+                repo.as_ref()
+                    .map_or_else(|| "-".to_owned(), |r| r.display().to_string()),
+            ]);
+        }
+        t.print()?;
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let mut failed = 0usize;
+    for (o, repo) in plan {
+        let path = Path::new(&o.path);
+        if let Some(repo) = repo {
+            if !args.yes && !confirm(&format!("Repair {} using {}?", o.path, repo.display()))? {
+                continue;
+            }
+            match Git::new(repo).repair_worktrees(&[path]) {
+                Ok(()) => println!("Repaired worktree: {}", o.path),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("Error: failed to repair {}: {e}", o.path);
+                }
+            }
+        } else if args.delete {
+            if !args.yes && !confirm(&format!("Delete orphaned directory {}?", o.path))? {
+                continue;
+            }
+            match std::fs::remove_dir_all(path) {
+                Ok(()) => println!("Deleted orphaned worktree: {}", o.path),
+                Err(e) => {
+                    failed += 1;
+                    eprintln!("Error: failed to delete {}: {e}", o.path);
+                }
+            }
+        } else {
+            failed += 1;
+            eprintln!(
+                "No repository found for {} ({}); pass --repo <path> or --delete",
+                o.path, o.reason
+            );
+        }
+    }
+
+    Ok(if failed > 0 {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+fn confirm(question: &str) -> anyhow::Result<bool> {
+    if !tui::is_tty() {
+        anyhow::bail!("confirmation requires a TTY; pass --yes");
+    }
+    print!("{question} (y/N): ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+    let mut input = String::new();
+    let _ = std::io::stdin().read_line(&mut input)?;
+    let resp = input.trim().to_lowercase();
+    Ok(resp == "y" || resp == "yes")
+}
+
+fn matches_pattern(wt: &Worktree, pattern: Option<&str>) -> bool {
+    let Some(pat) = pattern else {
+        return true;
+    };
+    let pat = pat.to_lowercase();
+    wt.branch.to_lowercase().contains(&pat) || wt.path.to_lowercase().contains(&pat)
+}
+
 async fn cmd_status(args: StatusArgs) -> anyhow::Result<ExitCode> {
     let cfg = load_cfg().await?;
+    StatusQuery::parse(&args.filter)?;
+    status::validate_sort(&args.sort)?;
 
     if args.watch && tui::is_tty() {
         let opts = StatusDashboardOptions {
@@ -738,47 +1621,84 @@ async fn collect_statuses_once(
     cfg: &crate::config::Config,
     args: &StatusArgs,
 ) -> anyhow::Result<Vec<WorktreeStatus>> {
-    let worktrees: Vec<Worktree> = match Git::from_cwd() {
-        Ok(git) if !args.global => {
+    let worktrees = load_status_worktrees(cfg, args.global)?;
+    let collector = status_collector(cfg, args.show_processes, !args.no_fetch, args.stale_days);
+    let mut statuses = collector.collect_all(&worktrees).await?;
+
+    if !args.filter.trim().is_empty() {
+        statuses = status::filter_statuses(statuses, &args.filter)?;
+    }
+    status::sort_statuses(&mut statuses, &args.sort)?;
+
+    Ok(statuses)
+}
+
+/// Worktrees of the current repository, or of every repository under the
+/// base directory with `global` (or outside a repository).
+fn load_status_worktrees(
+    cfg: &crate::config::Config,
+    global: bool,
+) -> anyhow::Result<Vec<Worktree>> {
+    Ok(match Git::from_cwd() {
+        Ok(git) if !global => {
             let wm = WorktreeManager::new(git, cfg.clone());
             wm.list()?
         }
         _ => {
-            let entries = discovery::discover_global_worktrees(
-                &cfg.worktree.base_dir,
-                cfg.discovery.global_scan_depth,
-            )?;
+            let entries = discovery::discover_global_worktrees_cached(cfg)?;
             entries
                 .into_iter()
                 .map(|e| Worktree {
+                    is_detached: e.branch == "HEAD",
                     path: e.path,
                     branch: e.branch,
                     commit_hash: e.commit_hash,
-                    is_main: false,
+                    is_main: e.is_main,
                     created_at: "0001-01-01T00:00:00Z".to_owned(),
+                    is_locked: e.is_locked,
+                    lock_reason: e.lock_reason,
+                    ..Worktree::default()
                 })
                 .collect()
         }
-    };
+    })
+}
 
-    let collector = StatusCollector::new(StatusCollectorOptions {
-        include_process: args.show_processes,
-        fetch_remote: !args.no_fetch,
-        stale_threshold: Duration::from_secs(args.stale_days * 24 * 60 * 60),
+fn status_collector(
+    cfg: &crate::config::Config,
+    include_process: bool,
+    fetch_remote: bool,
+    stale_days: u64,
+) -> StatusCollector {
+    StatusCollector::new(StatusCollectorOptions {
+        include_process,
+        fetch_remote,
+        include_base: true,
+        fetch_interval: Duration::from_secs(cfg.status.fetch_interval_seconds),
+        stale_threshold: Duration::from_secs(stale_days * 24 * 60 * 60),
         base_dir: cfg.worktree.base_dir.clone(),
         concurrency: cfg.status.concurrency,
-    });
-
-    let mut statuses = collector.collect_all(&worktrees).await?;
-
-    if !args.filter.trim().is_empty() {
-        statuses = status::filter_statuses(statuses, &args.filter);
-    }
-    if !args.sort.trim().is_empty() {
-        status::sort_statuses(&mut statuses, &args.sort);
-    }
+        activity: cfg.status.activity,
+        cache_dir: cfg.status.cache_path(),
+    })
+}
 
-    Ok(statuses)
+/// Paths of the worktrees matching a `--filter` query, for narrowing down
+/// `get`/`remove` candidates. Collects status without fetching.
+async fn paths_matching_query(
+    cfg: &crate::config::Config,
+    global: bool,
+    query: &str,
+) -> anyhow::Result<HashSet<String>> {
+    let query = StatusQuery::parse(query)?;
+    let worktrees = load_status_worktrees(cfg, global)?;
+    let collector = status_collector(cfg, false, false, DEFAULT_STALE_DAYS);
+    let statuses = collector.collect_all(&worktrees).await?;
+    Ok(statuses
+        .into_iter()
+        .filter(|s| query.matches(s))
+        .map(|s| s.path)
+        .collect())
 }
 
 fn output_statuses(
@@ -841,6 +1761,9 @@ fn output_statuses(
             "deleted",
             "ahead",
             "behind",
+            "base",
+            "base_ahead",
+            "base_behind",
             "last_activity",
             "process",
         ]);
@@ -853,8 +1776,11 @@ fn output_statuses(
                 s.git_status.deleted.to_string(),
                 s.git_status.ahead.to_string(),
                 s.git_status.behind.to_string(),
+                s.base.clone().unwrap_or_default(),
+                s.git_status.base_ahead.to_string(),
+                s.git_status.base_behind.to_string(),
                 s.last_activity.clone(),
-                String::new(),
+                process::format_processes_summary(&s.processes),
             ]);
         }
         t.write_csv()?;
@@ -864,23 +1790,24 @@ fn output_statuses(
     output_status_table(cfg, args.verbose, statuses)
 }
 
-fn format_changes(gs: status::GitStatus) -> String {
-    if gs.modified == 0 && gs.added == 0 && gs.deleted == 0 && gs.untracked == 0 {
+fn format_changes(gs: &status::GitStatus) -> String {
+    let counts = [
+        (gs.added, "added"),
+        (gs.modified, "modified"),
+        (gs.deleted, "deleted"),
+        (gs.renamed, "renamed"),
+        (gs.copied, "copied"),
+        (gs.typechange, "typechange"),
+        (gs.untracked, "untracked"),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{n} {label}"))
+        .collect();
+    if parts.is_empty() {
         return "-".to_owned();
     }
-    let mut parts = Vec::new();
-    if gs.added > 0 {
-        parts.push(format!("{} added", gs.added));
-    }
-    if gs.modified > 0 {
-        parts.push(format!("{} modified", gs.modified));
-    }
-    if gs.deleted > 0 {
-        parts.push(format!("{} deleted", gs.deleted));
-    }
-    if gs.untracked > 0 {
-        parts.push(format!("{} untracked", gs.untracked));
-    }
     parts.join(", ")
 }
 
@@ -934,6 +1861,11 @@ fn status_to_raw_string(status: status::WorktreeState) -> &'static str {
         status::WorktreeState::Conflict => "conflict",
         status::WorktreeState::Stale => "stale",
         status::WorktreeState::Unknown => "unknown",
+        status::WorktreeState::Rebasing => "rebasing",
+        status::WorktreeState::Merging => "merging",
+        status::WorktreeState::CherryPicking => "cherry-picking",
+        status::WorktreeState::Reverting => "reverting",
+        status::WorktreeState::Bisecting => "bisecting",
     }
 }
 
@@ -953,6 +1885,7 @@ fn output_status_table(
             "STATUS",
             "CHANGES",
             "AHEAD/BEHIND",
+            "BASE",
             "ACTIVITY",
             "PROCESS",
         ]);
@@ -965,10 +1898,11 @@ fn output_status_table(
             t.row([
                 format!("{marker}{}", s.branch),
                 status::format_status_for_table(s.status).to_owned(),
-                format_changes(s.git_status),
-                format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind),
+                format_changes(&s.git_status),
+                format_ahead_behind(s),
+                status::format_base_divergence(s),
                 format_activity(&s.last_activity),
-                "-".to_owned(),
+                process::format_processes_summary(&s.processes),
             ]);
         }
         t.print()?;
@@ -983,16 +1917,35 @@ fn output_status_table(
             t.row([
                 format!("{marker}{}", s.branch),
                 status::format_status_for_table(s.status).to_owned(),
-                format_changes(s.git_status),
+                format_changes(&s.git_status),
                 format_activity(&s.last_activity),
             ]);
         }
         t.print()?;
     }
 
+    let mut reported: Vec<&str> = Vec::new();
+    for s in statuses {
+        if let Some(err) = s.fetch_error.as_deref()
+            && !reported.contains(&err)
+        {
+            reported.push(err);
+            eprintln!("Warning: fetch failed for {}: {err}", s.repository);
+        }
+    }
+
     Ok(())
 }
 
+fn format_ahead_behind(s: &WorktreeStatus) -> String {
+    let ab = format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind);
+    if s.fetch_error.is_some() {
+        format!("{ab} (fetch failed)")
+    } else {
+        ab
+    }
+}
+
 async fn cmd_status_watch_nontty(
     cfg: &crate::config::Config,
     args: &StatusArgs,
@@ -1399,10 +2352,7 @@ fn resolve_worktree_path_str(pattern: &str, cfg: &crate::config::Config) -> anyh
         }
     }
 
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
     let matches = discovery::filter_global_worktrees(&entries, pattern);
     if matches.is_empty() {
         anyhow::bail!("no worktree found matching pattern: {pattern}");
@@ -2463,7 +3413,7 @@ fn print_worktree_table(
     }
 
     if verbose {
-        let mut t = Table::new(["BRANCH", "PATH", "COMMIT", "CREATED", "TYPE"]);
+        let mut t = Table::new(["BRANCH", "PATH", "COMMIT", "CREATED", "TYPE", "FLAGS"]);
         for wt in worktrees {
             let marker = if wt.is_main && cfg.ui.icons {
                 "● "
@@ -2475,7 +3425,7 @@ fn print_worktree_table(
                 path = config::tilde_path(&path);
             }
             t.row([
-                format!("{marker}{}", wt.branch),
+                format!("{marker}{}", wt.display_branch()),
                 path,
                 truncate_hash(&wt.commit_hash),
                 format_list_created(&wt.created_at),
@@ -2484,6 +3434,7 @@ fn print_worktree_table(
                 } else {
                     "worktree".to_owned()
                 },
+                format_worktree_flags(wt),
             ]);
         }
         t.print()?;
@@ -2499,7 +3450,7 @@ fn print_worktree_table(
             if cfg.ui.tilde_home {
                 path = config::tilde_path(&path);
             }
-            t.row([format!("{marker}{}", wt.branch), path]);
+            t.row([format!("{marker}{}", wt.display_branch()), path]);
         }
         t.print()?;
     }
@@ -2507,6 +3458,18 @@ fn print_worktree_table(
     Ok(())
 }
 
+fn format_worktree_flags(wt: &Worktree) -> String {
+    let flags = wt.flags();
+    if flags.is_empty() {
+        return "-".to_owned();
+    }
+    let mut out = flags.join(",");
+    if let Some(reason) = wt.lock_reason.as_deref() {
+        out = format!("{out} ({reason})");
+    }
+    out
+}
+
 fn format_list_created(created_at: &str) -> String {
     if created_at.is_empty() {
         return "unknown".to_owned();
@@ -2536,31 +3499,30 @@ fn get_local_worktree_path(
     cfg: &crate::config::Config,
     git: &Git,
     pattern: Option<&str>,
+    only: Option<&HashSet<String>>,
 ) -> anyhow::Result<String> {
     let wm = WorktreeManager::new(git.clone(), cfg.clone());
-    let path = if let Some(pat) = pattern {
-        let matches = wm.get_matching_worktrees(pat)?;
-        resolve_one_worktree(matches, "worktree selection")?
-    } else {
-        let worktrees = wm.list()?;
-        resolve_one_worktree(worktrees, "worktree selection")?
+    let mut worktrees = match pattern {
+        Some(pat) => wm.get_matching_worktrees(pat)?,
+        None => wm.list()?,
     };
-    Ok(path)
+    if let Some(only) = only {
+        worktrees.retain(|wt| only.contains(&wt.path));
+    }
+    resolve_one_worktree(worktrees, "worktree selection")
 }
 
 fn get_global_worktree_path(
     cfg: &crate::config::Config,
     pattern: Option<&str>,
+    only: Option<&HashSet<String>>,
 ) -> anyhow::Result<String> {
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
     if entries.is_empty() {
         anyhow::bail!("no worktrees found across all repositories");
     }
 
-    let matches: Vec<&discovery::GlobalWorktreeEntry> = if let Some(pat) = pattern {
+    let mut matches: Vec<&discovery::GlobalWorktreeEntry> = if let Some(pat) = pattern {
         let m = discovery::filter_global_worktrees(&entries, pat);
         if m.is_empty() {
             anyhow::bail!("no worktree matches pattern: {pat}");
@@ -2569,6 +3531,12 @@ fn get_global_worktree_path(
     } else {
         entries.iter().collect()
     };
+    if let Some(only) = only {
+        matches.retain(|e| only.contains(&e.path));
+        if matches.is_empty() {
+            anyhow::bail!("no worktree matches the filter");
+        }
+    }
 
     if matches.len() == 1 {
         return Ok(matches[0].path.clone());
@@ -2613,10 +3581,10 @@ fn resolve_one_worktree(mut worktrees: Vec<Worktree>, title: &str) -> anyhow::Re
     let items: Vec<PickerItem> = worktrees
         .iter()
         .map(|wt| PickerItem {
-            title: format!("{} ({})", wt.branch, wt.path),
+            title: format!("{} ({})", wt.display_branch(), wt.path),
             preview: format!(
                 "Branch: {}\nPath: {}\nCommit: {}\nCreated: {}",
-                wt.branch,
+                wt.display_branch(),
                 wt.path,
                 truncate_hash(&wt.commit_hash),
                 wt.created_at
@@ -2715,9 +3683,13 @@ fn remove_local(
     _cfg: &crate::config::Config,
     wm: &WorktreeManager,
     args: &RemoveArgs,
+    only: Option<&HashSet<String>>,
 ) -> anyhow::Result<()> {
     let worktrees = wm.list()?;
-    let candidates: Vec<Worktree> = worktrees.into_iter().filter(|w| !w.is_main).collect();
+    let candidates: Vec<Worktree> = worktrees
+        .into_iter()
+        .filter(|w| !w.is_main && only.is_none_or(|o| o.contains(&w.path)))
+        .collect();
     if candidates.is_empty() {
         anyhow::bail!("no removable worktrees found");
     }
@@ -2747,8 +3719,11 @@ fn remove_local(
     if args.dry_run {
         println!("Would remove the following worktrees:");
         for wt in &selected {
-            println!("  {} ({})", wt.branch, wt.path);
-            if args.delete_branch {
+            println!("  {} ({})", wt.display_branch(), wt.path);
+            if wt.is_locked && !args.force {
+                println!("    - Locked; would be refused without --force");
+            }
+            if args.delete_branch && !wt.is_detached {
                 println!("    - Would delete branch: {}", wt.branch);
             }
         }
@@ -2757,39 +3732,28 @@ fn remove_local(
 
     for wt in selected {
         let path = std::path::PathBuf::from(&wt.path);
-        if args.delete_branch {
-            if let Err(e) = wm.remove_with_branch(
-                &path,
-                &wt.branch,
-                args.force,
-                args.delete_branch,
-                args.force_delete_branch,
-            ) {
-                eprintln!("Error: failed to remove {}: {e}", wt.branch);
-                continue;
-            }
-            println!("Removed worktree: {}", wt.branch);
-            println!("Deleted branch: {}", wt.branch);
-        } else {
-            if let Err(e) = wm.remove(&path, args.force) {
-                eprintln!("Error: failed to remove {}: {e}", wt.branch);
-                continue;
-            }
-            println!("Removed worktree: {}", wt.branch);
+        let label = wt.display_branch();
+        if let Err(e) = remove_worktree_with_hooks(wm, &path, &wt.branch, &label, args) {
+            eprintln!("Error: failed to remove {label}: {e}");
         }
     }
 
     Ok(())
 }
 
-fn remove_global(cfg: &crate::config::Config, args: &RemoveArgs) -> anyhow::Result<()> {
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
+fn remove_global(
+    cfg: &crate::config::Config,
+    args: &RemoveArgs,
+    only: Option<&HashSet<String>>,
+) -> anyhow::Result<()> {
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
     if entries.is_empty() {
         anyhow::bail!("no worktrees found in {}", cfg.worktree.base_dir);
     }
+    let entries: Vec<discovery::GlobalWorktreeEntry> = entries
+        .into_iter()
+        .filter(|e| only.is_none_or(|o| o.contains(&e.path)))
+        .collect();
     let candidates: Vec<&discovery::GlobalWorktreeEntry> =
         entries.iter().filter(|e| !e.is_main).collect();
     if candidates.is_empty() {
@@ -2816,7 +3780,7 @@ fn remove_global(cfg: &crate::config::Config, args: &RemoveArgs) -> anyhow::Resu
         for e in &selected {
             let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
             println!("  {repo}:{} ({})", e.branch, e.path);
-            if args.delete_branch {
+            if args.delete_branch && e.branch != "HEAD" {
                 println!("    - Would delete branch: {}", e.branch);
             }
         }
@@ -2827,35 +3791,65 @@ fn remove_global(cfg: &crate::config::Config, args: &RemoveArgs) -> anyhow::Resu
         let git = Git::new(PathBuf::from(&e.path));
         let wm = WorktreeManager::new(git, cfg.clone());
         let path = std::path::PathBuf::from(&e.path);
-        if args.delete_branch {
-            if let Err(err) = wm.remove_with_branch(
-                &path,
-                &e.branch,
-                args.force,
-                args.delete_branch,
-                args.force_delete_branch,
-            ) {
-                let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
-                eprintln!("Error: failed to remove {repo}:{}: {err}", e.branch);
-                continue;
-            }
-            let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
-            println!("Removed worktree: {repo}:{}", e.branch);
-            println!("Deleted branch: {}", e.branch);
-        } else {
-            if let Err(err) = wm.remove(&path, args.force) {
-                let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
-                eprintln!("Error: failed to remove {repo}:{}: {err}", e.branch);
-                continue;
-            }
-            let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
-            println!("Removed worktree: {repo}:{}", e.branch);
+        let repo = e.repository.clone().unwrap_or_else(|| "unknown".to_owned());
+        let label = format!("{repo}:{}", e.branch);
+        if let Err(err) = remove_worktree_with_hooks(&wm, &path, &e.branch, &label, args) {
+            eprintln!("Error: failed to remove {label}: {err}");
         }
     }
 
     Ok(())
 }
 
+/// Removes one worktree (and optionally its branch), running the
+/// `pre_remove` hooks first and the `post_remove` hooks afterwards. Hooks only
+/// run once git would accept the removal; a failing `pre_remove` hook aborts
+/// it.
+fn remove_worktree_with_hooks(
+    wm: &WorktreeManager,
+    path: &Path,
+    branch: &str,
+    label: &str,
+    args: &RemoveArgs,
+) -> anyhow::Result<()> {
+    let repo_root = wm.main_root();
+    if !args.no_hooks {
+        wm.check_removable(path, args.force)?;
+        wm.run_hooks(
+            HookKind::PreRemove,
+            branch,
+            path,
+            &repo_root,
+            HookOutput::Inherit,
+        )?;
+    }
+
+    if args.delete_branch {
+        let deleted =
+            wm.remove_with_branch(path, branch, args.force, true, args.force_delete_branch)?;
+        println!("Removed worktree: {label}");
+        if deleted {
+            println!("Deleted branch: {branch}");
+        }
+    } else {
+        wm.remove(path, args.force)?;
+        println!("Removed worktree: {label}");
+    }
+
+    if !args.no_hooks
+        && let Err(e) = wm.run_hooks(
+            HookKind::PostRemove,
+            branch,
+            path,
+            &repo_root,
+            HookOutput::Inherit,
+        )
+    {
+        eprintln!("Error: {e}");
+    }
+    Ok(())
+}
+
 fn pick_many_worktrees(title: &str, worktrees: &[Worktree]) -> anyhow::Result<Vec<Worktree>> {
     if !tui::is_tty() {
         anyhow::bail!("interactive selection requires a TTY");
@@ -2863,10 +3857,10 @@ fn pick_many_worktrees(title: &str, worktrees: &[Worktree]) -> anyhow::Result<Ve
     let items: Vec<PickerItem> = worktrees
         .iter()
         .map(|wt| PickerItem {
-            title: format!("{} ({})", wt.branch, wt.path),
+            title: format!("{} ({})", wt.display_branch(), wt.path),
             preview: format!(
                 "Branch: {}\nPath: {}\nCommit: {}\nCreated: {}",
-                wt.branch,
+                wt.display_branch(),
                 wt.path,
                 truncate_hash(&wt.commit_hash),
                 wt.created_at
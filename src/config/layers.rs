@@ -0,0 +1,322 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use super::{Config, REPO_CONFIG_FILE, RepoOverride, key_type, normalize_key};
+use crate::core::naming::{RepoId, parse_origin_url};
+use crate::error::GwtuiError;
+
+/// The layer a resolved setting came from. Later layers win: defaults, the
+/// user config, matching `[[repos]]` entries in order, then `.gwtui.toml`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Origin {
+    Default,
+    User(PathBuf),
+    /// Index into [`Config::repos`].
+    Repos(usize),
+    RepoFile(PathBuf),
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Default => f.write_str("default"),
+            Self::User(path) | Self::RepoFile(path) => write!(f, "{}", path.display()),
+            Self::Repos(i) => write!(f, "[[repos]] #{}", i + 1),
+        }
+    }
+}
+
+/// The repository a config is resolved for.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RepoTarget {
+    /// Main worktree root, where `.gwtui.toml` lives.
+    pub root: PathBuf,
+    pub origin_url: Option<String>,
+    /// Parsed from `origin_url`; repositories without one are
+    /// `local/local/<root name>`, as in worktree paths.
+    pub id: RepoId,
+}
+
+impl RepoTarget {
+    #[must_use]
+    pub fn new(root: PathBuf, origin_url: Option<String>) -> Self {
+        let id = origin_url
+            .as_deref()
+            .and_then(parse_origin_url)
+            .unwrap_or_else(|| RepoId {
+                host: "local".to_owned(),
+                owner: "local".to_owned(),
+                repo: root
+                    .file_name()
+                    .and_then(|s| s.to_str())
+                    .unwrap_or("repo")
+                    .to_owned(),
+            });
+        Self {
+            root,
+            origin_url,
+            id,
+        }
+    }
+}
+
+/// A setting with its resolved value, as listed by `config list --origin`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedSetting {
+    pub key: String,
+    pub value: toml::Value,
+    pub origin: Origin,
+}
+
+impl RepoOverride {
+    fn has_matcher(&self) -> bool {
+        self.url.is_some() || self.host.is_some() || self.owner.is_some() || self.repo.is_some()
+    }
+
+    /// Whether every pattern of this entry matches `target`. An entry with a
+    /// `url` pattern never matches a repository without `origin`.
+    #[must_use]
+    pub fn matches(&self, target: &RepoTarget) -> bool {
+        let field = |pattern: &Option<String>, value: Option<&str>| match pattern {
+            None => true,
+            Some(p) => value.is_some_and(|v| wildcard_match(p, v)),
+        };
+        self.has_matcher()
+            && field(&self.url, target.origin_url.as_deref())
+            && field(&self.host, Some(&target.id.host))
+            && field(&self.owner, Some(&target.id.owner))
+            && field(&self.repo, Some(&target.id.repo))
+    }
+
+    fn settings(&self) -> toml::Table {
+        let mut table = toml::Table::new();
+        for (section, values) in [("worktree", &self.worktree), ("hooks", &self.hooks)] {
+            if !values.is_empty() {
+                table.insert(section.to_owned(), toml::Value::Table(values.clone()));
+            }
+        }
+        table
+    }
+}
+
+fn wildcard_match(pattern: &str, value: &str) -> bool {
+    let mut re = String::from("^");
+    for c in pattern.chars() {
+        match c {
+            '*' => re.push_str(".*"),
+            '?' => re.push('.'),
+            c => re.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+        }
+    }
+    re.push('$');
+    regex::Regex::new(&re).is_ok_and(|re| re.is_match(value))
+}
+
+/// `cfg` with the `[[repos]]` entries matching `target` and the target's
+/// `.gwtui.toml` layered on top, plus the layer each overridden key came
+/// from. `.gwtui.toml` may only set `[worktree]` here, and only
+/// `copy_files`/`link_files` unless the layers below enable
+/// `worktree.allow_repo_local`; its `[hooks]` are added by
+/// `hooks::commands_for` when `hooks.allow_repo_local` is enabled.
+pub fn resolve_for_repo(
+    cfg: &Config,
+    target: &RepoTarget,
+) -> Result<(Config, BTreeMap<String, Origin>), GwtuiError> {
+    let mut layered = cfg.clone();
+    let mut origins = BTreeMap::new();
+    let mut value = None;
+    let mut push = |layered: &mut Config, origin: Origin, table: toml::Table| {
+        let value = match &mut value {
+            Some(value) => value,
+            None => value
+                .insert(toml::Value::try_from(cfg).map_err(|e| GwtuiError::Config(e.to_string()))?),
+        };
+        *layered =
+            apply(value, &table).map_err(|e| GwtuiError::Config(format!("{origin}: {e}")))?;
+        for key in flatten_keys(&table) {
+            origins.insert(key, origin.clone());
+        }
+        Ok::<_, GwtuiError>(())
+    };
+    for (i, entry) in cfg.repos.iter().enumerate() {
+        if entry.matches(target) {
+            push(&mut layered, Origin::Repos(i), entry.settings())?;
+        }
+    }
+    let path = target.root.join(REPO_CONFIG_FILE);
+    if let Some(table) = read_repo_file(&path, layered.worktree.allow_repo_local)? {
+        push(&mut layered, Origin::RepoFile(path), table)?;
+    }
+
+    layered.validate_settings().map_err(|e| {
+        let id = &target.id;
+        GwtuiError::Config(format!(
+            "settings for {}/{}/{}: {}",
+            id.host,
+            id.owner,
+            id.repo,
+            message(e)
+        ))
+    })?;
+    Ok((layered, origins))
+}
+
+/// The `[worktree]` settings a repository's `.gwtui.toml` may apply: all of
+/// them when `allow_all`, otherwise only `copy_files` and `link_files`. The
+/// file can never enable `worktree.allow_repo_local` itself.
+fn read_repo_file(path: &Path, allow_all: bool) -> Result<Option<toml::Table>, GwtuiError> {
+    let raw = match std::fs::read_to_string(path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => {
+            return Err(GwtuiError::IoPath {
+                path: path.to_path_buf(),
+                source: e,
+            });
+        }
+    };
+    let mut doc: toml::Table =
+        toml::from_str(&raw).map_err(|e| GwtuiError::Config(format!("{}: {e}", path.display())))?;
+    if let Some(key) = doc
+        .keys()
+        .find(|k| !matches!(k.as_str(), "worktree" | "hooks"))
+    {
+        return Err(GwtuiError::Config(format!(
+            "{}: unexpected `{key}`; only [worktree] and [hooks] are read from {REPO_CONFIG_FILE}",
+            path.display()
+        )));
+    }
+    let Some(toml::Value::Table(mut worktree)) = doc.remove("worktree") else {
+        return Ok(None);
+    };
+    worktree.retain(|key, _| {
+        key != "allow_repo_local" && (allow_all || matches!(key, "copy_files" | "link_files"))
+    });
+    Ok((!worktree.is_empty())
+        .then(|| toml::Table::from_iter([("worktree".to_owned(), toml::Value::Table(worktree))])))
+}
+
+/// Checks that `[[repos]]` entry `i` can match something and only sets known
+/// `worktree`/`hooks` keys to values of the right type.
+pub(super) fn validate_entry(
+    cfg: &Config,
+    i: usize,
+    entry: &RepoOverride,
+) -> Result<(), GwtuiError> {
+    let origin = Origin::Repos(i);
+    if !entry.has_matcher() {
+        return Err(GwtuiError::Config(format!(
+            "{origin}: set at least one of url, host, owner or repo"
+        )));
+    }
+    let mut value = toml::Value::try_from(cfg).map_err(|e| GwtuiError::Config(e.to_string()))?;
+    apply(&mut value, &entry.settings())
+        .and_then(|layered| layered.validate_settings().map_err(message))
+        .map_err(|e| GwtuiError::Config(format!("{origin}: {e}")))
+}
+
+fn message(e: GwtuiError) -> String {
+    match e {
+        GwtuiError::Config(msg) => msg,
+        e => e.to_string(),
+    }
+}
+
+/// Writes the settings in `table` into `value` (a serialized [`Config`]) and
+/// deserializes the result. `worktree.sanitize` entries are added to the
+/// existing mappings; every other key replaces its value.
+fn apply(value: &mut toml::Value, table: &toml::Table) -> Result<Config, String> {
+    let mut leaves = Vec::new();
+    let mut unknown = Vec::new();
+    flatten(table, "", &mut leaves, &mut unknown);
+    if let Some(key) = unknown.first() {
+        return Err(format!("unknown setting `{key}`"));
+    }
+    for (path, leaf) in leaves {
+        let mut cur = &mut *value;
+        for seg in &path {
+            let Some(table) = cur.as_table_mut() else {
+                return Err(format!("`{}` is not a table", path.join(".")));
+            };
+            cur = table
+                .entry(seg.clone())
+                .or_insert_with(|| toml::Value::Table(toml::Table::new()));
+        }
+        *cur = leaf;
+    }
+    value
+        .clone()
+        .try_into()
+        .map_err(|e: toml::de::Error| e.to_string())
+}
+
+fn flatten_keys(table: &toml::Table) -> Vec<String> {
+    let mut leaves = Vec::new();
+    flatten(table, "", &mut leaves, &mut Vec::new());
+    leaves.into_iter().map(|(path, _)| path.join(".")).collect()
+}
+
+/// Collects the settings in `table` as key paths with aliases normalised.
+/// Leaves that are not known settings are also pushed to `unknown`.
+fn flatten(
+    table: &toml::Table,
+    prefix: &str,
+    out: &mut Vec<(Vec<String>, toml::Value)>,
+    unknown: &mut Vec<String>,
+) {
+    for (k, v) in table {
+        let key = normalize_key(&format!("{prefix}{k}"));
+        match v {
+            toml::Value::Table(map) if key == "worktree.sanitize" => {
+                for (from, to) in map {
+                    let path = ["worktree", "sanitize", from].map(str::to_owned);
+                    out.push((path.to_vec(), to.clone()));
+                }
+            }
+            toml::Value::Table(map) => flatten(map, &format!("{key}."), out, unknown),
+            _ => {
+                if key_type(&key).is_none() {
+                    unknown.push(key.clone());
+                }
+                out.push((key.split('.').map(str::to_owned).collect(), v.clone()));
+            }
+        }
+    }
+}
+
+/// Every setting of `cfg` resolved for `target`, attributing values set in
+/// `user` (the raw user config at `user_path`) to that file. `[[repos]]`
+/// entries themselves are not listed.
+pub(super) fn list_with_origins(
+    cfg: &Config,
+    user: &toml::Table,
+    user_path: &Path,
+    target: Option<&RepoTarget>,
+) -> Result<Vec<ResolvedSetting>, GwtuiError> {
+    let (resolved, overridden) = match target {
+        Some(target) => resolve_for_repo(cfg, target)?,
+        None => (cfg.clone(), BTreeMap::new()),
+    };
+    let user_keys: BTreeSet<String> = flatten_keys(user).into_iter().collect();
+
+    let mut table =
+        toml::Table::try_from(&resolved).map_err(|e| GwtuiError::Config(e.to_string()))?;
+    table.remove("repos");
+    let mut leaves = Vec::new();
+    flatten(&table, "", &mut leaves, &mut Vec::new());
+    Ok(leaves
+        .into_iter()
+        .map(|(path, value)| {
+            let key = path.join(".");
+            let origin = match overridden.get(&key) {
+                Some(origin) => origin.clone(),
+                None if user_keys.contains(&key) => Origin::User(user_path.to_path_buf()),
+                None => Origin::Default,
+            };
+            ResolvedSetting { key, value, origin }
+        })
+        .collect())
+}
@@ -7,8 +7,14 @@ use anyhow::Context as _;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 
+use crate::core::glob::GlobSet;
+use crate::core::naming;
 use crate::error::GwtuiError;
 
+mod layers;
+
+pub use layers::{Origin, RepoTarget, ResolvedSetting, resolve_for_repo};
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 #[serde(default)]
 pub struct Config {
@@ -18,6 +24,10 @@ pub struct Config {
     pub mux: MuxConfig,
     pub status: StatusConfig,
     pub tasks: TasksConfig,
+    pub hooks: HooksConfig,
+    /// Settings for particular repositories; see [`RepoOverride`].
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub repos: Vec<RepoOverride>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -30,6 +40,16 @@ pub struct WorktreeConfig {
     pub naming_template: String,
     #[serde(alias = "sanitize_chars")]
     pub sanitize: BTreeMap<String, String>,
+    /// Globs of untracked/ignored files copied from the main worktree on `add`.
+    pub copy_files: Vec<String>,
+    /// Like `copy_files`, but symlinked instead of copied.
+    pub link_files: Vec<String>,
+    /// Remote `add --pr` / `--mr` fetches reviews from.
+    pub review_remote: String,
+    /// Let a repository's `.gwtui.toml` set any `[worktree]` key, not just
+    /// `copy_files` and `link_files`. Off by default because that file comes
+    /// from whoever controls the repository.
+    pub allow_repo_local: bool,
 }
 
 impl Default for WorktreeConfig {
@@ -43,6 +63,10 @@ impl Default for WorktreeConfig {
             auto_mkdir: true,
             naming_template: "{{host}}/{{owner}}/{{repo}}/{{branch}}".to_owned(),
             sanitize,
+            copy_files: Vec::new(),
+            link_files: Vec::new(),
+            review_remote: "origin".to_owned(),
+            allow_repo_local: false,
         }
     }
 }
@@ -52,8 +76,20 @@ impl Default for WorktreeConfig {
 pub struct DiscoveryConfig {
     pub mode: DiscoveryMode,
     pub global_scan_depth: usize,
+    /// How long the discovery index is trusted before the roots are walked
+    /// again (incrementally, by directory mtime).
     pub cache_ttl_seconds: u64,
     pub dedupe_by_main_repo: bool,
+    /// Directories walked for worktrees in addition to `worktree.base_dir`.
+    pub roots: Vec<String>,
+    /// Main repositories whose `git worktree list` is merged into global
+    /// discovery wherever their worktrees live; maintained by `gwtui repo`.
+    pub repositories: Vec<String>,
+    /// Globs for directories global discovery does not descend into, matched
+    /// against the directory name and its path below the root.
+    pub ignore: Vec<String>,
+    /// Threads used to walk the roots.
+    pub concurrency: usize,
 }
 
 impl Default for DiscoveryConfig {
@@ -63,6 +99,14 @@ impl Default for DiscoveryConfig {
             global_scan_depth: 6,
             cache_ttl_seconds: 3,
             dedupe_by_main_repo: true,
+            roots: Vec::new(),
+            repositories: Vec::new(),
+            ignore: vec![
+                ".*".to_owned(),
+                "node_modules".to_owned(),
+                "target".to_owned(),
+            ],
+            concurrency: 8,
         }
     }
 }
@@ -125,8 +169,37 @@ pub enum MuxBackend {
 pub struct StatusConfig {
     pub refresh_interval_ms: u64,
     pub concurrency: usize,
+    /// Minimum time between `git fetch` runs for the same repository.
+    pub fetch_interval_seconds: u64,
     pub default_sort: String,
     pub default_filter: String,
+    /// Refresh the status views on filesystem changes instead of polling
+    /// every `refresh_interval_ms`.
+    pub watch: bool,
+    /// How "last activity" is determined; `exhaustive` stats every file.
+    pub activity: ActivityMode,
+    /// Where the activity cache and discovery index are kept; empty disables
+    /// caching.
+    pub cache_dir: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityMode {
+    #[default]
+    Fast,
+    Exhaustive,
+}
+
+impl StatusConfig {
+    /// Expanded `cache_dir`, or `None` when caching is disabled.
+    #[must_use]
+    pub fn cache_path(&self) -> Option<PathBuf> {
+        if self.cache_dir.trim().is_empty() {
+            return None;
+        }
+        expand_path(&self.cache_dir).ok()
+    }
 }
 
 impl Default for StatusConfig {
@@ -134,8 +207,12 @@ impl Default for StatusConfig {
         Self {
             refresh_interval_ms: 2000,
             concurrency: 8,
+            fetch_interval_seconds: 300,
             default_sort: "activity".to_owned(),
             default_filter: "all".to_owned(),
+            watch: true,
+            activity: ActivityMode::Fast,
+            cache_dir: "~/.cache/gwtui".to_owned(),
         }
     }
 }
@@ -174,6 +251,69 @@ impl Default for TasksConfig {
     }
 }
 
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct HooksConfig {
+    pub post_add: Vec<String>,
+    pub pre_remove: Vec<String>,
+    pub post_remove: Vec<String>,
+    /// Also run hooks from a repository's own `.gwtui.toml`. Off by default
+    /// because that file comes from whoever controls the repository.
+    pub allow_repo_local: bool,
+}
+
+/// A `[[repos]]` entry: `worktree` and `hooks` settings layered over the
+/// rest of the config for repositories matching every pattern given. `*` in
+/// a pattern matches any run of characters, `?` a single one.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RepoOverride {
+    /// Matched against the whole `origin` URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub owner: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub worktree: toml::Table,
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub hooks: toml::Table,
+}
+
+impl RepoOverride {
+    /// The `worktree.base_dir` this entry sets, if any.
+    #[must_use]
+    pub fn base_dir(&self) -> Option<&str> {
+        self.worktree
+            .get("base_dir")
+            .or_else(|| self.worktree.get("basedir"))
+            .and_then(toml::Value::as_str)
+    }
+}
+
+/// Hooks read from `.gwtui.toml` in the main worktree root. Its `[worktree]`
+/// table is layered over the user config by [`resolve_for_repo`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct RepoConfig {
+    pub hooks: HooksConfig,
+}
+
+pub const REPO_CONFIG_FILE: &str = ".gwtui.toml";
+
+pub fn load_repo_config(repo_root: &Path) -> Result<RepoConfig, GwtuiError> {
+    let path = repo_root.join(REPO_CONFIG_FILE);
+    let raw = match std::fs::read_to_string(&path) {
+        Ok(raw) => raw,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(RepoConfig::default()),
+        Err(e) => return Err(GwtuiError::IoPath { path, source: e }),
+    };
+    toml::from_str(&raw).map_err(|e| GwtuiError::Config(format!("{}: {e}", path.display())))
+}
+
 #[derive(Debug, Clone)]
 pub struct ConfigPaths {
     pub config_file: PathBuf,
@@ -272,11 +412,30 @@ pub fn load() -> anyhow::Result<(Config, toml_edit::DocumentMut, ConfigPaths)> {
     Ok((cfg, doc, paths))
 }
 
-pub fn list_resolved_toml() -> anyhow::Result<String> {
-    let (cfg, _doc, _paths) = load()?;
+/// The config as TOML, resolved for `target` when given.
+pub fn list_resolved_toml(target: Option<&RepoTarget>) -> anyhow::Result<String> {
+    let (mut cfg, _doc, _paths) = load()?;
+    if let Some(target) = target {
+        cfg = resolve_for_repo(&cfg, target)?.0;
+    }
     Ok(toml::to_string_pretty(&cfg)?)
 }
 
+/// Every setting resolved for `target` when given, with the layer it came from.
+pub fn list_with_origins(target: Option<&RepoTarget>) -> anyhow::Result<Vec<ResolvedSetting>> {
+    let (cfg, doc, paths) = load()?;
+    let user = doc
+        .to_string()
+        .parse::<toml::Table>()
+        .with_context(|| format!("failed to parse TOML in {}", paths.config_file.display()))?;
+    Ok(layers::list_with_origins(
+        &cfg,
+        &user,
+        &paths.config_file,
+        target,
+    )?)
+}
+
 pub fn get_value_string(key: &str) -> anyhow::Result<Option<String>> {
     let paths = default_paths()?;
     get_value_string_at_path(&paths.config_file, key)
@@ -321,7 +480,7 @@ pub fn set_value_string_at_path(path: &Path, key: &str, value: &str) -> anyhow::
     let (mut doc, cfg) = load_from_file(path)?;
     cfg.validate()?;
 
-    let (norm_key, value_item) = normalize_key_and_parse_value(key, value, &cfg)?;
+    let (norm_key, value_item) = normalize_key_and_parse_value(key, value)?;
     apply_set(&mut doc, &norm_key, value_item)?;
 
     // Validate by re-parsing the updated doc into a Config.
@@ -342,6 +501,15 @@ pub fn set_value_string_at_path(path: &Path, key: &str, value: &str) -> anyhow::
 
 impl Config {
     pub fn validate(&self) -> Result<(), GwtuiError> {
+        self.validate_settings()?;
+        for (i, entry) in self.repos.iter().enumerate() {
+            layers::validate_entry(self, i, entry)?;
+        }
+        Ok(())
+    }
+
+    /// Everything [`Config::validate`] checks except the `[[repos]]` entries.
+    fn validate_settings(&self) -> Result<(), GwtuiError> {
         if self.worktree.base_dir.trim().is_empty() {
             return Err(GwtuiError::Config(
                 "worktree.base_dir must not be empty".to_owned(),
@@ -352,6 +520,11 @@ impl Config {
                 "discovery.global_scan_depth must be >= 1".to_owned(),
             ));
         }
+        if self.discovery.concurrency == 0 {
+            return Err(GwtuiError::Config(
+                "discovery.concurrency must be >= 1".to_owned(),
+            ));
+        }
         if self.status.concurrency == 0 {
             return Err(GwtuiError::Config(
                 "status.concurrency must be >= 1".to_owned(),
@@ -367,6 +540,15 @@ impl Config {
                 "tasks.max_parallel must be >= 1".to_owned(),
             ));
         }
+        naming::template_variables(&self.worktree.naming_template)
+            .map_err(|e| GwtuiError::Config(format!("worktree.naming_template: {e}")))?;
+        for (key, globs) in [
+            ("worktree.copy_files", &self.worktree.copy_files),
+            ("worktree.link_files", &self.worktree.link_files),
+            ("discovery.ignore", &self.discovery.ignore),
+        ] {
+            GlobSet::new(globs).map_err(|e| GwtuiError::Config(format!("{key}: {e}")))?;
+        }
         Ok(())
     }
 }
@@ -376,6 +558,7 @@ enum KeyType {
     Bool,
     Int,
     String,
+    StringList,
     Enum(&'static [&'static str]),
 }
 
@@ -383,6 +566,8 @@ fn normalize_key(key: &str) -> String {
     // gwq compatibility aliases
     match key {
         "worktree.basedir" => "worktree.base_dir",
+        "worktree.template" => "worktree.naming_template",
+        "worktree.sanitize_chars" => "worktree.sanitize",
         "naming.template" => "worktree.naming_template",
         "naming.sanitize_chars" => "worktree.sanitize",
         "finder.preview" => "ui.picker_preview",
@@ -405,7 +590,6 @@ fn normalize_key(key: &str) -> String {
 fn normalize_key_and_parse_value(
     key: &str,
     value: &str,
-    cfg: &Config,
 ) -> anyhow::Result<(String, toml_edit::Item)> {
     // Special-case gwq tmux.enabled -> gwtui mux.backend
     if key == "tmux.enabled" {
@@ -425,8 +609,7 @@ fn normalize_key_and_parse_value(
         }
         .into());
     }
-    let key_type =
-        key_type(&norm, cfg).ok_or_else(|| GwtuiError::InvalidConfigKey(key.to_owned()))?;
+    let key_type = key_type(&norm).ok_or_else(|| GwtuiError::InvalidConfigKey(key.to_owned()))?;
     let item =
         match key_type {
             KeyType::Bool => toml_edit::value(parse_bool(value).map_err(|msg| {
@@ -442,6 +625,12 @@ fn normalize_key_and_parse_value(
                 }
             })?),
             KeyType::String => toml_edit::value(value),
+            KeyType::StringList => toml_edit::value(parse_string_list(value).map_err(|msg| {
+                GwtuiError::InvalidConfigValue {
+                    key: key.to_owned(),
+                    msg,
+                }
+            })?),
             KeyType::Enum(allowed) => {
                 let v = value.trim();
                 if !allowed.contains(&v) {
@@ -458,7 +647,7 @@ fn normalize_key_and_parse_value(
     Ok((norm, item))
 }
 
-fn key_type(key: &str, _cfg: &Config) -> Option<KeyType> {
+fn key_type(key: &str) -> Option<KeyType> {
     // Dynamic keys (maps)
     if key == "worktree.sanitize" || key.starts_with("worktree.sanitize.") {
         return Some(KeyType::String);
@@ -467,9 +656,11 @@ fn key_type(key: &str, _cfg: &Config) -> Option<KeyType> {
     Some(match key {
         "worktree.base_dir"
         | "worktree.naming_template"
+        | "worktree.review_remote"
         | "mux.zellij_command"
         | "status.default_sort"
         | "status.default_filter"
+        | "status.cache_dir"
         | "tasks.queue_dir"
         | "tasks.runner"
         | "tasks.codex_executable"
@@ -478,24 +669,39 @@ fn key_type(key: &str, _cfg: &Config) -> Option<KeyType> {
         | "tasks.claude_timeout" => KeyType::String,
 
         "worktree.auto_mkdir"
+        | "worktree.allow_repo_local"
         | "discovery.dedupe_by_main_repo"
         | "ui.icons"
         | "ui.tilde_home"
         | "ui.picker_preview"
         | "mux.require_session_for_run"
         | "tasks.enabled"
-        | "tasks.auto_cleanup" => KeyType::Bool,
+        | "tasks.auto_cleanup"
+        | "hooks.allow_repo_local"
+        | "status.watch" => KeyType::Bool,
 
         "discovery.global_scan_depth"
         | "discovery.cache_ttl_seconds"
+        | "discovery.concurrency"
         | "ui.picker_preview_lines"
         | "status.refresh_interval_ms"
         | "status.concurrency"
+        | "status.fetch_interval_seconds"
         | "tasks.log_retention_days"
         | "tasks.max_log_size_mb"
         | "tasks.max_parallel" => KeyType::Int,
 
+        "worktree.copy_files"
+        | "worktree.link_files"
+        | "discovery.roots"
+        | "discovery.repositories"
+        | "discovery.ignore"
+        | "hooks.post_add"
+        | "hooks.pre_remove"
+        | "hooks.post_remove" => KeyType::StringList,
+
         "discovery.mode" => KeyType::Enum(&["auto", "local", "global"]),
+        "status.activity" => KeyType::Enum(&["fast", "exhaustive"]),
         "mux.backend" => KeyType::Enum(&["zellij", "none"]),
 
         _ => return None,
@@ -510,6 +716,29 @@ fn parse_bool(s: &str) -> Result<bool, String> {
     }
 }
 
+/// Accepts a TOML array of strings (`["npm ci", "direnv allow"]`) or a single
+/// bare string, which becomes a one-element list.
+fn parse_string_list(s: &str) -> Result<toml_edit::Array, String> {
+    let s = s.trim();
+    if !s.starts_with('[') {
+        let mut arr = toml_edit::Array::new();
+        if !s.is_empty() {
+            arr.push(s);
+        }
+        return Ok(arr);
+    }
+    let v = s
+        .parse::<toml_edit::Value>()
+        .map_err(|e| format!("expected a TOML array of strings: {e}"))?;
+    let Some(arr) = v.as_array() else {
+        return Err("expected a TOML array of strings".to_owned());
+    };
+    if arr.iter().any(|v| !v.is_str()) {
+        return Err("expected a TOML array of strings".to_owned());
+    }
+    Ok(arr.clone())
+}
+
 fn parse_int(s: &str) -> Result<i64, String> {
     s.trim()
         .parse::<i64>()
@@ -571,6 +800,12 @@ mod tests {
         let mut cfg = Config::default();
         cfg.tasks.max_parallel = 0;
         assert!(cfg.validate().is_err());
+
+        let mut cfg = Config::default();
+        cfg.worktree.naming_template = "{{repo}}/{{branch|kebab}}".to_owned();
+        let err = cfg.validate().unwrap_err().to_string();
+        assert!(err.contains("worktree.naming_template"), "{err}");
+        assert!(err.contains("unknown filter `kebab`"), "{err}");
     }
 
     #[test]
@@ -621,6 +856,163 @@ mod tests {
         );
         assert_eq!(cfg.mux.backend, MuxBackend::None);
     }
+
+    #[test]
+    fn repo_overrides_layer_over_user_config() {
+        let cfg: Config = toml::from_str(
+            r#"
+[worktree]
+base_dir = "~/wt"
+copy_files = [".env"]
+
+[[repos]]
+owner = "acme"
+worktree = { base_dir = "~/work/acme", sanitize = { "." = "_" } }
+hooks = { post_add = ["npm ci"] }
+
+[[repos]]
+url = "*gitlab.com*"
+worktree = { base_dir = "~/gitlab" }
+
+[[repos]]
+host = "github.com"
+repo = "a?p"
+worktree = { template = "{{repo}}/{{branch_leaf}}" }
+"#,
+        )
+        .unwrap();
+        cfg.validate().unwrap();
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "[worktree]\ncopy_files = [\".env.local\"]\n\n[hooks]\npost_add = [\"make\"]\n",
+        )
+        .unwrap();
+        let target = RepoTarget::new(
+            dir.path().to_path_buf(),
+            Some("git@github.com:acme/app.git".to_owned()),
+        );
+        let (layered, origins) = resolve_for_repo(&cfg, &target).unwrap();
+        assert_eq!(layered.worktree.base_dir, "~/work/acme");
+        assert_eq!(layered.worktree.naming_template, "{{repo}}/{{branch_leaf}}");
+        assert_eq!(layered.worktree.copy_files, [".env.local"]);
+        assert_eq!(
+            layered.worktree.sanitize.get(".").map(String::as_str),
+            Some("_")
+        );
+        assert_eq!(
+            layered.worktree.sanitize.get("/").map(String::as_str),
+            Some("-")
+        );
+        assert_eq!(layered.hooks.post_add, ["npm ci"]);
+        assert_eq!(origins["worktree.base_dir"], Origin::Repos(0));
+        assert_eq!(origins["worktree.naming_template"], Origin::Repos(2));
+        assert_eq!(
+            origins["worktree.copy_files"],
+            Origin::RepoFile(dir.path().join(REPO_CONFIG_FILE))
+        );
+        // `.gwtui.toml` hooks are appended by `hooks::commands_for` instead.
+        assert_eq!(origins["hooks.post_add"], Origin::Repos(0));
+
+        let other = RepoTarget::new(
+            PathBuf::from("/nonexistent/other"),
+            Some("https://gitlab.com/acme-corp/other.git".to_owned()),
+        );
+        let (layered, _) = resolve_for_repo(&cfg, &other).unwrap();
+        assert_eq!(layered.worktree.base_dir, "~/gitlab");
+        assert_eq!(layered.worktree.copy_files, [".env"]);
+
+        let user: toml::Table = toml::from_str("[worktree]\nbasedir = \"~/wt\"\n").unwrap();
+        let user_path = Path::new("/home/me/.config/gwtui/config.toml");
+        let listed = layers::list_with_origins(&cfg, &user, user_path, Some(&other)).unwrap();
+        let origin_of = |key: &str| {
+            listed
+                .iter()
+                .find(|s| s.key == key)
+                .map(|s| s.origin.clone())
+                .unwrap()
+        };
+        assert_eq!(origin_of("worktree.base_dir"), Origin::Repos(1));
+        assert_eq!(origin_of("ui.icons"), Origin::Default);
+        let listed = layers::list_with_origins(&cfg, &user, user_path, None).unwrap();
+        let base_dir = listed
+            .iter()
+            .find(|s| s.key == "worktree.base_dir")
+            .unwrap();
+        assert_eq!(base_dir.origin, Origin::User(user_path.to_path_buf()));
+        assert!(listed.iter().all(|s| !s.key.starts_with("repos")));
+    }
+
+    #[test]
+    fn repo_file_needs_opt_in_beyond_file_lists() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(
+            dir.path().join(REPO_CONFIG_FILE),
+            "[worktree]\nbase_dir = \"/tmp/evil\"\nallow_repo_local = true\nlink_files = [\"node_modules\"]\n",
+        )
+        .unwrap();
+        let target = RepoTarget::new(dir.path().to_path_buf(), None);
+
+        let (layered, origins) = resolve_for_repo(&Config::default(), &target).unwrap();
+        assert_eq!(layered.worktree.base_dir, "~/worktrees");
+        assert!(!layered.worktree.allow_repo_local);
+        assert_eq!(layered.worktree.link_files, ["node_modules"]);
+        assert_eq!(origins.keys().collect::<Vec<_>>(), ["worktree.link_files"]);
+
+        let mut cfg = Config::default();
+        cfg.worktree.allow_repo_local = true;
+        let (layered, _) = resolve_for_repo(&cfg, &target).unwrap();
+        assert_eq!(layered.worktree.base_dir, "/tmp/evil");
+    }
+
+    #[test]
+    fn repo_overrides_are_validated() {
+        let err = |raw: &str| {
+            toml::from_str::<Config>(raw)
+                .unwrap()
+                .validate()
+                .unwrap_err()
+                .to_string()
+        };
+        let e = err("[[repos]]\nworktree = { base_dir = \"~/x\" }\n");
+        assert!(
+            e.contains("at least one of url, host, owner or repo"),
+            "{e}"
+        );
+        let e = err("[[repos]]\nowner = \"acme\"\nworktree = { colour = \"red\" }\n");
+        assert!(
+            e.contains("[[repos]] #1: unknown setting `worktree.colour`"),
+            "{e}"
+        );
+        let e = err("[[repos]]\nowner = \"acme\"\nworktree = { auto_mkdir = \"yes\" }\n");
+        assert!(e.contains("[[repos]] #1"), "{e}");
+        let e = err("[[repos]]\nowner = \"acme\"\nworktree = { naming_template = \"{{nope}}\" }\n");
+        assert!(e.contains("worktree.naming_template"), "{e}");
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        std::fs::write(dir.path().join(REPO_CONFIG_FILE), "[ui]\nicons = false\n").unwrap();
+        let target = RepoTarget::new(dir.path().to_path_buf(), None);
+        let e = resolve_for_repo(&Config::default(), &target)
+            .unwrap_err()
+            .to_string();
+        assert!(e.contains("only [worktree] and [hooks]"), "{e}");
+    }
+
+    #[test]
+    fn config_set_string_lists() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("config.toml");
+
+        set_value_string_at_path(&path, "hooks.post_add", r#"["npm ci", "direnv allow"]"#).unwrap();
+        set_value_string_at_path(&path, "hooks.pre_remove", "docker compose down").unwrap();
+        assert!(set_value_string_at_path(&path, "hooks.post_remove", "[1, 2]").is_err());
+
+        let (_doc, cfg) = load_from_file(&path).unwrap();
+        assert_eq!(cfg.hooks.post_add, vec!["npm ci", "direnv allow"]);
+        assert_eq!(cfg.hooks.pre_remove, vec!["docker compose down"]);
+        assert!(cfg.hooks.post_remove.is_empty());
+    }
 }
 
 fn format_value_for_stdout(v: serde_json::Value) -> String {
@@ -1,13 +1,18 @@
 #![forbid(unsafe_code)]
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeSet, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::{Condvar, Mutex, PoisonError};
+use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 
 use crate::config;
 use crate::core::git::Git;
+use crate::core::glob::GlobSet;
 use crate::core::naming::parse_origin_url;
+use crate::core::status;
+use crate::core::worktree::WorktreeManager;
 use crate::error::GwtuiError;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -18,31 +23,655 @@ pub struct GlobalWorktreeEntry {
     pub path: String,
     pub commit_hash: String,
     pub is_main: bool,
+    #[serde(default)]
+    pub is_locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    /// Main checkout (or bare repository) the worktree belongs to, resolved
+    /// through its common git dir.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub main_repo: Option<String>,
+}
+
+/// A checkout whose `.git` file points at a git dir that no longer exists,
+/// typically because the main repository was moved or deleted.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OrphanedWorktree {
+    pub path: String,
+    pub gitdir: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DiscoveryReport {
+    pub worktrees: Vec<GlobalWorktreeEntry>,
+    pub orphans: Vec<OrphanedWorktree>,
+    /// Main checkouts (directories with a `.git` directory) seen during the walk.
+    pub main_repos: Vec<PathBuf>,
 }
 
 pub fn discover_global_worktrees(
-    base_dir: &str,
-    scan_depth: usize,
+    cfg: &config::Config,
 ) -> Result<Vec<GlobalWorktreeEntry>, GwtuiError> {
-    if base_dir.trim().is_empty() {
-        return Err(GwtuiError::Config(
-            "worktree.base_dir must not be empty".to_owned(),
-        ));
+    Ok(discover_global(cfg)?.worktrees)
+}
+
+/// Walks the discovery roots without the index or the repository registry.
+pub fn discover_global(cfg: &config::Config) -> Result<DiscoveryReport, GwtuiError> {
+    let walk = Walk::from_config(cfg)?;
+    let (mut report, _) = scan(&walk, &DiscoveryIndex::default(), false);
+    group_by_main_repo(&mut report);
+    Ok(report)
+}
+
+/// [`discover_global_worktrees`] backed by the discovery index.
+pub fn discover_global_worktrees_cached(
+    cfg: &config::Config,
+) -> Result<Vec<GlobalWorktreeEntry>, GwtuiError> {
+    Ok(discover_global_cached(cfg)?.worktrees)
+}
+
+/// Global discovery over `worktree.base_dir`, every `discovery.roots` entry
+/// and the worktrees of each repository in `discovery.repositories`.
+///
+/// Results are kept in an index in `status.cache_dir`. Within
+/// `discovery.cache_ttl_seconds` of the last walk the index is returned as
+/// is (minus worktrees that have since disappeared); after that the walk is
+/// repeated, but directories whose mtime did not change are not listed again
+/// and worktrees whose git dir did not change are not asked for their branch
+/// and commit again.
+pub fn discover_global_cached(cfg: &config::Config) -> Result<DiscoveryReport, GwtuiError> {
+    let walk = Walk::from_config(cfg)?;
+    let repos = registered_repositories(cfg);
+
+    let cache_dir = cfg.status.cache_path();
+    let mut index = cache_dir
+        .as_deref()
+        .map(DiscoveryIndex::load)
+        .unwrap_or_default();
+    if index.roots != walk.roots || index.scan_depth != walk.max_depth {
+        index = DiscoveryIndex::default();
     }
+    let ttl = Duration::from_secs(cfg.discovery.cache_ttl_seconds);
+    let fresh = index
+        .scanned_at
+        .and_then(|t| t.elapsed().ok())
+        .is_some_and(|age| age < ttl);
 
-    let base = config::expand_path(base_dir).map_err(|e| GwtuiError::Other(e.to_string()))?;
-    if !base.exists() {
-        return Ok(Vec::new());
+    let (mut report, mut next) = scan(&walk, &index, fresh);
+    let registered = scan_registry(&repos, &index, fresh, &mut next);
+    merge_entries(&mut report, registered);
+    if fresh {
+        report
+            .worktrees
+            .retain(|e| Path::new(&e.path).join(".git").exists());
+    } else if let Some(dir) = &cache_dir {
+        next.roots = walk.roots;
+        next.scan_depth = walk.max_depth;
+        next.scanned_at = Some(SystemTime::now());
+        next.save(dir);
     }
+    if cfg.discovery.dedupe_by_main_repo {
+        dedupe_by_main_repo(&mut report);
+    }
+    group_by_main_repo(&mut report);
+    Ok(report)
+}
+
+/// Expanded `discovery.repositories`, skipping entries that cannot be expanded.
+#[must_use]
+pub fn registered_repositories(cfg: &config::Config) -> Vec<PathBuf> {
+    cfg.discovery
+        .repositories
+        .iter()
+        .filter_map(|r| config::expand_path(r).ok())
+        .collect()
+}
 
+/// Lists the worktrees of each registered repository with `git worktree
+/// list`, reusing `old` when nothing in the repository's git dir changed.
+fn scan_registry(
+    repos: &[PathBuf],
+    old: &DiscoveryIndex,
+    trust: bool,
+    next: &mut DiscoveryIndex,
+) -> Vec<GlobalWorktreeEntry> {
     let mut out = Vec::new();
-    let mut visited = BTreeSet::new();
-    walk_dirs(&base, scan_depth, &mut visited, &mut |dir| {
-        if let Some(entry) = extract_worktree_info(dir) {
-            out.push(entry);
+    for repo in repos {
+        let key = repo.to_string_lossy().to_string();
+        let stamp = if trust { None } else { registry_stamp(repo) };
+        let record = match old.repos.get(&key) {
+            Some(rec) if trust || (stamp.is_some() && rec.stamp == stamp) => rec.clone(),
+            _ => RegistryRecord {
+                stamp,
+                entries: list_registered(repo),
+            },
+        };
+        out.extend(record.entries.iter().cloned());
+        next.repos.insert(key, record);
+    }
+    out
+}
+
+fn list_registered(repo: &Path) -> Vec<GlobalWorktreeEntry> {
+    let Some(git_dir) = status::worktree_git_dir(repo) else {
+        return Vec::new();
+    };
+    let git = Git::new(repo.to_path_buf());
+    let Ok(repository_url) = git.get_repository_url() else {
+        return Vec::new();
+    };
+    let repository = parse_origin_url(&repository_url).map(|id| id.repo);
+    let main_repo = main_repo_path(&git_dir).to_string_lossy().to_string();
+    let Ok(worktrees) = WorktreeManager::new(git, config::Config::default()).list() else {
+        return Vec::new();
+    };
+    worktrees
+        .into_iter()
+        .enumerate()
+        .filter(|(_, wt)| !wt.is_bare && !wt.is_prunable)
+        .map(|(i, wt)| GlobalWorktreeEntry {
+            repository_url: repository_url.clone(),
+            repository: repository.clone(),
+            branch: if wt.is_detached {
+                "HEAD".to_owned()
+            } else {
+                wt.branch
+            },
+            path: wt.path,
+            commit_hash: wt.commit_hash,
+            // `git worktree list` always starts with the main worktree.
+            is_main: i == 0,
+            is_locked: wt.is_locked,
+            lock_reason: wt.lock_reason,
+            main_repo: Some(main_repo.clone()),
+        })
+        .collect()
+}
+
+/// Newest mtime among the repository's git dir, its worktree registry and
+/// each worktree's admin dir and reflog, which covers adding, removing,
+/// locking and moving HEAD in any of its worktrees.
+fn registry_stamp(repo: &Path) -> Option<SystemTime> {
+    let common = status::common_git_dir(&status::worktree_git_dir(repo)?);
+    let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let mut dirs = vec![common.clone(), common.join("worktrees")];
+    if let Ok(entries) = std::fs::read_dir(common.join("worktrees")) {
+        dirs.extend(entries.flatten().map(|e| e.path()));
+    }
+    dirs.iter()
+        .flat_map(|d| [mtime(d), mtime(&d.join("logs/HEAD"))])
+        .max()
+        .flatten()
+}
+
+/// Adds registry entries for checkouts the walk did not already find.
+fn merge_entries(report: &mut DiscoveryReport, registered: Vec<GlobalWorktreeEntry>) {
+    let canonical = |p: &str| std::fs::canonicalize(p).unwrap_or_else(|_| PathBuf::from(p));
+    let mut seen: BTreeSet<PathBuf> = report
+        .worktrees
+        .iter()
+        .map(|e| canonical(&e.path))
+        .collect();
+    for entry in registered {
+        if seen.insert(canonical(&entry.path)) {
+            if entry.is_main {
+                report.main_repos.push(PathBuf::from(&entry.path));
+            }
+            report.worktrees.push(entry);
+        }
+    }
+}
+
+/// Orders worktrees by main repository, each main checkout first.
+fn group_by_main_repo(report: &mut DiscoveryReport) {
+    report.worktrees.sort_by(|a, b| {
+        (&a.main_repo, !a.is_main, &a.path).cmp(&(&b.main_repo, !b.is_main, &b.path))
+    });
+}
+
+/// Drops entries that reach the same checkout through a symlinked path and
+/// gives every worktree of a repository the main checkout's remote URL, so
+/// worktrees whose config points `origin` elsewhere still group together.
+fn dedupe_by_main_repo(report: &mut DiscoveryReport) {
+    let canonical = |p: &Path| std::fs::canonicalize(p).unwrap_or_else(|_| p.to_path_buf());
+
+    let mut seen = BTreeSet::new();
+    report
+        .worktrees
+        .retain(|e| seen.insert(canonical(Path::new(&e.path))));
+    let mut seen = BTreeSet::new();
+    report.main_repos.retain(|p| seen.insert(canonical(p)));
+
+    let mut identity: HashMap<String, (String, Option<String>)> = HashMap::new();
+    for e in &report.worktrees {
+        if let Some(main) = &e.main_repo {
+            let id = (e.repository_url.clone(), e.repository.clone());
+            if e.is_main {
+                identity.insert(main.clone(), id);
+            } else {
+                identity.entry(main.clone()).or_insert(id);
+            }
+        }
+    }
+    for e in &mut report.worktrees {
+        if let Some((url, repo)) = e.main_repo.as_ref().and_then(|m| identity.get(m)) {
+            e.repository_url.clone_from(url);
+            e.repository.clone_from(repo);
+        }
+    }
+}
+
+/// Removes the files written to `cache_dir` (the activity cache and the
+/// discovery index) and returns the ones that existed.
+pub fn clear_caches(cache_dir: &Path) -> Result<Vec<PathBuf>, GwtuiError> {
+    let mut removed = Vec::new();
+    for name in [DiscoveryIndex::FILE, status::ACTIVITY_CACHE_FILE] {
+        let path = cache_dir.join(name);
+        match std::fs::remove_file(&path) {
+            Ok(()) => removed.push(path),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(source) => return Err(GwtuiError::IoPath { path, source }),
+        }
+    }
+    Ok(removed)
+}
+
+/// Patterns read from a `.gwtuiignore` file at the top of a discovery root.
+const IGNORE_FILE: &str = ".gwtuiignore";
+
+/// The roots to walk and how.
+#[derive(Debug)]
+struct Walk {
+    /// `worktree.base_dir`, `discovery.roots` and the `[[repos]]` base dirs,
+    /// existing ones only. Base dirs from `.gwtui.toml` are not known before
+    /// the repository is; `discovery.repositories` covers those.
+    roots: Vec<PathBuf>,
+    max_depth: usize,
+    concurrency: usize,
+    /// `discovery.ignore`, applied below every root.
+    ignore: GlobSet,
+    /// Each root's `.gwtuiignore`.
+    root_ignores: Vec<(PathBuf, GlobSet)>,
+}
+
+impl Walk {
+    fn from_config(cfg: &config::Config) -> Result<Self, GwtuiError> {
+        if cfg.worktree.base_dir.trim().is_empty() {
+            return Err(GwtuiError::Config(
+                "worktree.base_dir must not be empty".to_owned(),
+            ));
+        }
+        let mut roots = Vec::new();
+        let repo_base_dirs = cfg.repos.iter().filter_map(config::RepoOverride::base_dir);
+        for root in std::iter::once(cfg.worktree.base_dir.as_str())
+            .chain(cfg.discovery.roots.iter().map(String::as_str))
+            .chain(repo_base_dirs)
+        {
+            roots.push(config::expand_path(root).map_err(|e| GwtuiError::Other(e.to_string()))?);
+        }
+        let mut seen = BTreeSet::new();
+        roots.retain(|r| r.is_dir() && seen.insert(r.clone()));
+
+        let ignore = GlobSet::new(&cfg.discovery.ignore)
+            .map_err(|e| GwtuiError::Config(format!("discovery.ignore: {e}")))?;
+        let mut root_ignores = Vec::new();
+        for root in &roots {
+            let path = root.join(IGNORE_FILE);
+            let Ok(raw) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+            let patterns: Vec<String> = raw
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                .map(str::to_owned)
+                .collect();
+            let set = GlobSet::new(&patterns)
+                .map_err(|e| GwtuiError::Config(format!("{}: {e}", path.display())))?;
+            root_ignores.push((root.clone(), set));
+        }
+
+        Ok(Self {
+            roots,
+            max_depth: cfg.discovery.global_scan_depth,
+            concurrency: cfg.discovery.concurrency.max(1),
+            ignore,
+            root_ignores,
+        })
+    }
+
+    /// Whether the walk stays out of `dir`. Patterns are matched against the
+    /// path below the root and against the directory name, so `target`
+    /// skips every `target/` while `work/tmp` skips only that one.
+    fn is_ignored(&self, dir: &Path) -> bool {
+        let Some(name) = dir.file_name().map(|n| n.to_string_lossy()) else {
+            return false;
+        };
+        if name == ".git" {
+            return true;
+        }
+        let matches = |set: &GlobSet, root: &Path| {
+            if set.is_match(&name) {
+                return true;
+            }
+            dir.strip_prefix(root).is_ok_and(|rel| {
+                let rel: Vec<_> = rel.iter().map(|c| c.to_string_lossy()).collect();
+                set.is_match(&rel.join("/"))
+            })
+        };
+        self.roots
+            .iter()
+            .any(|root| dir.starts_with(root) && matches(&self.ignore, root))
+            || self
+                .root_ignores
+                .iter()
+                .any(|(root, set)| dir.starts_with(root) && matches(set, root))
+    }
+}
+
+/// What the last walk of the discovery roots found in each directory, and
+/// the worktrees of each registered repository, keyed by path.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct DiscoveryIndex {
+    roots: Vec<PathBuf>,
+    scan_depth: usize,
+    scanned_at: Option<SystemTime>,
+    dirs: HashMap<String, IndexedDir>,
+    #[serde(default)]
+    repos: HashMap<String, RegistryRecord>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistryRecord {
+    stamp: Option<SystemTime>,
+    entries: Vec<GlobalWorktreeEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedDir {
+    mtime: SystemTime,
+    /// Subdirectories, before ignore patterns are applied; empty for checkouts.
+    subdirs: Vec<PathBuf>,
+    kind: IndexedKind,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum IndexedKind {
+    Plain,
+    Orphan(OrphanedWorktree),
+    /// A main checkout (`.git` directory) or linked worktree (`.git` file).
+    /// `entry` is missing for repositories without an `origin` remote.
+    Checkout {
+        main: bool,
+        entry: Option<GlobalWorktreeEntry>,
+        /// Newest mtime of the checkout's git dir and its `logs/HEAD`, which
+        /// change on checkout, commit and lock.
+        stamp: Option<SystemTime>,
+    },
+}
+
+impl DiscoveryIndex {
+    const FILE: &'static str = "discovery.json";
+
+    fn load(dir: &Path) -> Self {
+        std::fs::read(dir.join(Self::FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// Best effort, like the activity cache: a stale or missing index only
+    /// costs a full walk.
+    fn save(&self, dir: &Path) {
+        if std::fs::create_dir_all(dir).is_err() {
+            return;
+        }
+        let Ok(raw) = serde_json::to_vec(self) else {
+            return;
+        };
+        let tmp = dir.join(format!("{}.{}.tmp", Self::FILE, std::process::id()));
+        if std::fs::write(&tmp, raw).is_ok() {
+            let _ = std::fs::rename(&tmp, dir.join(Self::FILE));
+        }
+    }
+}
+
+/// Walks the roots on `walk.concurrency` threads, reusing `old` where it is
+/// still valid, or wherever it has a record when `trust` is set. The walk
+/// does not descend into checkouts or ignored directories. Main checkouts
+/// outside the roots that linked worktrees point to are visited too, without
+/// descending. Returns the report and the index of this walk.
+fn scan(walk: &Walk, old: &DiscoveryIndex, trust: bool) -> (DiscoveryReport, DiscoveryIndex) {
+    struct Queue {
+        pending: Vec<(PathBuf, usize)>,
+        visited: BTreeSet<PathBuf>,
+        in_flight: usize,
+        done: Vec<(PathBuf, IndexedDir)>,
+    }
+
+    let queue = Mutex::new(Queue {
+        pending: walk.roots.iter().map(|r| (r.clone(), 0)).collect(),
+        visited: walk.roots.iter().cloned().collect(),
+        in_flight: 0,
+        done: Vec::new(),
+    });
+    let wakeup = Condvar::new();
+    let worker = || {
+        loop {
+            let (dir, depth) = {
+                let mut q = queue.lock().unwrap_or_else(PoisonError::into_inner);
+                loop {
+                    if let Some(next) = q.pending.pop() {
+                        q.in_flight += 1;
+                        break next;
+                    }
+                    if q.in_flight == 0 {
+                        return;
+                    }
+                    q = wakeup.wait(q).unwrap_or_else(PoisonError::into_inner);
+                }
+            };
+            let record = lookup(&dir, old, trust);
+            let mut q = queue.lock().unwrap_or_else(PoisonError::into_inner);
+            if let Some(record) = record {
+                if depth < walk.max_depth {
+                    for sub in &record.subdirs {
+                        if !walk.is_ignored(sub) && q.visited.insert(sub.clone()) {
+                            q.pending.push((sub.clone(), depth + 1));
+                        }
+                    }
+                }
+                q.done.push((dir, record));
+            }
+            q.in_flight -= 1;
+            wakeup.notify_all();
+        }
+    };
+    std::thread::scope(|s| {
+        for _ in 0..walk.concurrency {
+            s.spawn(worker);
         }
     });
-    Ok(out)
+    let mut done = queue
+        .into_inner()
+        .unwrap_or_else(PoisonError::into_inner)
+        .done;
+    done.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let mut report = DiscoveryReport::default();
+    let mut next = DiscoveryIndex::default();
+    for (dir, record) in done {
+        add_to_report(&mut report, &dir, &record.kind);
+        next.dirs.insert(dir.to_string_lossy().to_string(), record);
+    }
+
+    let found: BTreeSet<&str> = report
+        .worktrees
+        .iter()
+        .filter(|e| e.is_main)
+        .filter_map(|e| e.main_repo.as_deref())
+        .collect();
+    let outside: BTreeSet<PathBuf> = report
+        .worktrees
+        .iter()
+        .filter_map(|e| e.main_repo.as_deref())
+        .filter(|m| !found.contains(m))
+        .map(PathBuf::from)
+        .collect();
+    for dir in outside {
+        let Some(record) = lookup(&dir, old, trust) else {
+            continue;
+        };
+        if matches!(record.kind, IndexedKind::Checkout { main: true, .. }) {
+            add_to_report(&mut report, &dir, &record.kind);
+            next.dirs.insert(dir.to_string_lossy().to_string(), record);
+        }
+    }
+
+    (report, next)
+}
+
+/// The index record for `dir`, from `old` when it can be reused.
+fn lookup(dir: &Path, old: &DiscoveryIndex, trust: bool) -> Option<IndexedDir> {
+    let record = match old.dirs.get(dir.to_string_lossy().as_ref()) {
+        Some(rec) if trust => Some(rec.clone()),
+        Some(rec) => {
+            let mtime = std::fs::metadata(dir).and_then(|m| m.modified()).ok();
+            (mtime == Some(rec.mtime)).then(|| revalidate(dir, rec))
+        }
+        None => None,
+    };
+    record.or_else(|| index_dir(dir))
+}
+
+fn add_to_report(report: &mut DiscoveryReport, dir: &Path, kind: &IndexedKind) {
+    match kind {
+        IndexedKind::Plain => {}
+        IndexedKind::Orphan(orphan) => report.orphans.push(orphan.clone()),
+        IndexedKind::Checkout { main, entry, .. } => {
+            if *main {
+                report.main_repos.push(dir.to_path_buf());
+            }
+            if let Some(entry) = entry {
+                report.worktrees.push(entry.clone());
+            }
+        }
+    }
+}
+
+/// Classifies a directory from scratch and, unless it is a checkout, lists
+/// its subdirectories.
+fn index_dir(dir: &Path) -> Option<IndexedDir> {
+    let mtime = std::fs::metadata(dir).and_then(|m| m.modified()).ok()?;
+    let kind = classify(dir);
+    let subdirs = if dir.join(".git").exists() {
+        Vec::new()
+    } else {
+        std::fs::read_dir(dir)
+            .map(|entries| {
+                entries
+                    .flatten()
+                    .map(|e| e.path())
+                    .filter(|p| p.is_dir())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    Some(IndexedDir {
+        mtime,
+        subdirs,
+        kind,
+    })
+}
+
+/// Reuses the listing of a directory whose mtime is unchanged; only a
+/// checkout's git dir or an orphan's target can change without touching it.
+fn revalidate(dir: &Path, rec: &IndexedDir) -> IndexedDir {
+    let kind = match &rec.kind {
+        IndexedKind::Checkout { stamp, .. } if stamp.is_some() && *stamp == worktree_stamp(dir) => {
+            rec.kind.clone()
+        }
+        IndexedKind::Checkout { .. } | IndexedKind::Orphan(_) => classify(dir),
+        IndexedKind::Plain => IndexedKind::Plain,
+    };
+    IndexedDir {
+        mtime: rec.mtime,
+        subdirs: rec.subdirs.clone(),
+        kind,
+    }
+}
+
+fn classify(dir: &Path) -> IndexedKind {
+    if let Some(orphan) = check_orphan(dir) {
+        return IndexedKind::Orphan(orphan);
+    }
+    let Some(git_dir) = status::worktree_git_dir(dir) else {
+        return IndexedKind::Plain;
+    };
+    let main = dir.join(".git").is_dir();
+    IndexedKind::Checkout {
+        main,
+        entry: extract_worktree_info(dir, &git_dir, main),
+        stamp: worktree_stamp(dir),
+    }
+}
+
+fn worktree_stamp(dir: &Path) -> Option<SystemTime> {
+    let git_dir = status::worktree_git_dir(dir)?;
+    let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    mtime(&git_dir).max(mtime(&git_dir.join("logs/HEAD")))
+}
+
+fn check_orphan(worktree_path: &Path) -> Option<OrphanedWorktree> {
+    let raw = std::fs::read_to_string(worktree_path.join(".git")).ok()?;
+    let pointer = raw.trim().strip_prefix("gitdir: ")?.trim();
+    let gitdir = worktree_path.join(pointer);
+
+    let reason = if !gitdir.is_dir() {
+        "gitdir does not exist"
+    } else {
+        let commondir = std::fs::read_to_string(gitdir.join("commondir")).ok()?;
+        if gitdir.join(commondir.trim()).is_dir() {
+            return None;
+        }
+        "common git dir does not exist"
+    };
+
+    Some(OrphanedWorktree {
+        path: worktree_path.to_string_lossy().to_string(),
+        gitdir: pointer.to_owned(),
+        reason: reason.to_owned(),
+    })
+}
+
+/// Finds the repository that still lists `orphan` among its worktrees, i.e.
+/// one whose `worktrees/<id>/gitdir` points back at the orphan's `.git` file.
+/// Candidates may be main checkouts or bare repositories.
+#[must_use]
+pub fn find_repair_repo(orphan: &OrphanedWorktree, candidates: &[PathBuf]) -> Option<PathBuf> {
+    let target = Path::new(&orphan.path).join(".git");
+    let target = std::fs::canonicalize(&target).unwrap_or(target);
+    candidates
+        .iter()
+        .find(|repo| {
+            let common = if repo.join(".git").is_dir() {
+                repo.join(".git")
+            } else {
+                repo.to_path_buf()
+            };
+            let Ok(entries) = std::fs::read_dir(common.join("worktrees")) else {
+                return false;
+            };
+            entries.flatten().any(|e| {
+                std::fs::read_to_string(e.path().join("gitdir")).is_ok_and(|raw| {
+                    let p = PathBuf::from(raw.trim());
+                    std::fs::canonicalize(&p).unwrap_or(p) == target
+                })
+            })
+        })
+        .cloned()
 }
 
 #[must_use]
@@ -65,13 +694,15 @@ pub fn filter_global_worktrees<'a>(
         .collect()
 }
 
-fn extract_worktree_info(worktree_path: &Path) -> Option<GlobalWorktreeEntry> {
-    let gitfile = worktree_path.join(".git");
-    let raw = std::fs::read_to_string(&gitfile).ok()?;
-    let s = raw.trim();
-    if !s.starts_with("gitdir: ") {
-        return None;
-    }
+fn extract_worktree_info(
+    worktree_path: &Path,
+    git_dir: &Path,
+    is_main: bool,
+) -> Option<GlobalWorktreeEntry> {
+    // `git worktree lock` records the lock as a file inside the worktree's admin dir.
+    let lock_reason = std::fs::read_to_string(git_dir.join("locked"))
+        .ok()
+        .map(|r| r.trim().to_owned());
 
     let git = Git::new(worktree_path.to_path_buf());
     let repository_url = git.get_repository_url().ok()?;
@@ -92,63 +723,144 @@ fn extract_worktree_info(worktree_path: &Path) -> Option<GlobalWorktreeEntry> {
         branch,
         path: worktree_path.to_string_lossy().to_string(),
         commit_hash,
-        is_main: false,
+        is_main,
+        is_locked: lock_reason.is_some(),
+        lock_reason: lock_reason.filter(|r| !r.is_empty()),
+        main_repo: Some(main_repo_path(git_dir).to_string_lossy().to_string()),
     })
 }
 
-fn walk_dirs(
-    base: &Path,
-    max_depth: usize,
-    visited: &mut BTreeSet<PathBuf>,
-    on_dir: &mut dyn FnMut(&Path),
-) {
-    let mut stack: Vec<(PathBuf, usize)> = vec![(base.to_path_buf(), 0)];
-    while let Some((dir, depth)) = stack.pop() {
-        if depth > max_depth {
-            continue;
-        }
-        if !visited.insert(dir.clone()) {
-            continue;
+/// The main checkout owning `git_dir`: the parent of a `.git` common dir, or
+/// the common dir itself for bare repositories.
+fn main_repo_path(git_dir: &Path) -> PathBuf {
+    let common = status::common_git_dir(git_dir);
+    match common.parent() {
+        Some(parent) if common.file_name().is_some_and(|n| n == ".git") => parent.to_path_buf(),
+        _ => common,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_orphaned_worktrees_and_finds_moved_repo() {
+        let td = tempfile::tempdir().expect("tempdir");
+        let base = td.path().join("base");
+
+        // A worktree whose main repo used to live at <td>/old-repo.
+        let orphan_dir = base.join("example.com/me/repo/feature");
+        std::fs::create_dir_all(&orphan_dir).expect("mkdir");
+        let stale = td.path().join("old-repo/.git/worktrees/feature");
+        std::fs::write(
+            orphan_dir.join(".git"),
+            format!("gitdir: {}\n", stale.display()),
+        )
+        .expect("write .git");
+
+        // The repo now lives inside base_dir and still knows the worktree.
+        let moved = base.join("moved-repo");
+        let admin = moved.join(".git/worktrees/feature");
+        std::fs::create_dir_all(&admin).expect("mkdir admin");
+        std::fs::write(
+            admin.join("gitdir"),
+            format!("{}\n", orphan_dir.join(".git").display()),
+        )
+        .expect("write gitdir");
+
+        let mut cfg = config::Config::default();
+        cfg.worktree.base_dir = base.to_string_lossy().to_string();
+        cfg.discovery.global_scan_depth = 5;
+        let report = discover_global(&cfg).expect("discover");
+        assert!(report.worktrees.is_empty());
+        assert_eq!(report.orphans.len(), 1);
+        assert_eq!(report.orphans[0].path, orphan_dir.to_string_lossy());
+        assert_eq!(report.orphans[0].reason, "gitdir does not exist");
+        assert_eq!(report.main_repos, vec![moved.clone()]);
+
+        assert_eq!(
+            find_repair_repo(&report.orphans[0], &report.main_repos),
+            Some(moved)
+        );
+        assert_eq!(find_repair_repo(&report.orphans[0], &[]), None);
+    }
+
+    fn fake_checkout(dir: &Path) {
+        std::fs::create_dir_all(dir.join(".git")).expect("mkdir .git");
+    }
+
+    #[test]
+    fn walk_skips_ignored_dirs_and_stops_at_checkouts() {
+        let td = tempfile::tempdir().expect("tempdir");
+        let base = td.path().join("base");
+        for dir in [
+            "example.com/me/app",
+            "example.com/me/app/nested",
+            "example.com/me/lib/node_modules/dep",
+            "example.com/me/.cache/hidden",
+            "example.com/vendor/thing",
+            "scratch/tmp/repo",
+            "scratch/keep",
+            "a/b/c/d/e/f/too-deep",
+        ] {
+            fake_checkout(&base.join(dir));
         }
+        std::fs::write(base.join(IGNORE_FILE), "# scratch space\n\nscratch/tmp\n")
+            .expect("write ignore file");
 
-        on_dir(&dir);
+        let mut cfg = config::Config::default();
+        cfg.worktree.base_dir = base.to_string_lossy().to_string();
+        cfg.discovery.global_scan_depth = 5;
+        cfg.discovery.ignore.push("vendor".to_owned());
+        let report = discover_global(&cfg).expect("discover");
+        assert_eq!(
+            report.main_repos,
+            vec![base.join("example.com/me/app"), base.join("scratch/keep")]
+        );
 
-        let Ok(entries) = std::fs::read_dir(&dir) else {
-            continue;
-        };
+        // Without `discovery.ignore` only `.gwtuiignore` and the depth apply.
+        cfg.discovery.ignore.clear();
+        cfg.discovery.global_scan_depth = 6;
+        let report = discover_global(&cfg).expect("discover");
+        assert_eq!(
+            report.main_repos,
+            vec![
+                base.join("example.com/me/.cache/hidden"),
+                base.join("example.com/me/app"),
+                base.join("example.com/me/lib/node_modules/dep"),
+                base.join("example.com/vendor/thing"),
+                base.join("scratch/keep"),
+            ]
+        );
+
+        cfg.discovery.ignore = vec!["[".to_owned()];
+        assert!(discover_global(&cfg).is_err());
+    }
 
-        for entry in entries.flatten() {
-            let path = entry.path();
-            if path.is_dir() {
-                if should_skip_dir(&path) {
-                    continue;
+    #[test]
+    fn parallel_walk_matches_sequential_walk() {
+        let td = tempfile::tempdir().expect("tempdir");
+        let base = td.path().join("base");
+        for host in 0..3 {
+            for owner in 0..4 {
+                for repo in 0..3 {
+                    fake_checkout(&base.join(format!("h{host}/o{owner}/r{repo}")));
                 }
-                stack.push((path, depth + 1));
+                std::fs::create_dir_all(base.join(format!("h{host}/o{owner}/empty")))
+                    .expect("mkdir");
             }
         }
-    }
-}
 
-fn should_skip_dir(path: &Path) -> bool {
-    let Some(name) = path.file_name().and_then(|s| s.to_str()) else {
-        return false;
-    };
-    if name == ".git" {
-        return true;
-    }
-    if name == "node_modules" || name == "target" || name == ".idea" || name == ".vscode" {
-        return true;
+        let mut cfg = config::Config::default();
+        cfg.worktree.base_dir = base.to_string_lossy().to_string();
+        cfg.discovery.concurrency = 1;
+        let sequential = discover_global(&cfg).expect("discover").main_repos;
+        cfg.discovery.concurrency = 8;
+        let parallel = discover_global(&cfg).expect("discover").main_repos;
+        assert_eq!(sequential.len(), 36);
+        assert_eq!(sequential, parallel);
     }
-    // Skip hidden dirs except the base.
-    if name.starts_with('.') {
-        return true;
-    }
-    false
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
     fn matches_global_worktrees_by_repo_branch_path_and_repo_branch_combo() {
@@ -160,6 +872,9 @@ mod tests {
                 path: "/tmp/repo/main".to_owned(),
                 commit_hash: "abc".to_owned(),
                 is_main: false,
+                is_locked: false,
+                lock_reason: None,
+                main_repo: None,
             },
             GlobalWorktreeEntry {
                 repository_url: "https://example.com/a/other.git".to_owned(),
@@ -168,6 +883,9 @@ mod tests {
                 path: "/tmp/other/feature-x".to_owned(),
                 commit_hash: "def".to_owned(),
                 is_main: false,
+                is_locked: false,
+                lock_reason: None,
+                main_repo: None,
             },
         ];
 
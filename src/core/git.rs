@@ -2,6 +2,8 @@
 
 use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
+#[cfg(any(test, feature = "test-util"))]
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 use crate::error::GwtuiError;
 
@@ -21,6 +23,36 @@ pub struct Branch {
     pub last_commit: CommitInfo,
 }
 
+#[cfg(any(test, feature = "test-util"))]
+static GIT_SPAWNS: AtomicUsize = AtomicUsize::new(0);
+
+/// Number of `git` processes spawned through [`Git`] so far in this process.
+/// Only built for tests, through the `test-util` feature.
+#[cfg(any(test, feature = "test-util"))]
+#[must_use]
+pub fn spawn_count() -> usize {
+    GIT_SPAWNS.load(Ordering::Relaxed)
+}
+
+fn count_spawn() {
+    #[cfg(any(test, feature = "test-util"))]
+    GIT_SPAWNS.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Stdout of a finished git command, or its trimmed stderr as the error.
+fn stdout_or_error(args: &[&str], out: Output) -> Result<String, GwtuiError> {
+    if out.status.success() {
+        Ok(String::from_utf8_lossy(&out.stdout).to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        Err(GwtuiError::Other(format!(
+            "git {}: {}",
+            args.join(" "),
+            stderr.trim()
+        )))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct Git {
     repo_root: PathBuf,
@@ -49,6 +81,25 @@ impl Git {
         &self.repo_root
     }
 
+    /// Root of the main worktree, resolved through the common git dir so it works
+    /// from any linked worktree. For bare repositories this is the bare dir itself.
+    pub fn main_worktree_root(&self) -> Result<PathBuf, GwtuiError> {
+        let out = self.run(&["rev-parse", "--git-common-dir"])?;
+        let common = PathBuf::from(out.trim());
+        let common = if common.is_absolute() {
+            common
+        } else {
+            self.repo_root.join(common)
+        };
+        let common = std::fs::canonicalize(&common).unwrap_or(common);
+        if common.file_name().is_some_and(|n| n == ".git")
+            && let Some(parent) = common.parent()
+        {
+            return Ok(parent.to_path_buf());
+        }
+        Ok(common)
+    }
+
     pub fn get_repository_url(&self) -> Result<String, GwtuiError> {
         let out = self.run(&["remote", "get-url", "origin"])?;
         Ok(out.trim().to_owned())
@@ -88,16 +139,57 @@ impl Git {
         Ok(())
     }
 
+    pub fn add_worktree_detached(&self, path: &Path, rev: &str) -> Result<(), GwtuiError> {
+        let path = path.to_string_lossy();
+        let _ = self.run(&["worktree", "add", "--detach", &path, rev])?;
+        Ok(())
+    }
+
     pub fn remove_worktree(&self, path: &Path, force: bool) -> Result<(), GwtuiError> {
         let path = path.to_string_lossy();
         if force {
-            let _ = self.run(&["worktree", "remove", "--force", &path])?;
+            // A second --force is needed to remove locked worktrees; callers are
+            // expected to check the lock before forcing.
+            let _ = self.run(&["worktree", "remove", "--force", "--force", &path])?;
         } else {
             let _ = self.run(&["worktree", "remove", &path])?;
         }
         Ok(())
     }
 
+    pub fn move_worktree(&self, from: &Path, to: &Path) -> Result<(), GwtuiError> {
+        let from = from.to_string_lossy();
+        let to = to.to_string_lossy();
+        let _ = self.run(&["worktree", "move", &from, &to])?;
+        Ok(())
+    }
+
+    pub fn repair_worktrees(&self, paths: &[&Path]) -> Result<(), GwtuiError> {
+        let paths: Vec<String> = paths
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        let mut args = vec!["worktree", "repair"];
+        args.extend(paths.iter().map(String::as_str));
+        let _ = self.run(&args)?;
+        Ok(())
+    }
+
+    pub fn lock_worktree(&self, path: &Path, reason: Option<&str>) -> Result<(), GwtuiError> {
+        let path = path.to_string_lossy();
+        match reason {
+            Some(reason) => self.run(&["worktree", "lock", "--reason", reason, &path])?,
+            None => self.run(&["worktree", "lock", &path])?,
+        };
+        Ok(())
+    }
+
+    pub fn unlock_worktree(&self, path: &Path) -> Result<(), GwtuiError> {
+        let path = path.to_string_lossy();
+        let _ = self.run(&["worktree", "unlock", &path])?;
+        Ok(())
+    }
+
     pub fn prune_worktrees(&self) -> Result<(), GwtuiError> {
         let _ = self.run(&["worktree", "prune"])?;
         Ok(())
@@ -112,6 +204,155 @@ impl Git {
         Ok(())
     }
 
+    /// Stashes all changes of this worktree, including untracked files, and
+    /// returns the stash commit. `None` when there was nothing to stash.
+    pub fn stash_push_all(&self, message: &str) -> Result<Option<String>, GwtuiError> {
+        let before = self.stash_top()?;
+        let _ = self.run(&["stash", "push", "--include-untracked", "-m", message])?;
+        let after = self.stash_top()?;
+        Ok(after.filter(|sha| before.as_ref() != Some(sha)))
+    }
+
+    fn stash_top(&self) -> Result<Option<String>, GwtuiError> {
+        let out = self.run_raw(&["rev-parse", "-q", "--verify", "refs/stash"])?;
+        let sha = String::from_utf8_lossy(&out.stdout).trim().to_owned();
+        Ok((out.status.success() && !sha.is_empty()).then_some(sha))
+    }
+
+    /// `stash@{n}` for a stash commit; `stash pop`/`drop` only accept reflog refs.
+    fn stash_ref(&self, sha: &str) -> Result<String, GwtuiError> {
+        let out = self.run(&["stash", "list", "--format=%H"])?;
+        out.lines()
+            .position(|l| l.trim() == sha)
+            .map(|n| format!("stash@{{{n}}}"))
+            .ok_or_else(|| GwtuiError::Other(format!("stash {sha} not found")))
+    }
+
+    /// Applies the stash in the worktree at `dir`, restoring what was staged
+    /// when the index applies there and falling back to a plain apply when not.
+    pub fn stash_apply_in(&self, dir: &Path, sha: &str) -> Result<(), GwtuiError> {
+        if self
+            .run_in_dir(dir, &["stash", "apply", "--index", sha])
+            .is_err()
+        {
+            let _ = self.run_in_dir(dir, &["stash", "apply", sha])?;
+        }
+        Ok(())
+    }
+
+    /// Pops the stash back into this worktree, restoring the index as well.
+    pub fn stash_pop(&self, sha: &str) -> Result<(), GwtuiError> {
+        let stash = self.stash_ref(sha)?;
+        let _ = self.run(&["stash", "pop", "--index", &stash])?;
+        Ok(())
+    }
+
+    pub fn stash_drop(&self, sha: &str) -> Result<(), GwtuiError> {
+        let stash = self.stash_ref(sha)?;
+        let _ = self.run(&["stash", "drop", &stash])?;
+        Ok(())
+    }
+
+    /// Ref the repository's work lands on: `origin/HEAD`'s target (e.g.
+    /// `origin/main`) when set, otherwise the first local `main`, `master` or
+    /// `trunk` branch.
+    pub fn default_branch(&self) -> Result<String, GwtuiError> {
+        if let Ok(out) = self.run(&["symbolic-ref", "--short", "refs/remotes/origin/HEAD"]) {
+            let name = out.trim();
+            if !name.is_empty() {
+                return Ok(name.to_owned());
+            }
+        }
+        for name in ["main", "master", "trunk"] {
+            let out =
+                self.run_raw(&["rev-parse", "-q", "--verify", &format!("refs/heads/{name}")])?;
+            if out.status.success() {
+                return Ok(name.to_owned());
+            }
+        }
+        Err(GwtuiError::Other(
+            "could not determine the default branch (set origin/HEAD with `git remote set-head origin --auto`)"
+                .to_owned(),
+        ))
+    }
+
+    /// Commits on HEAD but not `base`, and on `base` but not HEAD.
+    pub fn ahead_behind(&self, base: &str) -> Result<(u32, u32), GwtuiError> {
+        let out = self.run(&[
+            "rev-list",
+            "--left-right",
+            "--count",
+            &format!("HEAD...{base}"),
+        ])?;
+        let mut counts = out.split_whitespace().map(|n| n.parse().unwrap_or(0));
+        Ok((counts.next().unwrap_or(0), counts.next().unwrap_or(0)))
+    }
+
+    /// Local branches whose tip is reachable from `target`.
+    pub fn merged_branches(&self, target: &str) -> Result<Vec<String>, GwtuiError> {
+        let out = self.run(&["branch", "--format=%(refname:short)", "--merged", target])?;
+        Ok(out
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty())
+            .map(str::to_owned)
+            .collect())
+    }
+
+    /// Local branches whose configured upstream no longer exists (`[gone]`).
+    pub fn gone_branches(&self) -> Result<Vec<String>, GwtuiError> {
+        let out = self.run(&[
+            "for-each-ref",
+            "--format=%(refname:short)|%(upstream:track)",
+            "refs/heads",
+        ])?;
+        Ok(out
+            .lines()
+            .filter_map(|l| l.split_once('|'))
+            .filter(|(_, track)| track.trim() == "[gone]")
+            .map(|(name, _)| name.to_owned())
+            .collect())
+    }
+
+    /// Whether `rev` is reachable from any remote-tracking branch.
+    pub fn remote_contains(&self, rev: &str) -> Result<bool, GwtuiError> {
+        let out = self.run(&[
+            "for-each-ref",
+            "--count=1",
+            "--format=%(refname)",
+            "--contains",
+            rev,
+            "refs/remotes",
+        ])?;
+        Ok(!out.trim().is_empty())
+    }
+
+    /// Whether the combined changes of `branch` since it forked from `target`
+    /// already landed on `target`, as after a squash merge. The branch diff is
+    /// folded into one dangling commit that `git cherry` compares by patch id.
+    pub fn squash_merged(&self, branch: &str, target: &str) -> Result<bool, GwtuiError> {
+        let base = self.run(&["merge-base", target, branch])?;
+        let tree = self.run(&["rev-parse", &format!("{branch}^{{tree}}")])?;
+        let squashed = self.run(&[
+            "commit-tree",
+            tree.trim(),
+            "-p",
+            base.trim(),
+            "-m",
+            "gwtui squash check",
+        ])?;
+        let out = self.run(&["cherry", target, squashed.trim()])?;
+        Ok(out.trim_start().starts_with('-'))
+    }
+
+    /// Creates or fast-forwards local `branch` to `remote`'s `src_ref`. Git
+    /// rejects the update when `branch` has commits `src_ref` lacks.
+    pub fn fetch_ref(&self, remote: &str, src_ref: &str, branch: &str) -> Result<(), GwtuiError> {
+        let refspec = format!("{src_ref}:refs/heads/{branch}");
+        let _ = self.run(&["fetch", remote, &refspec])?;
+        Ok(())
+    }
+
     pub fn list_branches(&self, include_remote: bool) -> Result<Vec<Branch>, GwtuiError> {
         let mut args = vec![
             "branch",
@@ -161,32 +402,43 @@ impl Git {
     }
 
     pub fn run(&self, args: &[&str]) -> Result<String, GwtuiError> {
-        let out = self.run_raw(args)?;
-        if out.status.success() {
-            Ok(String::from_utf8_lossy(&out.stdout).to_string())
-        } else {
-            let stderr = String::from_utf8_lossy(&out.stderr);
-            Err(GwtuiError::Other(format!(
-                "git {}: {}",
-                args.join(" "),
-                stderr.trim()
-            )))
-        }
+        stdout_or_error(args, self.run_raw(args)?)
     }
 
     pub fn run_raw(&self, args: &[&str]) -> Result<Output, GwtuiError> {
-        let out = Command::new("git")
-            .args(args)
-            .current_dir(&self.repo_root)
-            .output()
-            .map_err(|e| match e.kind() {
-                std::io::ErrorKind::NotFound => GwtuiError::GitNotFound,
-                _ => GwtuiError::Other(format!("failed to run git: {e}")),
-            })?;
-        Ok(out)
+        self.run_raw_with(args, |_| {})
+    }
+
+    /// [`run_raw`](Self::run_raw) with `configure` applied to the command
+    /// before it is spawned (environment, stdin, ...).
+    fn run_raw_with(
+        &self,
+        args: &[&str],
+        configure: impl FnOnce(&mut Command),
+    ) -> Result<Output, GwtuiError> {
+        count_spawn();
+        let mut cmd = Command::new("git");
+        cmd.args(args).current_dir(&self.repo_root);
+        configure(&mut cmd);
+        cmd.output().map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => GwtuiError::GitNotFound,
+            _ => GwtuiError::Other(format!("failed to run git: {e}")),
+        })
+    }
+
+    /// `git fetch --all` with credential prompts disabled, so a remote that needs
+    /// interactive auth fails instead of hanging a background refresh.
+    pub fn fetch_all(&self) -> Result<(), GwtuiError> {
+        let args = ["fetch", "--all", "--quiet"];
+        let out = self.run_raw_with(&args, |cmd| {
+            cmd.env("GIT_TERMINAL_PROMPT", "0")
+                .stdin(std::process::Stdio::null());
+        })?;
+        stdout_or_error(&args, out).map(|_| ())
     }
 
     pub fn run_in_dir(&self, dir: &Path, args: &[&str]) -> Result<String, GwtuiError> {
+        count_spawn();
         let out = Command::new("git")
             .args(args)
             .current_dir(dir)
@@ -0,0 +1,194 @@
+#![forbid(unsafe_code)]
+
+use regex::Regex;
+
+use crate::error::GwtuiError;
+
+/// A list of glob patterns matched against `/`-separated relative paths.
+///
+/// Supported syntax: `*` (within one path segment), `**` (any number of
+/// segments), `?`, `[abc]` / `[!abc]` and `{a,b}` alternatives.
+#[derive(Debug, Clone, Default)]
+pub struct GlobSet {
+    patterns: Vec<Pattern>,
+}
+
+#[derive(Debug, Clone)]
+struct Pattern {
+    regex: Regex,
+    /// One regex per leading directory segment, up to the last segment or the
+    /// first `**`.
+    dirs: Vec<Regex>,
+    /// Whether a `**` follows `dirs`, so any depth below them can match.
+    deep: bool,
+}
+
+impl GlobSet {
+    pub fn new(patterns: &[String]) -> Result<Self, GwtuiError> {
+        let mut out = Vec::with_capacity(patterns.len());
+        for p in patterns {
+            let p = p.trim().trim_start_matches("./").trim_end_matches('/');
+            if p.is_empty() {
+                continue;
+            }
+            let mut dirs = Vec::new();
+            let mut deep = false;
+            let segments: Vec<&str> = p.split('/').collect();
+            for seg in &segments[..segments.len() - 1] {
+                if seg.contains("**") {
+                    deep = true;
+                    break;
+                }
+                dirs.push(glob_to_regex(seg)?);
+            }
+            out.push(Pattern {
+                regex: glob_to_regex(p)?,
+                dirs,
+                deep,
+            });
+        }
+        Ok(Self { patterns: out })
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    #[must_use]
+    pub fn is_match(&self, path: &str) -> bool {
+        let path = path.trim_end_matches('/');
+        self.patterns.iter().any(|p| p.regex.is_match(path))
+    }
+
+    /// Whether some pattern could match a path below directory `dir`, judged by
+    /// the pattern's leading directory segments. A `**` only reaches below
+    /// directories named before it, so `**/.env` or `*.local` never descend.
+    #[must_use]
+    pub fn could_match_under(&self, dir: &str) -> bool {
+        let segments: Vec<&str> = dir.trim_end_matches('/').split('/').collect();
+        self.patterns.iter().any(|p| {
+            let named = segments.len() <= p.dirs.len() || (p.deep && !p.dirs.is_empty());
+            named
+                && p.dirs
+                    .iter()
+                    .zip(&segments)
+                    .all(|(re, seg)| re.is_match(seg))
+        })
+    }
+}
+
+pub fn glob_to_regex(pattern: &str) -> Result<Regex, GwtuiError> {
+    let chars: Vec<char> = pattern.chars().collect();
+    let mut re = String::from("^");
+    let mut in_braces = false;
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            '*' if chars.get(i + 1) == Some(&'*') => {
+                let at_segment_start = i == 0 || chars[i - 1] == '/';
+                if at_segment_start && chars.get(i + 2) == Some(&'/') {
+                    re.push_str("(?:.*/)?");
+                    i += 3;
+                } else {
+                    re.push_str(".*");
+                    i += 2;
+                }
+                continue;
+            }
+            '*' => re.push_str("[^/]*"),
+            '?' => re.push_str("[^/]"),
+            '[' => {
+                let Some(end) = chars[i + 1..].iter().position(|c| *c == ']') else {
+                    return Err(invalid(pattern, "unclosed '['"));
+                };
+                let class: String = chars[i + 1..i + 1 + end].iter().collect();
+                let class = class.replace('\\', "\\\\");
+                match class.strip_prefix('!') {
+                    Some(rest) => re.push_str(&format!("[^{rest}]")),
+                    None => re.push_str(&format!("[{class}]")),
+                }
+                i += end + 2;
+                continue;
+            }
+            '{' if !in_braces => {
+                in_braces = true;
+                re.push_str("(?:");
+            }
+            ',' if in_braces => re.push('|'),
+            '}' if in_braces => {
+                in_braces = false;
+                re.push(')');
+            }
+            other => re.push_str(&regex::escape(&other.to_string())),
+        }
+        i += 1;
+    }
+    if in_braces {
+        return Err(invalid(pattern, "unclosed '{'"));
+    }
+    re.push('$');
+    Regex::new(&re).map_err(|e| invalid(pattern, &e.to_string()))
+}
+
+fn invalid(pattern: &str, msg: &str) -> GwtuiError {
+    GwtuiError::Other(format!("invalid glob '{pattern}': {msg}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(patterns: &[&str]) -> GlobSet {
+        let patterns: Vec<String> = patterns.iter().map(|p| (*p).to_owned()).collect();
+        GlobSet::new(&patterns).expect("valid globs")
+    }
+
+    #[test]
+    fn matches_relative_paths() {
+        let g = set(&[
+            ".env",
+            "config/*.toml",
+            "**/.envrc",
+            ".vscode/",
+            "*.{key,pem}",
+        ]);
+        assert!(g.is_match(".env"));
+        assert!(!g.is_match("app/.env"));
+        assert!(g.is_match("config/local.toml"));
+        assert!(!g.is_match("config/nested/local.toml"));
+        assert!(g.is_match(".envrc"));
+        assert!(g.is_match("services/api/.envrc"));
+        assert!(g.is_match(".vscode"));
+        assert!(g.is_match(".vscode/"));
+        assert!(g.is_match("dev.pem"));
+        assert!(!g.is_match("dev.txt"));
+
+        let g = set(&["src/**", "file[0-9].txt", "[!a]b"]);
+        assert!(g.is_match("src/a/b/c.rs"));
+        assert!(g.is_match("file7.txt"));
+        assert!(!g.is_match("fileX.txt"));
+        assert!(g.is_match("cb"));
+        assert!(!g.is_match("ab"));
+    }
+
+    #[test]
+    fn prunes_directories_by_leading_segments() {
+        let g = set(&[".vscode/settings.json", "config/*/local.toml"]);
+        assert!(g.could_match_under(".vscode"));
+        assert!(g.could_match_under("config"));
+        assert!(g.could_match_under("config/dev"));
+        assert!(!g.could_match_under("config/dev/nested"));
+        assert!(!g.could_match_under("node_modules"));
+        assert!(!set(&["**/.env", "*.local"]).could_match_under("node_modules"));
+        assert!(set(&["apps/**/.env"]).could_match_under("apps/web/src"));
+        assert!(!set(&["apps/**/.env"]).could_match_under("node_modules"));
+    }
+
+    #[test]
+    fn rejects_malformed_globs() {
+        assert!(glob_to_regex("[abc").is_err());
+        assert!(glob_to_regex("{a,b").is_err());
+    }
+}
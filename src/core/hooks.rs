@@ -0,0 +1,181 @@
+#![forbid(unsafe_code)]
+
+use std::fmt::Write as _;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+use crate::config::{self, Config};
+use crate::error::GwtuiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookKind {
+    PostAdd,
+    PreRemove,
+    PostRemove,
+}
+
+impl HookKind {
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            HookKind::PostAdd => "post_add",
+            HookKind::PreRemove => "pre_remove",
+            HookKind::PostRemove => "post_remove",
+        }
+    }
+}
+
+/// Where hook output goes: straight to the terminal (CLI) or appended to a
+/// buffer that the caller displays (TUI). The buffer keeps whatever ran
+/// before a failing hook.
+#[derive(Debug)]
+pub enum HookOutput<'a> {
+    Inherit,
+    Capture(&'a mut String),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct HookContext<'a> {
+    pub branch: &'a str,
+    pub worktree_path: &'a Path,
+    pub repo_root: &'a Path,
+}
+
+/// `cfg`'s hooks followed by the repository's `.gwtui.toml` hooks (when
+/// `hooks.allow_repo_local` is enabled).
+pub fn commands_for(
+    cfg: &Config,
+    repo_root: &Path,
+    kind: HookKind,
+) -> Result<Vec<String>, GwtuiError> {
+    let mut commands = select(&cfg.hooks, kind).to_vec();
+    if cfg.hooks.allow_repo_local {
+        let repo_cfg = config::load_repo_config(repo_root)?;
+        commands.extend(select(&repo_cfg.hooks, kind).iter().cloned());
+    }
+    Ok(commands)
+}
+
+fn select(hooks: &config::HooksConfig, kind: HookKind) -> &[String] {
+    match kind {
+        HookKind::PostAdd => &hooks.post_add,
+        HookKind::PreRemove => &hooks.pre_remove,
+        HookKind::PostRemove => &hooks.post_remove,
+    }
+}
+
+/// Runs `commands` in order through the shell, stopping at the first failure.
+/// Commands run inside the worktree, or the repository root once the worktree
+/// is gone (`post_remove`).
+pub fn run_hooks(
+    kind: HookKind,
+    ctx: &HookContext<'_>,
+    commands: &[String],
+    mut output: HookOutput<'_>,
+) -> Result<(), GwtuiError> {
+    let cwd = if ctx.worktree_path.is_dir() {
+        ctx.worktree_path
+    } else {
+        ctx.repo_root
+    };
+
+    for command in commands {
+        let mut cmd = shell_command(command);
+        cmd.current_dir(cwd)
+            .env("GWTUI_HOOK", kind.name())
+            .env("GWTUI_BRANCH", ctx.branch)
+            .env("GWTUI_WORKTREE_PATH", ctx.worktree_path)
+            .env("GWTUI_REPO_ROOT", ctx.repo_root)
+            .stdin(Stdio::null());
+
+        let failed = |detail: String| GwtuiError::HookFailed {
+            hook: kind.name().to_owned(),
+            command: command.clone(),
+            detail,
+        };
+
+        match &mut output {
+            HookOutput::Inherit => {
+                let status = cmd
+                    .status()
+                    .map_err(|e| failed(format!("failed to start: {e}")))?;
+                if !status.success() {
+                    return Err(failed(status.to_string()));
+                }
+            }
+            HookOutput::Capture(captured) => {
+                let out = cmd
+                    .output()
+                    .map_err(|e| failed(format!("failed to start: {e}")))?;
+                let stdout = String::from_utf8_lossy(&out.stdout);
+                let stderr = String::from_utf8_lossy(&out.stderr);
+                let _ = write!(captured, "$ {command}\n{stdout}{stderr}");
+                if !out.status.success() {
+                    let last = stderr.lines().rev().find(|l| !l.trim().is_empty());
+                    return Err(failed(match last {
+                        Some(line) => format!("{}: {}", out.status, line.trim()),
+                        None => out.status.to_string(),
+                    }));
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn shell_command(command: &str) -> Command {
+    if cfg!(windows) {
+        let mut cmd = Command::new("cmd");
+        cmd.arg("/C").arg(command);
+        cmd
+    } else {
+        let mut cmd = Command::new("sh");
+        cmd.arg("-c").arg(command);
+        cmd
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_hooks_with_env_and_stops_on_failure() {
+        let td = tempfile::tempdir().expect("tempdir");
+        let ctx = HookContext {
+            branch: "feature/x",
+            worktree_path: td.path(),
+            repo_root: Path::new("/repo"),
+        };
+
+        let mut out = String::new();
+        run_hooks(
+            HookKind::PostAdd,
+            &ctx,
+            &["echo \"$GWTUI_HOOK $GWTUI_BRANCH $GWTUI_REPO_ROOT\"".to_owned()],
+            HookOutput::Capture(&mut out),
+        )
+        .expect("hook succeeds");
+        assert!(out.contains("post_add feature/x /repo"));
+
+        let mut out = String::new();
+        let err = run_hooks(
+            HookKind::PreRemove,
+            &ctx,
+            &[
+                "echo stopping".to_owned(),
+                "echo boom >&2; exit 3".to_owned(),
+                "touch should-not-run".to_owned(),
+            ],
+            HookOutput::Capture(&mut out),
+        )
+        .expect_err("hook fails");
+        let msg = err.to_string();
+        assert!(msg.contains("pre_remove"));
+        assert!(msg.contains("boom"));
+        // What ran before the failure is kept.
+        assert!(out.contains("stopping"));
+        assert!(out.contains("boom"));
+        assert!(!td.path().join("should-not-run").exists());
+    }
+}
@@ -0,0 +1,146 @@
+#![forbid(unsafe_code)]
+
+use std::path::Path;
+
+use crate::core::git::Git;
+use crate::core::glob::GlobSet;
+use crate::error::GwtuiError;
+
+/// What `apply_local_files` did, as paths relative to the worktree root.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalFilesReport {
+    pub copied: Vec<String>,
+    pub linked: Vec<String>,
+    pub skipped: Vec<(String, String)>,
+}
+
+impl LocalFilesReport {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.copied.is_empty() && self.linked.is_empty() && self.skipped.is_empty()
+    }
+}
+
+/// Copies or symlinks untracked/ignored files matching `copy` / `link` from
+/// `source` (normally the main worktree) into `dest`. Tracked files are never
+/// touched since the new checkout already has them; existing destination
+/// paths are left alone and reported as skipped.
+pub fn apply_local_files(
+    source: &Path,
+    dest: &Path,
+    copy: &GlobSet,
+    link: &GlobSet,
+) -> Result<LocalFilesReport, GwtuiError> {
+    let mut report = LocalFilesReport::default();
+    if copy.is_empty() && link.is_empty() {
+        return Ok(report);
+    }
+
+    // `--directory` collapses fully untracked directories (node_modules/, .vscode/)
+    // into a single entry, which keeps this cheap on large checkouts: they are
+    // only entered when a pattern names them.
+    let out = Git::new(source.to_path_buf()).run(&["ls-files", "-z", "--others", "--directory"])?;
+    for entry in out.split('\0').filter(|e| !e.is_empty()) {
+        visit(
+            source,
+            dest,
+            entry.trim_end_matches('/'),
+            copy,
+            link,
+            &mut report,
+        );
+    }
+    Ok(report)
+}
+
+fn visit(
+    source: &Path,
+    dest: &Path,
+    rel: &str,
+    copy: &GlobSet,
+    link: &GlobSet,
+    report: &mut LocalFilesReport,
+) {
+    let src = source.join(rel);
+    let is_dir = src.is_dir();
+
+    if link.is_match(rel) {
+        let dst = dest.join(rel);
+        match place(&dst).and_then(|()| symlink(&src, &dst, is_dir)) {
+            Ok(()) => report.linked.push(rel.to_owned()),
+            Err(reason) => report.skipped.push((rel.to_owned(), reason)),
+        }
+        return;
+    }
+    if copy.is_match(rel) {
+        let dst = dest.join(rel);
+        match place(&dst).and_then(|()| copy_path(&src, &dst)) {
+            Ok(()) => report.copied.push(rel.to_owned()),
+            Err(reason) => report.skipped.push((rel.to_owned(), reason)),
+        }
+        return;
+    }
+
+    // Nested checkouts (worktrees under the main one) are never entered.
+    if is_dir
+        && !src.join(".git").exists()
+        && (copy.could_match_under(rel) || link.could_match_under(rel))
+    {
+        let Ok(entries) = std::fs::read_dir(&src) else {
+            return;
+        };
+        let mut names: Vec<String> = entries
+            .flatten()
+            .filter_map(|e| e.file_name().to_str().map(str::to_owned))
+            .filter(|n| n != ".git")
+            .collect();
+        names.sort();
+        for name in names {
+            visit(source, dest, &format!("{rel}/{name}"), copy, link, report);
+        }
+    }
+}
+
+/// Refuses to overwrite and creates the parent directory.
+fn place(dst: &Path) -> Result<(), String> {
+    if dst.symlink_metadata().is_ok() {
+        return Err("already exists".to_owned());
+    }
+    if let Some(parent) = dst.parent() {
+        std::fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn copy_path(src: &Path, dst: &Path) -> Result<(), String> {
+    let meta = src.symlink_metadata().map_err(|e| e.to_string())?;
+    if meta.file_type().is_symlink() {
+        let target = std::fs::read_link(src).map_err(|e| e.to_string())?;
+        return symlink(&target, dst, src.is_dir());
+    }
+    if meta.is_dir() {
+        std::fs::create_dir_all(dst).map_err(|e| e.to_string())?;
+        let entries = std::fs::read_dir(src).map_err(|e| e.to_string())?;
+        for entry in entries.flatten() {
+            copy_path(&entry.path(), &dst.join(entry.file_name()))?;
+        }
+        return Ok(());
+    }
+    std::fs::copy(src, dst)
+        .map(|_| ())
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path, _is_dir: bool) -> Result<(), String> {
+    std::os::unix::fs::symlink(target, link).map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+fn symlink(target: &Path, link: &Path, is_dir: bool) -> Result<(), String> {
+    if is_dir {
+        std::os::windows::fs::symlink_dir(target, link).map_err(|e| e.to_string())
+    } else {
+        std::os::windows::fs::symlink_file(target, link).map_err(|e| e.to_string())
+    }
+}
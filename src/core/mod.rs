@@ -2,6 +2,13 @@
 
 pub mod discovery;
 pub mod git;
+pub mod glob;
+pub mod hooks;
+pub mod local_files;
 pub mod naming;
+pub mod process;
+pub mod query;
 pub mod status;
+pub mod tree;
+pub mod watch;
 pub mod worktree;
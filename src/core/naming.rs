@@ -4,7 +4,9 @@ use regex::Regex;
 use sha2::{Digest as _, Sha256};
 use std::fmt::Write as _;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+use crate::error::GwtuiError;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct RepoId {
     pub host: String,
     pub owner: String,
@@ -47,22 +49,272 @@ fn parse_host_path(host: &str, path: &str) -> Option<RepoId> {
     })
 }
 
+/// Variables the naming template accepts.
+pub const TEMPLATE_VARIABLES: &[&str] = &[
+    "host",
+    "owner",
+    "repo",
+    "branch",
+    "ref",
+    "hash",
+    "pr",
+    "date",
+    "user",
+    "short_hash",
+    "branch_leaf",
+    "branch_prefix",
+    "base",
+];
+
+/// gwq-style spellings of some variables.
+const TEMPLATE_ALIASES: &[(&str, &str)] = &[
+    (".Host", "host"),
+    (".Owner", "owner"),
+    (".Repository", "repo"),
+    (".Branch", "branch"),
+    (".Hash", "hash"),
+];
+
+/// Values for the naming template. `branch` is the branch name, or the
+/// revision of a detached worktree; it fills both `{{branch}}` and
+/// `{{ref}}`. Empty fields render as empty strings.
+#[derive(Debug, Clone, Default)]
+pub struct TemplateContext {
+    pub id: RepoId,
+    pub branch: String,
+    /// Last `/`-separated component of the branch (`feat/ui/x` → `x`).
+    pub branch_leaf: String,
+    /// Everything before it (`feat/ui`), empty for branches without a `/`.
+    pub branch_prefix: String,
+    /// Pull/merge request number (empty for other branches).
+    pub pr: Option<String>,
+    /// Default branch of the repository, without the remote.
+    pub base: String,
+    pub user: String,
+    /// Abbreviated commit the worktree is created at.
+    pub short_hash: String,
+    /// Current date as `YYYY-MM-DD` (UTC).
+    pub date: String,
+}
+
+impl TemplateContext {
+    #[must_use]
+    pub fn new(id: RepoId, branch: &str) -> Self {
+        let (prefix, leaf) = branch.rsplit_once('/').unwrap_or(("", branch));
+        Self {
+            id,
+            branch: branch.to_owned(),
+            branch_leaf: leaf.to_owned(),
+            branch_prefix: prefix.to_owned(),
+            ..Self::default()
+        }
+    }
+
+    /// Applies `worktree.sanitize` to the branch-derived values.
+    #[must_use]
+    pub fn sanitized(mut self, sanitize: &std::collections::BTreeMap<String, String>) -> Self {
+        for value in [
+            &mut self.branch,
+            &mut self.branch_leaf,
+            &mut self.branch_prefix,
+        ] {
+            *value = sanitize_all(std::mem::take(value), sanitize);
+        }
+        self
+    }
+
+    fn get(&self, var: &str) -> String {
+        match var {
+            "host" => self.id.host.clone(),
+            "owner" => self.id.owner.clone(),
+            "repo" => self.id.repo.clone(),
+            "branch" | "ref" => self.branch.clone(),
+            "hash" => short_hash(
+                &format!("{}/{}/{}", self.id.host, self.id.owner, self.id.repo),
+                &self.branch,
+            ),
+            "pr" => self.pr.clone().unwrap_or_default(),
+            "date" => self.date.clone(),
+            "user" => self.user.clone(),
+            "short_hash" => self.short_hash.clone(),
+            "branch_leaf" => self.branch_leaf.clone(),
+            "branch_prefix" => self.branch_prefix.clone(),
+            "base" => self.base.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TemplateFilter {
+    Lower,
+    Upper,
+    Slug,
+    Truncate(usize),
+}
+
+impl TemplateFilter {
+    fn parse(raw: &str) -> Result<Self, GwtuiError> {
+        let (name, arg) = match raw.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg.trim())),
+            None => (raw.trim(), None),
+        };
+        let filter = match name {
+            "lower" => Self::Lower,
+            "upper" => Self::Upper,
+            "slug" => Self::Slug,
+            "truncate" => {
+                let n = arg
+                    .and_then(|a| a.parse::<usize>().ok())
+                    .filter(|n| *n > 0)
+                    .ok_or_else(|| {
+                        GwtuiError::InvalidTemplate(format!(
+                            "filter `truncate` needs a positive length, e.g. `truncate:30` (got `{raw}`)"
+                        ))
+                    })?;
+                return Ok(Self::Truncate(n));
+            }
+            _ => {
+                return Err(GwtuiError::InvalidTemplate(format!(
+                    "unknown filter `{name}` (expected lower, upper, slug or truncate:N)"
+                )));
+            }
+        };
+        if arg.is_some() {
+            return Err(GwtuiError::InvalidTemplate(format!(
+                "filter `{name}` takes no argument"
+            )));
+        }
+        Ok(filter)
+    }
+
+    fn apply(self, value: String) -> String {
+        match self {
+            Self::Lower => value.to_lowercase(),
+            Self::Upper => value.to_uppercase(),
+            Self::Slug => {
+                let mut out = String::with_capacity(value.len());
+                for c in value.chars().flat_map(char::to_lowercase) {
+                    if c.is_alphanumeric() {
+                        out.push(c);
+                    } else if !out.is_empty() && !out.ends_with('-') {
+                        out.push('-');
+                    }
+                }
+                out.trim_end_matches('-').to_owned()
+            }
+            Self::Truncate(n) => value.chars().take(n).collect(),
+        }
+    }
+}
+
+#[derive(Debug)]
+enum TemplatePart<'a> {
+    Text(&'a str),
+    Var {
+        name: &'static str,
+        filters: Vec<TemplateFilter>,
+    },
+}
+
+fn parse_template(template: &str) -> Result<Vec<TemplatePart<'_>>, GwtuiError> {
+    let mut parts = Vec::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        if start > 0 {
+            parts.push(TemplatePart::Text(&rest[..start]));
+        }
+        let after = &rest[start + 2..];
+        let Some(end) = after.find("}}") else {
+            return Err(GwtuiError::InvalidTemplate(format!(
+                "unclosed `{{{{` in `{template}`"
+            )));
+        };
+        let mut pieces = after[..end].split('|');
+        let raw_name = pieces.next().unwrap_or_default().trim();
+        let canonical = TEMPLATE_ALIASES
+            .iter()
+            .find(|(alias, _)| *alias == raw_name)
+            .map_or(raw_name, |(_, name)| name);
+        let Some(name) = TEMPLATE_VARIABLES.iter().find(|v| **v == canonical) else {
+            return Err(GwtuiError::InvalidTemplate(format!(
+                "unknown variable `{raw_name}` (expected one of {})",
+                TEMPLATE_VARIABLES.join(", ")
+            )));
+        };
+        let filters = pieces
+            .map(TemplateFilter::parse)
+            .collect::<Result<Vec<_>, _>>()?;
+        parts.push(TemplatePart::Var { name, filters });
+        rest = &after[end + 2..];
+    }
+    if !rest.is_empty() {
+        parts.push(TemplatePart::Text(rest));
+    }
+    Ok(parts)
+}
+
+/// Checks that a template only uses known variables and filters, and returns
+/// the variables it refers to.
+pub fn template_variables(template: &str) -> Result<Vec<&'static str>, GwtuiError> {
+    Ok(parse_template(template)?
+        .into_iter()
+        .filter_map(|part| match part {
+            TemplatePart::Var { name, .. } => Some(name),
+            TemplatePart::Text(_) => None,
+        })
+        .collect())
+}
+
+/// Renders `{{variable}}` and `{{variable|filter|...}}` placeholders, e.g.
+/// `{{repo}}/{{branch_prefix}}/{{branch_leaf|slug|truncate:30}}`.
+pub fn render_template(template: &str, ctx: &TemplateContext) -> Result<String, GwtuiError> {
+    let mut out = String::with_capacity(template.len());
+    for part in parse_template(template)? {
+        match part {
+            TemplatePart::Text(text) => out.push_str(text),
+            TemplatePart::Var { name, filters } => {
+                let value = filters.iter().fold(ctx.get(name), |v, f| f.apply(v));
+                out.push_str(&value);
+            }
+        }
+    }
+    Ok(out)
+}
+
+/// A rendered template as a path relative to `worktree.base_dir`. Empty and
+/// `.` segments are dropped, so a variable that renders empty cannot turn the
+/// path absolute; `..` segments and paths with nothing left are rejected.
+pub fn template_path(rendered: &str) -> Result<std::path::PathBuf, GwtuiError> {
+    let mut path = std::path::PathBuf::new();
+    for seg in rendered.split('/') {
+        match seg {
+            "" | "." => {}
+            ".." => {
+                return Err(GwtuiError::InvalidTemplate(format!(
+                    "rendered path `{rendered}` leaves worktree.base_dir"
+                )));
+            }
+            seg => path.push(seg),
+        }
+    }
+    if path.as_os_str().is_empty() {
+        return Err(GwtuiError::InvalidTemplate(format!(
+            "rendered path `{rendered}` is empty"
+        )));
+    }
+    Ok(path)
+}
+
+/// The pull/merge request number of a branch created by `add --pr` / `--mr`
+/// (`pr/123`, `mr/45`).
 #[must_use]
-pub fn render_template(template: &str, id: &RepoId, branch: &str) -> String {
-    let hash = short_hash(&format!("{}/{}/{}", id.host, id.owner, id.repo), branch);
-
-    // Support both gwtui-style and gwq-style tokens.
-    template
-        .replace("{{host}}", &id.host)
-        .replace("{{owner}}", &id.owner)
-        .replace("{{repo}}", &id.repo)
-        .replace("{{branch}}", branch)
-        .replace("{{hash}}", &hash)
-        .replace("{{.Host}}", &id.host)
-        .replace("{{.Owner}}", &id.owner)
-        .replace("{{.Repository}}", &id.repo)
-        .replace("{{.Branch}}", branch)
-        .replace("{{.Hash}}", &hash)
+pub fn review_number(branch: &str) -> Option<&str> {
+    let (prefix, number) = branch.split_once('/')?;
+    (matches!(prefix, "pr" | "mr")
+        && !number.is_empty()
+        && number.bytes().all(|b| b.is_ascii_digit()))
+    .then_some(number)
 }
 
 #[must_use]
@@ -146,25 +398,102 @@ mod tests {
             owner: "me".to_owned(),
             repo: "repo".to_owned(),
         };
+        let ctx = |branch: &str, pr: Option<&str>| TemplateContext {
+            pr: pr.map(str::to_owned),
+            ..TemplateContext::new(id.clone(), branch)
+        };
         let rendered = render_template(
             "{{host}}/{{owner}}/{{repo}}/{{branch}}-{{hash}}",
-            &id,
-            "feat/x",
-        );
+            &ctx("feat/x", None),
+        )
+        .unwrap();
         assert!(rendered.starts_with("example.com/me/repo/feat/x-"));
         assert_eq!(rendered.split('-').next_back().unwrap().len(), 8);
 
         let rendered = render_template(
             "{{.Host}}/{{.Owner}}/{{.Repository}}/{{.Branch}}-{{.Hash}}",
-            &id,
-            "b",
-        );
+            &ctx("b", None),
+        )
+        .unwrap();
         assert!(rendered.starts_with("example.com/me/repo/b-"));
 
+        let rendered =
+            render_template("{{repo}}/review/{{pr}}", &ctx("pr-42", Some("42"))).unwrap();
+        assert_eq!(rendered, "repo/review/42");
+        let rendered = render_template("{{repo}}/at-{{ref}}", &ctx("v1.2.0", None)).unwrap();
+        assert_eq!(rendered, "repo/at-v1.2.0");
+        assert_eq!(review_number("pr/42"), Some("42"));
+        assert_eq!(review_number("mr/7"), Some("7"));
+        assert_eq!(review_number("pr/x"), None);
+        assert_eq!(review_number("feature/42"), None);
+
         let mut sanitize = BTreeMap::new();
         sanitize.insert("/".to_owned(), "-".to_owned());
         sanitize.insert(":".to_owned(), "_".to_owned());
         let s = sanitize_all("feat/foo:bar".to_owned(), &sanitize);
         assert_eq!(s, "feat-foo_bar");
     }
+
+    #[test]
+    fn renders_extra_variables_and_filters() {
+        let id = RepoId {
+            host: "example.com".to_owned(),
+            owner: "me".to_owned(),
+            repo: "repo".to_owned(),
+        };
+        let mut sanitize = BTreeMap::new();
+        sanitize.insert("/".to_owned(), "-".to_owned());
+        let ctx = TemplateContext {
+            user: "alice".to_owned(),
+            date: "2026-10-16".to_owned(),
+            short_hash: "1a2b3c4".to_owned(),
+            base: "main".to_owned(),
+            ..TemplateContext::new(id, "Feat/UI/Add Dark_Mode!").sanitized(&sanitize)
+        };
+        let render = |t: &str| render_template(t, &ctx).unwrap();
+
+        assert_eq!(
+            render("{{branch_prefix}}/{{branch_leaf}}"),
+            "Feat-UI/Add Dark_Mode!"
+        );
+        assert_eq!(render("{{branch|lower}}"), "feat-ui-add dark_mode!");
+        assert_eq!(render("{{ branch | slug }}"), "feat-ui-add-dark-mode");
+        assert_eq!(render("{{branch|slug|truncate:10}}"), "feat-ui-ad");
+        assert_eq!(
+            render("{{user}}/{{date}}-{{short_hash}}-{{base|upper}}"),
+            "alice/2026-10-16-1a2b3c4-MAIN"
+        );
+        assert_eq!(
+            template_variables("{{repo}}/{{.Branch|lower}}").unwrap(),
+            ["repo", "branch"]
+        );
+
+        for (template, needle) in [
+            ("{{repo}}/{{nope}}", "unknown variable `nope`"),
+            ("{{branch|shout}}", "unknown filter `shout`"),
+            ("{{branch|truncate}}", "positive length"),
+            ("{{branch|truncate:0}}", "positive length"),
+            ("{{branch|lower:2}}", "takes no argument"),
+            ("{{repo}}/{{branch", "unclosed"),
+        ] {
+            let err = render_template(template, &ctx).unwrap_err().to_string();
+            assert!(err.contains(needle), "{template}: {err}");
+        }
+
+        let main = TemplateContext::new(RepoId::default(), "main");
+        let rendered = render_template("{{branch_prefix}}/{{branch_leaf}}", &main).unwrap();
+        assert_eq!(rendered, "/main");
+        assert_eq!(
+            template_path(&rendered).unwrap(),
+            std::path::Path::new("main")
+        );
+        assert_eq!(
+            template_path("repo//./alice/x/").unwrap(),
+            std::path::Path::new("repo/alice/x")
+        );
+        for (rendered, needle) in [("repo/../../etc", "leaves"), ("/./", "is empty")] {
+            let err = template_path(rendered).unwrap_err().to_string();
+            assert!(err.contains(needle), "{rendered}: {err}");
+        }
+    }
 }
@@ -0,0 +1,252 @@
+#![forbid(unsafe_code)]
+
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    pub command: String,
+}
+
+/// A running process together with the filesystem locations it is using.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProcessSnapshot {
+    pub pid: u32,
+    pub command: String,
+    pub cwd: Option<PathBuf>,
+    pub open_files: Vec<PathBuf>,
+}
+
+/// Scans the process table once. Only implemented on Linux (via `/proc`);
+/// other platforms return an empty list.
+#[must_use]
+pub fn scan_processes() -> Vec<ProcessSnapshot> {
+    scan_processes_impl()
+}
+
+#[cfg(target_os = "linux")]
+fn scan_processes_impl() -> Vec<ProcessSnapshot> {
+    let own_pid = std::process::id();
+    let Ok(entries) = std::fs::read_dir("/proc") else {
+        return Vec::new();
+    };
+
+    let mut out = Vec::new();
+    for entry in entries.flatten() {
+        let Some(pid) = entry
+            .file_name()
+            .to_str()
+            .and_then(|s| s.parse::<u32>().ok())
+        else {
+            continue;
+        };
+        if pid == own_pid {
+            continue;
+        }
+        let proc_dir = entry.path();
+
+        let command = read_command(&proc_dir);
+        if command.is_empty() {
+            // Kernel threads have no cmdline and never hold a user cwd.
+            continue;
+        }
+
+        let cwd = std::fs::read_link(proc_dir.join("cwd")).ok();
+        let mut open_files = Vec::new();
+        if let Ok(fds) = std::fs::read_dir(proc_dir.join("fd")) {
+            for fd in fds.flatten() {
+                if let Ok(target) = std::fs::read_link(fd.path())
+                    && target.is_absolute()
+                {
+                    open_files.push(target);
+                }
+            }
+        }
+
+        if cwd.is_none() && open_files.is_empty() {
+            continue;
+        }
+
+        out.push(ProcessSnapshot {
+            pid,
+            command,
+            cwd,
+            open_files,
+        });
+    }
+    out.sort_by_key(|p| p.pid);
+    out
+}
+
+#[cfg(not(target_os = "linux"))]
+fn scan_processes_impl() -> Vec<ProcessSnapshot> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn read_command(proc_dir: &Path) -> String {
+    if let Ok(raw) = std::fs::read(proc_dir.join("cmdline")) {
+        let parts: Vec<String> = raw
+            .split(|b| *b == 0)
+            .filter(|p| !p.is_empty())
+            .map(|p| String::from_utf8_lossy(p).to_string())
+            .collect();
+        if !parts.is_empty() {
+            return parts.join(" ");
+        }
+    }
+    std::fs::read_to_string(proc_dir.join("comm"))
+        .map(|s| s.trim().to_owned())
+        .unwrap_or_default()
+}
+
+/// Assigns each process to the worktree it is working in. A process matches a
+/// worktree when its cwd or any open file lives below the worktree root; when
+/// worktrees are nested, the deepest root wins.
+#[must_use]
+pub fn assign_to_worktrees(
+    processes: &[ProcessSnapshot],
+    worktree_roots: &[PathBuf],
+) -> Vec<Vec<ProcessInfo>> {
+    let roots: Vec<PathBuf> = worktree_roots
+        .iter()
+        .map(|p| std::fs::canonicalize(p).unwrap_or_else(|_| p.clone()))
+        .collect();
+
+    let mut out = vec![Vec::new(); roots.len()];
+    for p in processes {
+        let mut matched: Vec<usize> = Vec::new();
+        let paths = p.cwd.iter().chain(p.open_files.iter());
+        for path in paths {
+            if let Some(idx) = deepest_root(&roots, path)
+                && !matched.contains(&idx)
+            {
+                matched.push(idx);
+            }
+        }
+        for idx in matched {
+            out[idx].push(ProcessInfo {
+                pid: p.pid,
+                command: p.command.clone(),
+            });
+        }
+    }
+    out
+}
+
+fn deepest_root(roots: &[PathBuf], path: &Path) -> Option<usize> {
+    roots
+        .iter()
+        .enumerate()
+        .filter(|(_, root)| path.starts_with(root))
+        .max_by_key(|(_, root)| root.components().count())
+        .map(|(i, _)| i)
+}
+
+/// Short one-line summary, e.g. `node:1234, cargo:5678`.
+#[must_use]
+pub fn format_processes_summary(processes: &[ProcessInfo]) -> String {
+    if processes.is_empty() {
+        return "-".to_owned();
+    }
+    processes
+        .iter()
+        .map(|p| format!("{}:{}", command_name(&p.command), p.pid))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[must_use]
+pub fn command_name(command: &str) -> &str {
+    let argv0 = command.split_whitespace().next().unwrap_or(command);
+    Path::new(argv0)
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or(argv0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(pid: u32, command: &str, cwd: &str, files: &[&str]) -> ProcessSnapshot {
+        ProcessSnapshot {
+            pid,
+            command: command.to_owned(),
+            cwd: Some(PathBuf::from(cwd)),
+            open_files: files.iter().map(PathBuf::from).collect(),
+        }
+    }
+
+    #[test]
+    fn assigns_processes_to_deepest_worktree() {
+        let roots = vec![
+            PathBuf::from("/nonexistent/repo"),
+            PathBuf::from("/nonexistent/repo/.worktrees/feature"),
+            PathBuf::from("/nonexistent/other"),
+        ];
+        let procs = vec![
+            snapshot(10, "/usr/bin/node server.js", "/nonexistent/repo/web", &[]),
+            snapshot(
+                11,
+                "cargo watch",
+                "/nonexistent/repo/.worktrees/feature/src",
+                &[],
+            ),
+            snapshot(
+                12,
+                "vim notes.md",
+                "/home/me",
+                &["/nonexistent/other/notes.md"],
+            ),
+            snapshot(13, "bash", "/tmp", &[]),
+        ];
+
+        let assigned = assign_to_worktrees(&procs, &roots);
+        assert_eq!(assigned[0].len(), 1);
+        assert_eq!(assigned[0][0].pid, 10);
+        assert_eq!(assigned[1].len(), 1);
+        assert_eq!(assigned[1][0].pid, 11);
+        assert_eq!(assigned[2].len(), 1);
+        assert_eq!(assigned[2][0].pid, 12);
+    }
+
+    #[test]
+    fn formats_process_summary() {
+        let procs = vec![
+            ProcessInfo {
+                pid: 1,
+                command: "/usr/bin/node server.js".to_owned(),
+            },
+            ProcessInfo {
+                pid: 2,
+                command: "cargo watch -x test".to_owned(),
+            },
+        ];
+        assert_eq!(format_processes_summary(&procs), "node:1, cargo:2");
+        assert_eq!(format_processes_summary(&[]), "-");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn scan_finds_process_running_in_directory() {
+        let td = tempfile::tempdir().expect("tempdir");
+        // Without `sleep` there is no process to look for.
+        let Ok(mut child) = std::process::Command::new("sleep")
+            .arg("30")
+            .current_dir(td.path())
+            .spawn()
+        else {
+            return;
+        };
+
+        let procs = scan_processes();
+        let assigned = assign_to_worktrees(&procs, &[td.path().to_path_buf()]);
+        let _ = child.kill();
+        let _ = child.wait();
+
+        assert!(assigned[0].iter().any(|p| p.pid == child.id()));
+    }
+}
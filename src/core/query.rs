@@ -0,0 +1,703 @@
+#![forbid(unsafe_code)]
+
+use regex::Regex;
+use time::OffsetDateTime;
+
+use crate::core::status::{self, WorktreeState, WorktreeStatus};
+use crate::error::GwtuiError;
+
+/// A parsed status filter, as accepted by `status --filter`, the TUI `/`
+/// filter and `get`/`remove --filter`.
+///
+/// Terms are bare words (`modified`, `stale`, `in-progress` and the other
+/// state names, `locked`, `detached`, `current`, `all`, otherwise a substring
+/// of the branch, repository or path), `field:value` for `state`, `branch`,
+/// `repo` and `path` (`field:~regex` matches a case-insensitive regex),
+/// counts such as `ahead>0` or `behind>=5`, and `activity<2d`. Terms are
+/// combined with `AND` (or just juxtaposition), `OR`, `NOT`/`!` and
+/// parentheses; values containing spaces or parentheses can be quoted.
+#[derive(Debug, Clone)]
+pub struct StatusQuery {
+    expr: Option<Expr>,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Term(Term),
+}
+
+#[derive(Debug, Clone)]
+enum Term {
+    All,
+    State(Vec<WorktreeState>),
+    Locked,
+    Detached,
+    Current,
+    Text(TextField, TextPattern),
+    Count(CountField, CmpOp, u32),
+    /// Age of the last activity in seconds; unknown activity never matches.
+    Activity(CmpOp, i64),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum TextField {
+    Branch,
+    Repo,
+    Path,
+    /// Bare words: branch, repository or path.
+    Any,
+}
+
+#[derive(Debug, Clone)]
+enum TextPattern {
+    /// Lowercased needle.
+    Substring(String),
+    Regex(Regex),
+}
+
+#[derive(Debug, Clone, Copy)]
+enum CountField {
+    Ahead,
+    Behind,
+    BaseAhead,
+    BaseBehind,
+    Changes,
+    Staged,
+    Untracked,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl CmpOp {
+    fn holds<T: Ord>(self, lhs: T, rhs: T) -> bool {
+        match self {
+            Self::Lt => lhs < rhs,
+            Self::Le => lhs <= rhs,
+            Self::Gt => lhs > rhs,
+            Self::Ge => lhs >= rhs,
+            Self::Eq => lhs == rhs,
+            Self::Ne => lhs != rhs,
+        }
+    }
+}
+
+const TEXT_FIELDS: &str = "state, branch, repo or path";
+const COMPARABLE_FIELDS: &str =
+    "ahead, behind, base-ahead, base-behind, changes, staged, untracked or activity";
+
+impl StatusQuery {
+    /// Parses a filter. An empty filter (or `all`) matches every worktree.
+    pub fn parse(input: &str) -> Result<Self, GwtuiError> {
+        // The table label, which predates the query syntax.
+        if input.trim().eq_ignore_ascii_case("up to date") {
+            return Ok(Self {
+                expr: Some(Expr::Term(Term::State(vec![WorktreeState::Clean]))),
+            });
+        }
+        let tokens = lex(input)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            end_col: input.chars().count() + 1,
+        };
+        if parser.tokens.is_empty() {
+            return Ok(Self { expr: None });
+        }
+        let expr = parser.parse_or()?;
+        if let Some(tok) = parser.peek() {
+            return Err(query_error(tok.col, format!("unexpected {}", tok.kind)));
+        }
+        Ok(Self { expr: Some(expr) })
+    }
+
+    #[must_use]
+    pub fn matches(&self, s: &WorktreeStatus) -> bool {
+        self.matches_at(s, OffsetDateTime::now_utc())
+    }
+
+    fn matches_at(&self, s: &WorktreeStatus, now: OffsetDateTime) -> bool {
+        self.expr.as_ref().is_none_or(|e| e.eval(s, now))
+    }
+}
+
+impl Expr {
+    fn eval(&self, s: &WorktreeStatus, now: OffsetDateTime) -> bool {
+        match self {
+            Self::And(a, b) => a.eval(s, now) && b.eval(s, now),
+            Self::Or(a, b) => a.eval(s, now) || b.eval(s, now),
+            Self::Not(e) => !e.eval(s, now),
+            Self::Term(t) => t.eval(s, now),
+        }
+    }
+}
+
+impl Term {
+    fn eval(&self, s: &WorktreeStatus, now: OffsetDateTime) -> bool {
+        match self {
+            Self::All => true,
+            Self::State(states) => states.contains(&s.status),
+            Self::Locked => s.is_locked,
+            Self::Detached => s.is_detached,
+            Self::Current => s.is_current,
+            Self::Text(field, pattern) => match field {
+                TextField::Branch => pattern.is_match(&s.branch),
+                TextField::Repo => pattern.is_match(&s.repository),
+                TextField::Path => pattern.is_match(&s.path),
+                TextField::Any => {
+                    pattern.is_match(&s.branch)
+                        || pattern.is_match(&s.repository)
+                        || pattern.is_match(&s.path)
+                }
+            },
+            Self::Count(field, op, value) => {
+                let gs = &s.git_status;
+                let actual = match field {
+                    CountField::Ahead => gs.ahead,
+                    CountField::Behind => gs.behind,
+                    CountField::BaseAhead => gs.base_ahead,
+                    CountField::BaseBehind => gs.base_behind,
+                    CountField::Changes => status::count_total_changes(gs),
+                    CountField::Staged => gs.staged,
+                    CountField::Untracked => gs.untracked,
+                };
+                op.holds(actual, *value)
+            }
+            Self::Activity(op, secs) => OffsetDateTime::parse(
+                &s.last_activity,
+                &time::format_description::well_known::Rfc3339,
+            )
+            .is_ok_and(|t| op.holds((now - t).whole_seconds(), *secs)),
+        }
+    }
+}
+
+impl TextPattern {
+    fn is_match(&self, haystack: &str) -> bool {
+        match self {
+            Self::Substring(needle) => haystack.to_lowercase().contains(needle),
+            Self::Regex(re) => re.is_match(haystack),
+        }
+    }
+}
+
+/// State names accepted as bare words and by `state:`.
+fn state_keyword(word: &str) -> Option<Vec<WorktreeState>> {
+    let state = match word {
+        "modified" | "changed" => WorktreeState::Modified,
+        "clean" | "up-to-date" | "up to date" => WorktreeState::Clean,
+        "stale" | "inactive" => WorktreeState::Stale,
+        "staged" => WorktreeState::Staged,
+        "conflict" | "conflicted" => WorktreeState::Conflict,
+        "rebase" | "rebasing" => WorktreeState::Rebasing,
+        "merge" | "merging" => WorktreeState::Merging,
+        "cherry-pick" | "cherry-picking" => WorktreeState::CherryPicking,
+        "revert" | "reverting" => WorktreeState::Reverting,
+        "bisect" | "bisecting" => WorktreeState::Bisecting,
+        "unknown" => WorktreeState::Unknown,
+        "in-progress" | "operation" => {
+            return Some(vec![
+                WorktreeState::Rebasing,
+                WorktreeState::Merging,
+                WorktreeState::CherryPicking,
+                WorktreeState::Reverting,
+                WorktreeState::Bisecting,
+            ]);
+        }
+        _ => return None,
+    };
+    Some(vec![state])
+}
+
+fn count_field(name: &str) -> Option<CountField> {
+    Some(match name {
+        "ahead" => CountField::Ahead,
+        "behind" => CountField::Behind,
+        "base-ahead" | "base_ahead" => CountField::BaseAhead,
+        "base-behind" | "base_behind" => CountField::BaseBehind,
+        "changes" => CountField::Changes,
+        "staged" => CountField::Staged,
+        "untracked" => CountField::Untracked,
+        _ => return None,
+    })
+}
+
+fn is_comparable(name: &str) -> bool {
+    count_field(name).is_some() || name == "activity"
+}
+
+fn is_op_char(c: char) -> bool {
+    matches!(c, '<' | '>' | '=' | '!')
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Word { text: String, quoted: bool },
+}
+
+impl std::fmt::Display for TokenKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::LParen => f.write_str("'('"),
+            Self::RParen => f.write_str("')'"),
+            Self::And => f.write_str("AND"),
+            Self::Or => f.write_str("OR"),
+            Self::Not => f.write_str("NOT"),
+            Self::Word { text, .. } => write!(f, "'{text}'"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    /// 1-based character column, for error messages.
+    col: usize,
+}
+
+fn lex(input: &str) -> Result<Vec<Token>, GwtuiError> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let col = i + 1;
+        let next = chars.get(i + 1).copied();
+        let kind = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => TokenKind::LParen,
+            ')' => TokenKind::RParen,
+            '&' | '|' => {
+                if next == Some(c) {
+                    i += 1;
+                }
+                if c == '&' {
+                    TokenKind::And
+                } else {
+                    TokenKind::Or
+                }
+            }
+            '!' if next != Some('=') => TokenKind::Not,
+            _ => {
+                let mut text = String::new();
+                // Only a fully quoted word escapes keywords and operators.
+                let quoted = c == '"';
+                while let Some(&c) = chars.get(i) {
+                    if c.is_whitespace() || c == '(' || c == ')' {
+                        break;
+                    }
+                    if c == '"' {
+                        let Some(end) = chars[i + 1..].iter().position(|c| *c == '"') else {
+                            return Err(query_error(i + 1, "unterminated quote".to_owned()));
+                        };
+                        text.extend(&chars[i + 1..i + 1 + end]);
+                        i += end + 2;
+                        continue;
+                    }
+                    text.push(c);
+                    i += 1;
+                }
+                let kind = match text.to_ascii_uppercase().as_str() {
+                    "AND" if !quoted => TokenKind::And,
+                    "OR" if !quoted => TokenKind::Or,
+                    "NOT" if !quoted => TokenKind::Not,
+                    _ => TokenKind::Word { text, quoted },
+                };
+                tokens.push(Token { kind, col });
+                continue;
+            }
+        };
+        tokens.push(Token { kind, col });
+        i += 1;
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Column just past the input, for errors about a missing term.
+    end_col: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let tok = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        tok
+    }
+
+    fn parse_or(&mut self) -> Result<Expr, GwtuiError> {
+        let mut lhs = self.parse_and()?;
+        while self.peek().is_some_and(|t| t.kind == TokenKind::Or) {
+            self.pos += 1;
+            let rhs = self.parse_and()?;
+            lhs = Expr::Or(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr, GwtuiError> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            match self.peek().map(|t| &t.kind) {
+                Some(TokenKind::And) => self.pos += 1,
+                Some(TokenKind::Word { .. } | TokenKind::Not | TokenKind::LParen) => {}
+                _ => break,
+            }
+            let rhs = self.parse_unary()?;
+            lhs = Expr::And(Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr, GwtuiError> {
+        if self.peek().is_some_and(|t| t.kind == TokenKind::Not) {
+            self.pos += 1;
+            return Ok(Expr::Not(Box::new(self.parse_unary()?)));
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr, GwtuiError> {
+        let Some(tok) = self.next() else {
+            return Err(query_error(self.end_col, "expected a term".to_owned()));
+        };
+        match tok.kind {
+            TokenKind::LParen => {
+                if self.peek().is_some_and(|t| t.kind == TokenKind::RParen) {
+                    return Err(query_error(tok.col, "empty parentheses".to_owned()));
+                }
+                let inner = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::RParen,
+                        ..
+                    }) => Ok(inner),
+                    _ => Err(query_error(tok.col, "missing ')' for this '('".to_owned())),
+                }
+            }
+            TokenKind::Word { text, quoted } => {
+                let text = if quoted {
+                    text
+                } else {
+                    self.join_comparison(text)
+                };
+                parse_term(&text, quoted, tok.col).map(Expr::Term)
+            }
+            kind => Err(query_error(
+                tok.col,
+                format!("expected a term, found {kind}"),
+            )),
+        }
+    }
+
+    /// Glues `ahead > 0` and `ahead >0` back into `ahead>0`.
+    fn join_comparison(&mut self, mut text: String) -> String {
+        loop {
+            let Some(Token {
+                kind: TokenKind::Word { text: next, .. },
+                ..
+            }) = self.peek()
+            else {
+                return text;
+            };
+            let wants_op = is_comparable(&text.to_lowercase()) && next.starts_with(is_op_char);
+            let wants_value = text.ends_with(is_op_char);
+            if !(wants_op || wants_value) {
+                return text;
+            }
+            text.push_str(next);
+            self.pos += 1;
+        }
+    }
+}
+
+fn parse_term(text: &str, quoted: bool, col: usize) -> Result<Term, GwtuiError> {
+    let special = if quoted {
+        None
+    } else {
+        text.find(|c| c == ':' || is_op_char(c))
+    };
+    let Some(at) = special else {
+        let word = text.to_lowercase();
+        if !quoted {
+            match word.as_str() {
+                "all" => return Ok(Term::All),
+                "locked" => return Ok(Term::Locked),
+                "detached" => return Ok(Term::Detached),
+                "current" => return Ok(Term::Current),
+                _ => {}
+            }
+            if let Some(states) = state_keyword(&word) {
+                return Ok(Term::State(states));
+            }
+        }
+        return Ok(Term::Text(TextField::Any, TextPattern::Substring(word)));
+    };
+
+    let field = text[..at].to_lowercase();
+    if text[at..].starts_with(':') {
+        let value = &text[at + 1..];
+        let value_col = col + text[..=at].chars().count();
+        if value.is_empty() {
+            return Err(query_error(
+                value_col,
+                format!("missing value after '{field}:'"),
+            ));
+        }
+        let field = match field.as_str() {
+            "state" | "status" => {
+                let word = value.to_lowercase();
+                return state_keyword(&word)
+                    .map(Term::State)
+                    .ok_or_else(|| query_error(value_col, format!("unknown state '{value}'")));
+            }
+            "branch" => TextField::Branch,
+            "repo" | "repository" => TextField::Repo,
+            "path" => TextField::Path,
+            _ => {
+                return Err(query_error(
+                    col,
+                    format!("unknown field '{field}' (expected {TEXT_FIELDS})"),
+                ));
+            }
+        };
+        let pattern = match value.strip_prefix('~') {
+            Some(re) => TextPattern::Regex(
+                Regex::new(&format!("(?i){re}"))
+                    .map_err(|e| query_error(value_col, format!("invalid regex '{re}': {e}")))?,
+            ),
+            None => TextPattern::Substring(value.to_lowercase()),
+        };
+        return Ok(Term::Text(field, pattern));
+    }
+
+    let rest = &text[at..];
+    let (op, len) = [
+        (">=", CmpOp::Ge),
+        ("<=", CmpOp::Le),
+        ("!=", CmpOp::Ne),
+        ("==", CmpOp::Eq),
+        (">", CmpOp::Gt),
+        ("<", CmpOp::Lt),
+        ("=", CmpOp::Eq),
+    ]
+    .into_iter()
+    .find(|(sym, _)| rest.starts_with(sym))
+    .map(|(sym, op)| (op, sym.len()))
+    .ok_or_else(|| {
+        query_error(
+            col + text[..at].chars().count(),
+            "invalid operator".to_owned(),
+        )
+    })?;
+    let value = &rest[len..];
+    let value_col = col + text[..at + len].chars().count();
+    if field.is_empty() {
+        return Err(query_error(
+            col,
+            "missing field before comparison".to_owned(),
+        ));
+    }
+    if value.is_empty() {
+        return Err(query_error(
+            value_col,
+            format!("missing value after '{}'", &text[..at + len]),
+        ));
+    }
+    if field == "activity" {
+        let secs = parse_age(value).ok_or_else(|| {
+            query_error(
+                value_col,
+                format!("invalid duration '{value}' (use e.g. 30m, 12h, 2d or 1w)"),
+            )
+        })?;
+        return Ok(Term::Activity(op, secs));
+    }
+    let Some(count) = count_field(&field) else {
+        return Err(query_error(
+            col,
+            format!("'{field}' cannot be compared (expected {COMPARABLE_FIELDS})"),
+        ));
+    };
+    let value: u32 = value
+        .parse()
+        .map_err(|_| query_error(value_col, format!("expected a number, found '{value}'")))?;
+    Ok(Term::Count(count, op, value))
+}
+
+/// `90s`, `30m`, `12h`, `2d`, `1w` in seconds; a bare number means days.
+fn parse_age(value: &str) -> Option<i64> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let n: i64 = value[..split].parse().ok()?;
+    let unit = match &value[split..] {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    n.checked_mul(unit)
+}
+
+fn query_error(column: usize, msg: String) -> GwtuiError {
+    GwtuiError::InvalidQuery { column, msg }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::status::GitStatus;
+
+    fn wt(
+        branch: &str,
+        repo: &str,
+        status: WorktreeState,
+        ahead: u32,
+        activity: &str,
+    ) -> WorktreeStatus {
+        WorktreeStatus {
+            path: format!("/src/{repo}/{branch}"),
+            branch: branch.to_owned(),
+            repository: repo.to_owned(),
+            status,
+            git_status: GitStatus {
+                ahead,
+                behind: ahead * 2,
+                ..GitStatus::default()
+            },
+            last_activity: activity.to_owned(),
+            is_current: false,
+            is_locked: branch == "release",
+            lock_reason: None,
+            processes: Vec::new(),
+            is_detached: false,
+            upstream: None,
+            base: None,
+            last_fetch: None,
+            fetch_error: None,
+        }
+    }
+
+    fn select(query: &str, rows: &[WorktreeStatus]) -> Vec<String> {
+        let now = OffsetDateTime::parse(
+            "2026-03-10T12:00:00Z",
+            &time::format_description::well_known::Rfc3339,
+        )
+        .expect("timestamp");
+        let q = StatusQuery::parse(query).expect("valid query");
+        rows.iter()
+            .filter(|s| q.matches_at(s, now))
+            .map(|s| s.branch.clone())
+            .collect()
+    }
+
+    #[test]
+    fn evaluates_terms_and_boolean_operators() {
+        let rows = vec![
+            wt(
+                "feat/login",
+                "api",
+                WorktreeState::Modified,
+                3,
+                "2026-03-10T10:00:00Z",
+            ),
+            wt(
+                "fix/crash",
+                "api",
+                WorktreeState::Clean,
+                0,
+                "2026-02-01T00:00:00Z",
+            ),
+            wt("feat/theme", "web", WorktreeState::Stale, 6, "unknown"),
+            wt(
+                "release",
+                "web",
+                WorktreeState::Rebasing,
+                0,
+                "2026-03-09T12:00:00Z",
+            ),
+        ];
+
+        assert_eq!(select("", &rows).len(), 4);
+        assert_eq!(select("all", &rows).len(), 4);
+        assert_eq!(select("modified", &rows), ["feat/login"]);
+        assert_eq!(select("state:in-progress", &rows), ["release"]);
+        assert_eq!(select("repo:api ahead>0", &rows), ["feat/login"]);
+        assert_eq!(select("behind>=12", &rows), ["feat/theme"]);
+        assert_eq!(select("ahead >= 3 AND ahead != 6", &rows), ["feat/login"]);
+        assert_eq!(
+            select("branch:~^FEAT/", &rows),
+            ["feat/login", "feat/theme"]
+        );
+        assert_eq!(select("activity<2d", &rows), ["feat/login", "release"]);
+        assert_eq!(select("activity>1w", &rows), ["fix/crash"]);
+        assert_eq!(select("stale OR locked", &rows), ["feat/theme", "release"]);
+        assert_eq!(select("NOT (repo:web || clean)", &rows), ["feat/login"]);
+        assert_eq!(select("!feat & !locked", &rows), ["fix/crash"]);
+        assert_eq!(select("crash", &rows), ["fix/crash"]);
+        // Count and activity names without a comparison are plain words.
+        let named = [wt(
+            "activity-log",
+            "changes",
+            WorktreeState::Clean,
+            0,
+            "unknown",
+        )];
+        assert_eq!(select("activity", &named), ["activity-log"]);
+        assert_eq!(select("changes", &named), ["activity-log"]);
+        assert!(select("ahead", &named).is_empty());
+        assert_eq!(
+            select("path:\"/src/web\"", &rows),
+            ["feat/theme", "release"]
+        );
+    }
+
+    #[test]
+    fn reports_parse_errors_with_columns() {
+        let err = |q: &str| StatusQuery::parse(q).expect_err(q).to_string();
+        assert_eq!(
+            err("(stale"),
+            "invalid filter at column 1: missing ')' for this '('"
+        );
+        assert_eq!(err("stale )"), "invalid filter at column 7: unexpected ')'");
+        assert_eq!(
+            err("stale AND"),
+            "invalid filter at column 10: expected a term"
+        );
+        assert!(err("owner:me").contains("unknown field 'owner'"));
+        assert!(err("state:happy").contains("column 7: unknown state 'happy'"));
+        assert!(err("ahead>x").contains("column 7: expected a number"));
+        assert!(err("activity<2y").contains("invalid duration '2y'"));
+        assert!(err("branch>2").contains("'branch' cannot be compared"));
+        assert!(err("branch:~\"(\"").contains("invalid regex"));
+        assert!(err("OR stale").contains("expected a term, found OR"));
+        assert!(err("\"open").contains("unterminated quote"));
+    }
+}
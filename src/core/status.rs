@@ -1,18 +1,22 @@
 #![forbid(unsafe_code)]
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
 use std::time::{Duration, SystemTime};
 
 use serde::{Deserialize, Serialize};
 use tokio::sync::Semaphore;
 
-use crate::config;
+use crate::config::{self, ActivityMode};
 use crate::core::git::Git;
+use crate::core::process::{self, ProcessInfo};
+use crate::core::query::StatusQuery;
 use crate::core::worktree::Worktree;
 use crate::error::GwtuiError;
 
 #[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "kebab-case")]
 pub enum WorktreeState {
     Clean,
     Modified,
@@ -20,9 +24,32 @@ pub enum WorktreeState {
     Conflict,
     Stale,
     Unknown,
+    /// `git rebase` (or `git am`) stopped partway.
+    Rebasing,
+    Merging,
+    CherryPicking,
+    Reverting,
+    Bisecting,
+}
+
+impl WorktreeState {
+    /// Whether a rebase, merge, cherry-pick, revert or bisect is unfinished.
+    #[must_use]
+    pub fn is_in_progress(self) -> bool {
+        matches!(
+            self,
+            WorktreeState::Rebasing
+                | WorktreeState::Merging
+                | WorktreeState::CherryPicking
+                | WorktreeState::Reverting
+                | WorktreeState::Bisecting
+        )
+    }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+/// Change counters count files, so a file modified in both the index and the
+/// worktree counts once; `staged` counts files with any index-side change.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 pub struct GitStatus {
     pub modified: u32,
     pub added: u32,
@@ -32,6 +59,86 @@ pub struct GitStatus {
     pub ahead: u32,
     pub behind: u32,
     pub conflicts: u32,
+    /// Divergence from the repository's default branch (see `WorktreeStatus::base`).
+    #[serde(default)]
+    pub base_ahead: u32,
+    #[serde(default)]
+    pub base_behind: u32,
+    #[serde(default)]
+    pub renamed: u32,
+    #[serde(default)]
+    pub copied: u32,
+    #[serde(default)]
+    pub typechange: u32,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub files: Vec<FileChange>,
+}
+
+/// State of one side (index or worktree) of a changed path.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FileState {
+    Unmodified,
+    Modified,
+    Added,
+    Deleted,
+    Renamed,
+    Copied,
+    TypeChange,
+    Unmerged,
+    Untracked,
+}
+
+impl FileState {
+    fn from_code(code: u8) -> Self {
+        match code {
+            b'M' => FileState::Modified,
+            b'A' => FileState::Added,
+            b'D' => FileState::Deleted,
+            b'R' => FileState::Renamed,
+            b'C' => FileState::Copied,
+            b'T' => FileState::TypeChange,
+            b'U' => FileState::Unmerged,
+            b'?' => FileState::Untracked,
+            _ => FileState::Unmodified,
+        }
+    }
+
+    /// Git's one-letter status code (`.` for unmodified).
+    #[must_use]
+    pub fn code(self) -> char {
+        match self {
+            FileState::Unmodified => '.',
+            FileState::Modified => 'M',
+            FileState::Added => 'A',
+            FileState::Deleted => 'D',
+            FileState::Renamed => 'R',
+            FileState::Copied => 'C',
+            FileState::TypeChange => 'T',
+            FileState::Unmerged => 'U',
+            FileState::Untracked => '?',
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct FileChange {
+    pub path: String,
+    pub index: FileState,
+    pub worktree: FileState,
+    /// Source path of a rename or copy.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub original_path: Option<String>,
+    #[serde(default)]
+    pub conflict: bool,
+}
+
+impl FileChange {
+    /// `XY` code as in `git status --short`, e.g. `R.` or `.M`.
+    #[must_use]
+    pub fn short_code(&self) -> String {
+        format!("{}{}", self.index.code(), self.worktree.code())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -43,15 +150,43 @@ pub struct WorktreeStatus {
     pub git_status: GitStatus,
     pub last_activity: String,
     pub is_current: bool,
+    #[serde(default)]
+    pub is_locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    #[serde(default)]
+    pub processes: Vec<ProcessInfo>,
+    #[serde(default)]
+    pub is_detached: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub upstream: Option<String>,
+    /// Default branch the worktree is compared against, e.g. `origin/main`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base: Option<String>,
+    /// When the worktree's repository was last fetched (RFC 3339).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_fetch: Option<String>,
+    /// Error from the most recent fetch of the worktree's repository.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fetch_error: Option<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct StatusCollectorOptions {
     pub include_process: bool,
+    /// Fetch each repository (at most once per `fetch_interval`) and report
+    /// ahead/behind against the upstream.
     pub fetch_remote: bool,
+    /// Report ahead/behind against the repository's default branch, using
+    /// whatever refs are present locally when `fetch_remote` is off.
+    pub include_base: bool,
+    pub fetch_interval: Duration,
     pub stale_threshold: Duration,
     pub base_dir: String,
     pub concurrency: usize,
+    pub activity: ActivityMode,
+    /// Directory for the activity cache; `None` disables it.
+    pub cache_dir: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -59,6 +194,78 @@ pub struct StatusCollector {
     opts: StatusCollectorOptions,
 }
 
+/// Outcome of the last fetch attempted in this process, keyed by common git dir.
+#[derive(Debug, Clone)]
+struct FetchRecord {
+    at: SystemTime,
+    error: Option<String>,
+}
+
+static FETCHES: LazyLock<Mutex<HashMap<PathBuf, FetchRecord>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Newest directory mtime below a worktree, remembered together with the
+/// index mtime it was computed at. The walk is only repeated once the index
+/// changes (commit, checkout, add); edits in between show up through the
+/// changed-file list instead.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+struct ActivityCacheEntry {
+    index_mtime: SystemTime,
+    newest_dir: SystemTime,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ActivityCache {
+    entries: HashMap<String, ActivityCacheEntry>,
+    #[serde(skip)]
+    dirty: bool,
+}
+
+/// Activity cache file name inside `status.cache_dir`.
+pub(crate) const ACTIVITY_CACHE_FILE: &str = "activity.json";
+
+impl ActivityCache {
+    const FILE: &'static str = ACTIVITY_CACHE_FILE;
+
+    fn lock(cache: &Mutex<Self>) -> std::sync::MutexGuard<'_, Self> {
+        cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn load(dir: &Path) -> Self {
+        std::fs::read(dir.join(Self::FILE))
+            .ok()
+            .and_then(|raw| serde_json::from_slice(&raw).ok())
+            .unwrap_or_default()
+    }
+
+    /// Drops entries of worktrees that are neither in `current` nor on disk,
+    /// so removed worktrees do not pile up. Incremental refreshes collect only
+    /// some worktrees, so entries of the others are kept while they exist.
+    fn prune(&mut self, current: &[Worktree]) {
+        let before = self.entries.len();
+        self.entries.retain(|path, _| {
+            current.iter().any(|wt| wt.path == *path) || Path::new(path).is_dir()
+        });
+        self.dirty |= self.entries.len() != before;
+    }
+
+    /// Best effort: a cache that cannot be written only costs speed.
+    fn save(&self, dir: &Path) {
+        if !self.dirty || std::fs::create_dir_all(dir).is_err() {
+            return;
+        }
+        let Ok(raw) = serde_json::to_vec(self) else {
+            return;
+        };
+        let tmp = dir.join(format!("{}.{}.tmp", Self::FILE, std::process::id()));
+        if std::fs::write(&tmp, raw).is_ok() {
+            let _ = std::fs::rename(&tmp, dir.join(Self::FILE));
+        }
+    }
+}
+
 impl StatusCollector {
     #[must_use]
     pub fn new(opts: StatusCollectorOptions) -> Self {
@@ -71,19 +278,54 @@ impl StatusCollector {
     ) -> Result<Vec<WorktreeStatus>, GwtuiError> {
         let current_path = std::env::current_dir().unwrap_or_else(|_| PathBuf::from("."));
 
+        // Scan the process table once up front instead of per worktree.
+        let process_scan = if self.opts.include_process {
+            tokio::task::spawn_blocking(process::scan_processes)
+                .await
+                .map_err(|e| GwtuiError::Other(format!("process scan join error: {e}")))?
+        } else {
+            Vec::new()
+        };
+
         let sem = std::sync::Arc::new(Semaphore::new(self.opts.concurrency.max(1)));
+
+        let repos: Vec<Option<PathBuf>> = worktrees
+            .iter()
+            .map(|wt| worktree_git_dir(Path::new(&wt.path)).map(|d| common_git_dir(&d)))
+            .collect();
+        if self.opts.fetch_remote {
+            self.fetch_repositories(worktrees, &repos, &sem).await?;
+        }
+        let bases = if self.opts.include_base {
+            self.base_branches(worktrees, &repos, &sem).await?
+        } else {
+            vec![None; worktrees.len()]
+        };
+
+        let cache = match (&self.opts.activity, &self.opts.cache_dir) {
+            (ActivityMode::Fast, Some(dir)) => {
+                let dir = dir.clone();
+                let cache = tokio::task::spawn_blocking(move || ActivityCache::load(&dir))
+                    .await
+                    .map_err(|e| GwtuiError::Other(format!("status task join error: {e}")))?;
+                Some(std::sync::Arc::new(Mutex::new(cache)))
+            }
+            _ => None,
+        };
+
         let mut handles = Vec::with_capacity(worktrees.len());
 
-        for wt in worktrees {
+        for (wt, base) in worktrees.iter().zip(bases) {
             let wt = wt.clone();
             let permit = sem.clone().acquire_owned().await.map_err(|_| {
                 GwtuiError::Other("failed to acquire status collector semaphore".to_owned())
             })?;
             let opts = self.opts.clone();
             let current_path = current_path.clone();
+            let cache = cache.clone();
             handles.push(tokio::task::spawn_blocking(move || {
                 let _permit = permit;
-                collect_one_blocking(&opts, &wt, &current_path)
+                collect_one_blocking(&opts, &wt, base, &current_path, cache.as_deref())
             }));
         }
 
@@ -95,36 +337,210 @@ impl StatusCollector {
             }
         }
 
+        if let (Some(cache), Some(dir)) = (cache, self.opts.cache_dir.clone()) {
+            let current = worktrees.to_vec();
+            tokio::task::spawn_blocking(move || {
+                let mut cache = ActivityCache::lock(&cache);
+                cache.prune(&current);
+                cache.save(&dir);
+            })
+            .await
+            .map_err(|e| GwtuiError::Other(format!("status task join error: {e}")))?;
+        }
+
+        if self.opts.fetch_remote {
+            for (status, repo) in statuses.iter_mut().zip(&repos) {
+                let Some(repo) = repo else { continue };
+                let (last, error) = last_fetch(repo);
+                status.last_fetch = last.and_then(format_rfc3339);
+                status.fetch_error = error;
+            }
+        }
+
+        if self.opts.include_process {
+            let roots: Vec<PathBuf> = statuses.iter().map(|s| PathBuf::from(&s.path)).collect();
+            let assigned = process::assign_to_worktrees(&process_scan, &roots);
+            for (status, procs) in statuses.iter_mut().zip(assigned) {
+                status.processes = procs;
+            }
+        }
+
         Ok(statuses)
     }
+
+    /// Default branch of each worktree's repository, detected once per repository.
+    async fn base_branches(
+        &self,
+        worktrees: &[Worktree],
+        repos: &[Option<PathBuf>],
+        sem: &std::sync::Arc<Semaphore>,
+    ) -> Result<Vec<Option<String>>, GwtuiError> {
+        let mut handles: Vec<(&PathBuf, _)> = Vec::new();
+        for (wt, repo) in worktrees.iter().zip(repos) {
+            let Some(repo) = repo else { continue };
+            if handles.iter().any(|(r, _)| *r == repo) {
+                continue;
+            }
+            let permit = sem.clone().acquire_owned().await.map_err(|_| {
+                GwtuiError::Other("failed to acquire status collector semaphore".to_owned())
+            })?;
+            let git = Git::new(PathBuf::from(&wt.path));
+            handles.push((
+                repo,
+                tokio::task::spawn_blocking(move || {
+                    let _permit = permit;
+                    git.default_branch().ok()
+                }),
+            ));
+        }
+
+        let mut detected: Vec<(&PathBuf, Option<String>)> = Vec::with_capacity(handles.len());
+        for (repo, h) in handles {
+            let base = h
+                .await
+                .map_err(|e| GwtuiError::Other(format!("status task join error: {e}")))?;
+            detected.push((repo, base));
+        }
+        Ok(repos
+            .iter()
+            .map(|repo| {
+                let repo = repo.as_ref()?;
+                detected
+                    .iter()
+                    .find(|(r, _)| *r == repo)
+                    .and_then(|(_, base)| base.clone())
+            })
+            .collect())
+    }
+
+    /// Fetches every distinct repository behind `worktrees` once, skipping
+    /// repositories fetched less than `fetch_interval` ago. Failures are
+    /// recorded for the rows rather than returned.
+    async fn fetch_repositories(
+        &self,
+        worktrees: &[Worktree],
+        repos: &[Option<PathBuf>],
+        sem: &std::sync::Arc<Semaphore>,
+    ) -> Result<(), GwtuiError> {
+        let now = SystemTime::now();
+        let mut seen: Vec<&PathBuf> = Vec::new();
+        let mut handles = Vec::new();
+        for (wt, repo) in worktrees.iter().zip(repos) {
+            let Some(repo) = repo else { continue };
+            if seen.contains(&repo) {
+                continue;
+            }
+            seen.push(repo);
+            let due = last_fetch(repo).0.is_none_or(|last| {
+                now.duration_since(last)
+                    .is_ok_and(|age| age >= self.opts.fetch_interval)
+            });
+            if !due {
+                continue;
+            }
+
+            let permit = sem.clone().acquire_owned().await.map_err(|_| {
+                GwtuiError::Other("failed to acquire status collector semaphore".to_owned())
+            })?;
+            let git = Git::new(PathBuf::from(&wt.path));
+            let repo = repo.clone();
+            handles.push(tokio::task::spawn_blocking(move || {
+                let _permit = permit;
+                let error = git.fetch_all().err().map(|e| e.to_string());
+                (repo, error)
+            }));
+        }
+
+        for h in handles {
+            let (repo, error) = h
+                .await
+                .map_err(|e| GwtuiError::Other(format!("fetch task join error: {e}")))?;
+            let record = FetchRecord {
+                at: SystemTime::now(),
+                error,
+            };
+            FETCHES
+                .lock()
+                .unwrap_or_else(std::sync::PoisonError::into_inner)
+                .insert(repo, record);
+        }
+        Ok(())
+    }
+}
+
+/// Most recent fetch of `common_dir`: whichever is newer of the fetch attempted
+/// by this process and `FETCH_HEAD` (written by any fetch, including the
+/// user's own), plus the error if this process's attempt failed.
+fn last_fetch(common_dir: &Path) -> (Option<SystemTime>, Option<String>) {
+    let on_disk = std::fs::metadata(common_dir.join("FETCH_HEAD"))
+        .and_then(|m| m.modified())
+        .ok();
+    let record = FETCHES
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+        .get(common_dir)
+        .cloned();
+    match (record, on_disk) {
+        (Some(r), Some(disk)) if disk > r.at => (Some(disk), None),
+        (Some(r), _) => (Some(r.at), r.error),
+        (None, disk) => (disk, None),
+    }
 }
 
 fn collect_one_blocking(
     opts: &StatusCollectorOptions,
     wt: &Worktree,
+    base: Option<String>,
     current_path: &Path,
+    cache: Option<&Mutex<ActivityCache>>,
 ) -> WorktreeStatus {
     let worktree_path = PathBuf::from(&wt.path);
     let git = Git::new(worktree_path.clone());
 
     let mut status = WorktreeStatus {
         path: wt.path.clone(),
-        branch: wt.branch.clone(),
+        branch: wt.display_branch(),
         repository: extract_repository(&wt.path, &opts.base_dir),
         status: WorktreeState::Clean,
         git_status: GitStatus::default(),
         last_activity: "unknown".to_owned(),
         is_current: current_path.starts_with(&worktree_path),
+        is_locked: wt.is_locked,
+        lock_reason: wt.lock_reason.clone(),
+        processes: Vec::new(),
+        is_detached: wt.is_detached,
+        upstream: None,
+        base: None,
+        last_fetch: None,
+        fetch_error: None,
     };
 
-    if let Ok(gs) = collect_git_status(&git, opts.fetch_remote) {
-        status.status = determine_worktree_state(&gs);
-        status.git_status = gs;
+    if let Ok(ps) = collect_porcelain_status(&git, opts.fetch_remote) {
+        status.status = determine_worktree_state(&ps.git_status);
+        status.git_status = ps.git_status;
+        status.upstream = ps.upstream;
     } else {
         status.status = WorktreeState::Unknown;
     }
 
-    let last = get_last_activity(&git, &worktree_path);
+    if let Some(base) = base
+        && let Ok((ahead, behind)) = git.ahead_behind(&base)
+    {
+        status.git_status.base_ahead = ahead;
+        status.git_status.base_behind = behind;
+        status.base = Some(base);
+    }
+
+    let git_dir = worktree_git_dir(&worktree_path);
+    let last = match opts.activity {
+        ActivityMode::Fast => get_last_activity_fast(
+            &worktree_path,
+            git_dir.as_deref(),
+            &status.git_status.files,
+            cache,
+        ),
+        ActivityMode::Exhaustive => get_last_activity(&git, &worktree_path),
+    };
     if last != SystemTime::UNIX_EPOCH {
         status.last_activity = format_rfc3339(last).unwrap_or_else(|| "unknown".to_owned());
         if let Ok(age) = SystemTime::now().duration_since(last)
@@ -134,87 +550,203 @@ fn collect_one_blocking(
         }
     }
 
-    if opts.include_process {
-        // Process detection not implemented yet.
+    // An unfinished operation outranks both the change state and staleness:
+    // a forgotten rebase is exactly the kind of worktree that goes stale.
+    if let Some(op) = git_dir.as_deref().and_then(detect_operation) {
+        status.status = op;
     }
 
     status
 }
 
-fn collect_git_status(git: &Git, fetch_remote: bool) -> Result<GitStatus, GwtuiError> {
-    let mut status = GitStatus::default();
-
-    let out = git.run(&["status", "--porcelain=v1", "-uno"])?;
-    for line in out.lines() {
-        if line.len() < 2 {
-            continue;
-        }
-        process_status_line(line, &mut status);
-    }
-
-    // Count untracked via ls-files for accuracy.
-    if let Ok(untracked) = git.run(&["ls-files", "--others", "--exclude-standard"]) {
-        let count = untracked.lines().filter(|l| !l.trim().is_empty()).count();
-        status.untracked = u32::try_from(count).unwrap_or(u32::MAX);
+/// The worktree's private git dir: `.git` itself for the main worktree, or
+/// the `gitdir:` target of the `.git` file for linked worktrees. Read from
+/// disk so status collection does not spawn another git process.
+pub(crate) fn worktree_git_dir(worktree_path: &Path) -> Option<PathBuf> {
+    let dot_git = worktree_path.join(".git");
+    if dot_git.is_dir() {
+        return Some(dot_git);
     }
+    let content = std::fs::read_to_string(&dot_git).ok()?;
+    let target = content.lines().next()?.strip_prefix("gitdir:")?.trim();
+    Some(worktree_path.join(target))
+}
 
-    if fetch_remote {
-        let _ = fetch_remote_status(git, &mut status);
-    }
+/// The repository's shared git dir, which linked worktrees point to through
+/// their `commondir` file; used to fetch each repository only once.
+pub(crate) fn common_git_dir(git_dir: &Path) -> PathBuf {
+    let dir = match std::fs::read_to_string(git_dir.join("commondir")) {
+        Ok(content) => git_dir.join(content.trim()),
+        Err(_) => git_dir.to_path_buf(),
+    };
+    std::fs::canonicalize(&dir).unwrap_or(dir)
+}
 
-    Ok(status)
+/// Looks for the state files git leaves behind while an operation is stopped.
+fn detect_operation(git_dir: &Path) -> Option<WorktreeState> {
+    let checks = [
+        ("rebase-merge", WorktreeState::Rebasing),
+        ("rebase-apply", WorktreeState::Rebasing),
+        ("MERGE_HEAD", WorktreeState::Merging),
+        ("CHERRY_PICK_HEAD", WorktreeState::CherryPicking),
+        ("REVERT_HEAD", WorktreeState::Reverting),
+        ("BISECT_LOG", WorktreeState::Bisecting),
+    ];
+    checks
+        .into_iter()
+        .find(|(name, _)| git_dir.join(name).exists())
+        .map(|(_, state)| state)
 }
 
-fn process_status_line(line: &str, status: &mut GitStatus) {
-    let bytes = line.as_bytes();
-    let index = bytes[0] as char;
-    let worktree = bytes[1] as char;
+/// Everything a single `git status --porcelain=v2 --branch` call reports.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct PorcelainStatus {
+    pub git_status: GitStatus,
+    /// Checked-out branch; `None` when detached.
+    pub head: Option<String>,
+    pub upstream: Option<String>,
+}
 
-    if index != ' ' && index != '?' {
-        status.staged += 1;
+/// Changes, untracked files, upstream and ahead/behind from one `git status`
+/// process. Ahead/behind are only filled in when `include_upstream` is set.
+pub(crate) fn collect_porcelain_status(
+    git: &Git,
+    include_upstream: bool,
+) -> Result<PorcelainStatus, GwtuiError> {
+    // --no-optional-locks keeps `status` from rewriting the index, which
+    // would otherwise bump the index mtime used as an activity signal.
+    let out = git.run(&[
+        "--no-optional-locks",
+        "status",
+        "--porcelain=v2",
+        "--branch",
+        "-z",
+        "--untracked-files=all",
+    ])?;
+    let mut status = parse_porcelain_v2(&out);
+    if !include_upstream {
+        status.git_status.ahead = 0;
+        status.git_status.behind = 0;
     }
+    Ok(status)
+}
 
-    match worktree {
-        'M' => status.modified += 1,
-        'A' => status.added += 1,
-        'D' => status.deleted += 1,
-        '?' => status.untracked += 1,
-        'U' => status.conflicts += 1,
-        _ => {}
-    }
+pub(crate) fn collect_git_status(
+    git: &Git,
+    include_upstream: bool,
+) -> Result<GitStatus, GwtuiError> {
+    collect_porcelain_status(git, include_upstream).map(|s| s.git_status)
 }
 
-fn fetch_remote_status(git: &Git, status: &mut GitStatus) -> Result<(), GwtuiError> {
-    let current_branch = git.run(&["rev-parse", "--abbrev-ref", "HEAD"])?;
-    let current_branch = current_branch.trim();
-    if current_branch.is_empty() {
-        return Ok(());
-    }
+/// Parses `git status --porcelain=v2 --branch -z` output.
+pub(crate) fn parse_porcelain_v2(out: &str) -> PorcelainStatus {
+    let mut status = PorcelainStatus::default();
+    let mut records = out.split('\0');
+    while let Some(record) = records.next() {
+        if let Some(header) = record.strip_prefix("# ") {
+            let (key, value) = header.split_once(' ').unwrap_or((header, ""));
+            match key {
+                "branch.head" if value != "(detached)" => status.head = Some(value.to_owned()),
+                "branch.upstream" => status.upstream = Some(value.to_owned()),
+                "branch.ab" => {
+                    for part in value.split_whitespace() {
+                        if let Some(n) = part.strip_prefix('+') {
+                            status.git_status.ahead = n.parse().unwrap_or(0);
+                        } else if let Some(n) = part.strip_prefix('-') {
+                            status.git_status.behind = n.parse().unwrap_or(0);
+                        }
+                    }
+                }
+                _ => {}
+            }
+            continue;
+        }
 
-    let upstream = match git.run(&[
-        "rev-parse",
-        "--abbrev-ref",
-        &format!("{current_branch}@{{upstream}}"),
-    ]) {
-        Ok(s) => s.trim().to_owned(),
-        Err(_) => return Ok(()),
-    };
-    if upstream.is_empty() {
-        return Ok(());
+        // Ordinary entries have 8 fields before the path, renames/copies 9
+        // (plus the original path as the next record), unmerged entries 10.
+        let kind = record.split(' ').next().unwrap_or("");
+        let gs = &mut status.git_status;
+        match kind {
+            "1" | "2" => {
+                let fields = if kind == "1" { 8 } else { 9 };
+                let (Some(xy), Some(path)) = (record.get(2..4), nth_field_rest(record, fields))
+                else {
+                    continue;
+                };
+                let original_path = if kind == "2" {
+                    records.next().map(str::to_owned)
+                } else {
+                    None
+                };
+                let xy = xy.as_bytes();
+                let change = FileChange {
+                    path: path.to_owned(),
+                    index: FileState::from_code(xy[0]),
+                    worktree: FileState::from_code(xy[1]),
+                    original_path,
+                    conflict: false,
+                };
+                count_change(&change, gs);
+                gs.files.push(change);
+            }
+            "u" => {
+                let (Some(xy), Some(path)) = (record.get(2..4), nth_field_rest(record, 10)) else {
+                    continue;
+                };
+                let xy = xy.as_bytes();
+                gs.conflicts += 1;
+                gs.files.push(FileChange {
+                    path: path.to_owned(),
+                    index: FileState::from_code(xy[0]),
+                    worktree: FileState::from_code(xy[1]),
+                    original_path: None,
+                    conflict: true,
+                });
+            }
+            "?" => {
+                gs.untracked += 1;
+                gs.files.push(FileChange {
+                    path: record.get(2..).unwrap_or_default().to_owned(),
+                    index: FileState::Untracked,
+                    worktree: FileState::Untracked,
+                    original_path: None,
+                    conflict: false,
+                });
+            }
+            _ => {}
+        }
     }
+    status
+}
 
-    status.ahead = count_rev_list(git, &format!("{upstream}..HEAD")).unwrap_or(0);
-    status.behind = count_rev_list(git, &format!("HEAD..{upstream}")).unwrap_or(0);
-    Ok(())
+/// Everything after the first `n` space-separated fields (paths may contain spaces).
+fn nth_field_rest(record: &str, n: usize) -> Option<&str> {
+    record.splitn(n + 1, ' ').nth(n)
 }
 
-fn count_rev_list(git: &Git, rev_range: &str) -> Result<u32, GwtuiError> {
-    let out = git.run(&["rev-list", "--count", rev_range])?;
-    let n: u32 = out
-        .trim()
-        .parse()
-        .map_err(|e| GwtuiError::Other(format!("invalid rev-list count: {e}")))?;
-    Ok(n)
+fn count_change(change: &FileChange, gs: &mut GitStatus) {
+    if change.index != FileState::Unmodified {
+        gs.staged += 1;
+    }
+    let either = |state: FileState| change.index == state || change.worktree == state;
+    if either(FileState::Modified) {
+        gs.modified += 1;
+    }
+    if either(FileState::Added) {
+        gs.added += 1;
+    }
+    if either(FileState::Deleted) {
+        gs.deleted += 1;
+    }
+    if either(FileState::Renamed) {
+        gs.renamed += 1;
+    }
+    if either(FileState::Copied) {
+        gs.copied += 1;
+    }
+    if either(FileState::TypeChange) {
+        gs.typechange += 1;
+    }
 }
 
 fn determine_worktree_state(status: &GitStatus) -> WorktreeState {
@@ -224,47 +756,126 @@ fn determine_worktree_state(status: &GitStatus) -> WorktreeState {
     if status.staged > 0 {
         return WorktreeState::Staged;
     }
-    if status.modified > 0 || status.added > 0 || status.deleted > 0 || status.untracked > 0 {
+    if status.modified > 0
+        || status.added > 0
+        || status.deleted > 0
+        || status.renamed > 0
+        || status.copied > 0
+        || status.typechange > 0
+        || status.untracked > 0
+    {
         return WorktreeState::Modified;
     }
     WorktreeState::Clean
 }
 
+/// Last activity from cheap signals: the index and HEAD reflog mtimes (commits,
+/// checkouts, staging), the mtimes of files `git status` reported as changed,
+/// and the newest directory mtime (files created, renamed or deleted), which
+/// is cached per index mtime.
+fn get_last_activity_fast(
+    worktree_path: &Path,
+    git_dir: Option<&Path>,
+    changed: &[FileChange],
+    cache: Option<&Mutex<ActivityCache>>,
+) -> SystemTime {
+    let mtime = |p: &Path| std::fs::metadata(p).and_then(|m| m.modified()).ok();
+    let index_mtime = git_dir.and_then(|d| mtime(&d.join("index")));
+
+    let key = worktree_path.to_string_lossy().into_owned();
+    let cached = cache
+        .and_then(|c| ActivityCache::lock(c).entries.get(&key).copied())
+        .filter(|e| Some(e.index_mtime) == index_mtime)
+        .map(|e| e.newest_dir);
+    let newest_dir = cached.unwrap_or_else(|| {
+        let newest = newest_dir_mtime(worktree_path);
+        if let (Some(c), Some(index_mtime)) = (cache, index_mtime) {
+            let mut c = ActivityCache::lock(c);
+            c.entries.insert(
+                key,
+                ActivityCacheEntry {
+                    index_mtime,
+                    newest_dir: newest,
+                },
+            );
+            c.dirty = true;
+        }
+        newest
+    });
+
+    let mut latest = newest_dir;
+    let signals = git_dir
+        .into_iter()
+        .flat_map(|d| [d.join("index"), d.join("logs/HEAD"), d.join("HEAD")]);
+    for t in signals.filter_map(|p| mtime(&p)) {
+        latest = latest.max(t);
+    }
+    for f in changed {
+        // Deleted files are gone; their directory changed when they went.
+        let p = worktree_path.join(&f.path);
+        if let Some(t) = mtime(&p).or_else(|| p.parent().and_then(mtime)) {
+            latest = latest.max(t);
+        }
+    }
+    latest
+}
+
+/// Newest mtime among the worktree root and the directories below it,
+/// skipping the same build/dependency directories as the fallback walk.
+fn newest_dir_mtime(root: &Path) -> SystemTime {
+    let mut latest = std::fs::metadata(root)
+        .and_then(|m| m.modified())
+        .unwrap_or(SystemTime::UNIX_EPOCH);
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let p = entry.path();
+            let name = p.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            if should_skip_status_dir(name) {
+                continue;
+            }
+            if let Ok(t) = entry.metadata().and_then(|m| m.modified()) {
+                latest = latest.max(t);
+            }
+            stack.push(p);
+        }
+    }
+    latest
+}
+
+/// Exhaustive mode: the newest mtime of every tracked and untracked file.
 fn get_last_activity(git: &Git, worktree_path: &Path) -> SystemTime {
     let mut latest = SystemTime::UNIX_EPOCH;
     let mut any = false;
 
-    if let Ok(tracked) = git.run(&["ls-files", "-z"]) {
-        for rel in tracked.split('\0') {
+    // Tracked and untracked (non-ignored) files in one `ls-files` call.
+    if let Ok(files) = git.run(&[
+        "ls-files",
+        "-z",
+        "--cached",
+        "--others",
+        "--exclude-standard",
+    ]) {
+        for rel in files.split('\0') {
             if rel.is_empty() {
                 continue;
             }
             let p = worktree_path.join(rel);
-            if let Ok(m) = std::fs::metadata(&p)
-                && let Ok(t) = m.modified()
-                && (!any || t > latest)
-            {
-                latest = t;
-                any = true;
-            }
-        }
-
-        if let Ok(untracked) = git.run(&["ls-files", "-z", "--others", "--exclude-standard"]) {
-            for rel in untracked.split('\0') {
-                if rel.is_empty() {
+            if let Ok(m) = std::fs::metadata(&p) {
+                if m.is_dir() {
                     continue;
                 }
-                let p = worktree_path.join(rel);
-                if let Ok(m) = std::fs::metadata(&p) {
-                    if m.is_dir() {
-                        continue;
-                    }
-                    if let Ok(t) = m.modified()
-                        && (!any || t > latest)
-                    {
-                        latest = t;
-                        any = true;
-                    }
+                if let Ok(t) = m.modified()
+                    && (!any || t > latest)
+                {
+                    latest = t;
+                    any = true;
                 }
             }
         }
@@ -314,7 +925,7 @@ fn get_last_activity_fallback(root: &Path) -> SystemTime {
     latest
 }
 
-fn should_skip_status_dir(name: &str) -> bool {
+pub(crate) fn should_skip_status_dir(name: &str) -> bool {
     matches!(
         name,
         ".git"
@@ -392,65 +1003,176 @@ pub fn format_status_for_table(status: WorktreeState) -> &'static str {
         WorktreeState::Conflict => "conflicted",
         WorktreeState::Stale => "inactive",
         WorktreeState::Unknown => "unknown",
+        WorktreeState::Rebasing => "rebasing",
+        WorktreeState::Merging => "merging",
+        WorktreeState::CherryPicking => "cherry-picking",
+        WorktreeState::Reverting => "reverting",
+        WorktreeState::Bisecting => "bisecting",
     }
 }
 
-#[must_use]
-pub fn filter_statuses(statuses: Vec<WorktreeStatus>, filter: &str) -> Vec<WorktreeStatus> {
-    let f = filter.trim().to_lowercase();
-    if f.is_empty() || f == "all" {
-        return statuses;
+/// Keeps the statuses matching `filter`, a [`StatusQuery`] expression.
+pub fn filter_statuses(
+    statuses: Vec<WorktreeStatus>,
+    filter: &str,
+) -> Result<Vec<WorktreeStatus>, GwtuiError> {
+    let query = StatusQuery::parse(filter)?;
+    Ok(statuses.into_iter().filter(|s| query.matches(s)).collect())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Branch,
+    Status,
+    Changes,
+    Activity,
+    Ahead,
+    Behind,
+    Base,
+}
+
+impl SortKey {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "branch" | "name" => Self::Branch,
+            "status" => Self::Status,
+            "modified" | "changes" => Self::Changes,
+            "activity" | "time" => Self::Activity,
+            "ahead" => Self::Ahead,
+            "behind" => Self::Behind,
+            "base" => Self::Base,
+            _ => return None,
+        })
     }
-    statuses
-        .into_iter()
-        .filter(|s| match f.as_str() {
-            "modified" | "changed" => s.status == WorktreeState::Modified,
-            "clean" | "up to date" => s.status == WorktreeState::Clean,
-            "stale" | "inactive" => s.status == WorktreeState::Stale,
-            "staged" => s.status == WorktreeState::Staged,
-            "conflict" | "conflicted" => s.status == WorktreeState::Conflict,
-            _ => {
-                let needle = f.as_str();
-                s.branch.to_lowercase().contains(needle)
-                    || s.repository.to_lowercase().contains(needle)
-                    || s.path.to_lowercase().contains(needle)
+
+    /// Direction of a key given without `-`/`+`: branch A–Z and most urgent
+    /// status first; most changes, most recent activity, most ahead/behind
+    /// and most behind the base first.
+    fn descending_by_default(self) -> bool {
+        !matches!(self, Self::Branch | Self::Status)
+    }
+
+    /// Ascending order: branch A–Z, most urgent status, fewest changes,
+    /// oldest activity, least ahead/behind and least behind the base first.
+    fn compare(self, a: &WorktreeStatus, b: &WorktreeStatus) -> std::cmp::Ordering {
+        match self {
+            Self::Branch => a.branch.cmp(&b.branch),
+            Self::Status => status_priority(a.status).cmp(&status_priority(b.status)),
+            Self::Changes => {
+                count_total_changes(&a.git_status).cmp(&count_total_changes(&b.git_status))
             }
+            Self::Activity => a.last_activity.cmp(&b.last_activity),
+            Self::Ahead => a.git_status.ahead.cmp(&b.git_status.ahead),
+            Self::Behind => a.git_status.behind.cmp(&b.git_status.behind),
+            Self::Base => (a.git_status.base_behind, a.git_status.base_ahead)
+                .cmp(&(b.git_status.base_behind, b.git_status.base_ahead)),
+        }
+    }
+}
+
+/// Parses a comma-separated sort spec such as `status,+ahead` into keys and
+/// whether each is descending. `-` and `+` force descending and ascending
+/// order; a bare key keeps its default direction.
+fn parse_sort(sort_by: &str) -> Result<Vec<(SortKey, bool)>, GwtuiError> {
+    sort_by
+        .split(',')
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+        .map(|k| {
+            let (name, direction) = if let Some(name) = k.strip_prefix('-') {
+                (name, Some(true))
+            } else if let Some(name) = k.strip_prefix('+') {
+                (name, Some(false))
+            } else {
+                (k, None)
+            };
+            SortKey::parse(&name.to_lowercase())
+                .map(|key| (key, direction.unwrap_or(key.descending_by_default())))
+                .ok_or_else(|| {
+                    GwtuiError::Other(format!(
+                        "unknown sort key '{name}' (expected branch, status, changes, activity, ahead, behind or base)"
+                    ))
+                })
         })
         .collect()
 }
 
-pub fn sort_statuses(statuses: &mut [WorktreeStatus], sort_by: &str) {
-    let key = sort_by.to_lowercase();
-    match key.as_str() {
-        "branch" | "name" => statuses.sort_by(|a, b| a.branch.cmp(&b.branch)),
-        "status" => {
-            statuses.sort_by(|a, b| status_priority(a.status).cmp(&status_priority(b.status)));
-        }
-        "modified" | "changes" => statuses.sort_by(|a, b| {
-            count_total_changes(b.git_status)
-                .cmp(&count_total_changes(a.git_status))
-                .then_with(|| a.branch.cmp(&b.branch))
-        }),
-        "activity" | "time" => statuses.sort_by(|a, b| b.last_activity.cmp(&a.last_activity)),
-        "ahead" => statuses.sort_by(|a, b| b.git_status.ahead.cmp(&a.git_status.ahead)),
-        "behind" => statuses.sort_by(|a, b| b.git_status.behind.cmp(&a.git_status.behind)),
-        _ => {}
+/// Checks a sort spec up front so typos are reported instead of ignored.
+pub fn validate_sort(sort_by: &str) -> Result<(), GwtuiError> {
+    parse_sort(sort_by).map(|_| ())
+}
+
+/// Sorts by each key of a comma-separated spec in turn (in its default
+/// direction, or descending/ascending with a `-`/`+` prefix), then by branch.
+pub fn sort_statuses(statuses: &mut [WorktreeStatus], sort_by: &str) -> Result<(), GwtuiError> {
+    let keys = parse_sort(sort_by)?;
+    if keys.is_empty() {
+        return Ok(());
     }
+    statuses.sort_by(|a, b| {
+        keys.iter()
+            .map(|(key, descending)| {
+                let ord = key.compare(a, b);
+                if *descending { ord.reverse() } else { ord }
+            })
+            .find(|ord| ord.is_ne())
+            .unwrap_or_else(|| a.branch.cmp(&b.branch))
+    });
+    Ok(())
 }
 
 fn status_priority(status: WorktreeState) -> u32 {
     match status {
-        WorktreeState::Conflict => 0,
-        WorktreeState::Modified => 1,
-        WorktreeState::Staged => 2,
-        WorktreeState::Stale => 3,
-        WorktreeState::Clean => 4,
+        WorktreeState::Rebasing => 0,
+        WorktreeState::Merging => 1,
+        WorktreeState::CherryPicking => 2,
+        WorktreeState::Reverting => 3,
+        WorktreeState::Conflict => 4,
+        WorktreeState::Bisecting => 5,
+        WorktreeState::Modified => 6,
+        WorktreeState::Staged => 7,
+        WorktreeState::Stale => 8,
+        WorktreeState::Clean => 9,
         WorktreeState::Unknown => 999,
     }
 }
 
-fn count_total_changes(gs: GitStatus) -> u32 {
-    gs.modified + gs.added + gs.deleted + gs.untracked + gs.staged
+/// `↑ahead ↓behind` relative to the default branch, or `-` when it is unknown.
+#[must_use]
+pub fn format_base_divergence(s: &WorktreeStatus) -> String {
+    if s.base.is_none() {
+        return "-".to_owned();
+    }
+    format!("↑{} ↓{}", s.git_status.base_ahead, s.git_status.base_behind)
+}
+
+/// `Files (N):` header followed by one `XY path` line per change, at most
+/// `limit` of them.
+#[must_use]
+pub fn format_file_changes(files: &[FileChange], limit: usize) -> Vec<String> {
+    let mut lines = vec![format!("Files ({}):", files.len())];
+    for f in files.iter().take(limit) {
+        let mut line = format!("  {} {}", f.short_code(), f.path);
+        if let Some(orig) = &f.original_path {
+            line.push_str(&format!(" (from {orig})"));
+        }
+        lines.push(line);
+    }
+    if files.len() > limit {
+        lines.push(format!("  … {} more", files.len() - limit));
+    }
+    lines
+}
+
+pub(crate) fn count_total_changes(gs: &GitStatus) -> u32 {
+    gs.modified
+        + gs.added
+        + gs.deleted
+        + gs.untracked
+        + gs.staged
+        + gs.renamed
+        + gs.copied
+        + gs.typechange
 }
 
 #[cfg(test)]
@@ -458,18 +1180,167 @@ mod tests {
     use super::*;
 
     #[test]
-    fn parses_git_status_porcelain_v1_lines() {
-        let mut gs = GitStatus::default();
-        process_status_line(" M foo.txt", &mut gs);
-        process_status_line("A  added.txt", &mut gs);
-        process_status_line("?? untracked.txt", &mut gs);
-        process_status_line("UU conflict.txt", &mut gs);
+    fn parses_git_status_porcelain_v2_records() {
+        let out = [
+            "# branch.oid 1111111111111111111111111111111111111111",
+            "# branch.head feature/x",
+            "# branch.upstream origin/feature/x",
+            "# branch.ab +2 -3",
+            "1 .M N... 100644 100644 100644 aaaa bbbb foo.txt",
+            "1 A. N... 000000 100644 100644 0000 cccc added.txt",
+            "2 R. N... 100644 100644 100644 dddd dddd R100 new name.txt",
+            "old name.txt",
+            "2 C. N... 100644 100644 100644 dddd dddd C75 copy.txt",
+            "orig.txt",
+            "1 D. N... 100644 000000 000000 gggg 0000 gone.txt",
+            "1 .T N... 100644 100644 120000 hhhh hhhh link",
+            "1 MM N... 100644 100644 100644 iiii jjjj both.txt",
+            "u UU N... 100644 100644 100644 100644 eeee ffff 0000 conflict.txt",
+            "u AA N... 000000 100644 100644 100644 0000 ffff 0000 both added.txt",
+            "u DU N... 100644 000000 100644 100644 eeee 0000 ffff deleted by us.txt",
+            "? untracked.txt",
+            "? dir/also untracked.txt",
+            "",
+        ]
+        .join("\0");
 
-        assert_eq!(gs.modified, 1);
-        assert_eq!(gs.untracked, 1);
-        assert_eq!(gs.conflicts, 1);
-        // "A  file" counts as staged.
-        assert!(gs.staged >= 1);
+        let ps = parse_porcelain_v2(&out);
+        let gs = &ps.git_status;
+        assert_eq!(gs.modified, 2);
+        assert_eq!(gs.added, 1);
+        assert_eq!(gs.deleted, 1);
+        assert_eq!(gs.renamed, 1);
+        assert_eq!(gs.copied, 1);
+        assert_eq!(gs.typechange, 1);
+        assert_eq!(gs.untracked, 2);
+        assert_eq!(gs.conflicts, 3);
+        // Index-side changes: A., R., C., D. and MM; original paths are not records.
+        assert_eq!(gs.staged, 5);
+        assert_eq!((gs.ahead, gs.behind), (2, 3));
+        assert_eq!(ps.head.as_deref(), Some("feature/x"));
+        assert_eq!(ps.upstream.as_deref(), Some("origin/feature/x"));
+
+        assert_eq!(gs.files.len(), 12);
+        let rename = &gs.files[2];
+        assert_eq!(rename.path, "new name.txt");
+        assert_eq!(rename.original_path.as_deref(), Some("old name.txt"));
+        assert_eq!(rename.short_code(), "R.");
+        assert_eq!(gs.files[4].index, FileState::Deleted);
+        assert_eq!(gs.files[5].worktree, FileState::TypeChange);
+        let both_added = &gs.files[8];
+        assert_eq!(both_added.path, "both added.txt");
+        assert!(both_added.conflict);
+        assert_eq!(both_added.short_code(), "AA");
+        assert_eq!(gs.files[9].short_code(), "DU");
+        assert_eq!(gs.files[11].path, "dir/also untracked.txt");
+
+        let lines = format_file_changes(&gs.files, 3);
+        assert_eq!(lines[0], "Files (12):");
+        assert_eq!(lines[3], "  R. new name.txt (from old name.txt)");
+        assert_eq!(lines[4], "  … 9 more");
+
+        let ps = parse_porcelain_v2("# branch.oid 1111\0# branch.head (detached)\0");
+        assert_eq!(ps.head, None);
+        assert_eq!(ps.upstream, None);
+        assert_eq!(ps.git_status, GitStatus::default());
+    }
+
+    #[test]
+    fn detects_in_progress_operations_from_linked_git_dir() {
+        let td = tempfile::tempdir().expect("tempdir");
+        let wt = td.path().join("wt");
+        let git_dir = td.path().join("repo/.git/worktrees/wt");
+        std::fs::create_dir_all(&wt).expect("mkdir wt");
+        std::fs::create_dir_all(&git_dir).expect("mkdir git dir");
+        std::fs::write(wt.join(".git"), "gitdir: ../repo/.git/worktrees/wt\n").expect("write");
+
+        let resolved = worktree_git_dir(&wt).expect("git dir");
+        assert_eq!(
+            std::fs::canonicalize(&resolved).expect("canonicalize"),
+            std::fs::canonicalize(&git_dir).expect("canonicalize")
+        );
+        assert_eq!(detect_operation(&resolved), None);
+
+        std::fs::write(git_dir.join("BISECT_LOG"), "").expect("write");
+        assert_eq!(detect_operation(&git_dir), Some(WorktreeState::Bisecting));
+        std::fs::write(git_dir.join("CHERRY_PICK_HEAD"), "").expect("write");
+        assert_eq!(
+            detect_operation(&git_dir),
+            Some(WorktreeState::CherryPicking)
+        );
+        std::fs::create_dir_all(git_dir.join("rebase-merge")).expect("mkdir");
+        assert_eq!(detect_operation(&git_dir), Some(WorktreeState::Rebasing));
+        assert!(WorktreeState::Rebasing.is_in_progress());
+        assert!(!WorktreeState::Conflict.is_in_progress());
+    }
+
+    #[test]
+    fn fast_activity_caches_directory_walk_per_index_mtime() {
+        let at = |secs| SystemTime::UNIX_EPOCH + Duration::from_secs(secs);
+        let set_mtime = |p: &Path, secs| {
+            std::fs::File::open(p)
+                .and_then(|f| f.set_modified(at(secs)))
+                .expect("set mtime");
+        };
+
+        let td = tempfile::tempdir().expect("tempdir");
+        let wt = td.path().join("wt");
+        let git_dir = td.path().join("gitdir");
+        std::fs::create_dir_all(wt.join("a/b")).expect("mkdir");
+        std::fs::create_dir_all(&git_dir).expect("mkdir");
+        std::fs::write(git_dir.join("index"), "").expect("write");
+        std::fs::write(wt.join("a/f.txt"), "x").expect("write");
+        for p in [wt.join("a/f.txt"), wt.join("a/b"), wt.join("a"), wt.clone()] {
+            set_mtime(&p, 500);
+        }
+        set_mtime(&git_dir.join("index"), 1000);
+
+        let cache = Mutex::new(ActivityCache::default());
+        let fast = |changed: &[FileChange], cache| {
+            get_last_activity_fast(&wt, Some(&git_dir), changed, cache)
+        };
+        assert_eq!(fast(&[], Some(&cache)), at(1000));
+        assert!(ActivityCache::lock(&cache).dirty);
+
+        // Directory changes are picked up once the index moves on.
+        set_mtime(&wt.join("a/b"), 2000);
+        assert_eq!(fast(&[], Some(&cache)), at(1000));
+        assert_eq!(fast(&[], None), at(2000));
+
+        // Changed files are always checked.
+        set_mtime(&wt.join("a/f.txt"), 3000);
+        let changed = [FileChange {
+            path: "a/f.txt".to_owned(),
+            index: FileState::Unmodified,
+            worktree: FileState::Modified,
+            original_path: None,
+            conflict: false,
+        }];
+        assert_eq!(fast(&changed, Some(&cache)), at(3000));
+
+        set_mtime(&git_dir.join("index"), 1500);
+        assert_eq!(fast(&[], Some(&cache)), at(2000));
+
+        // Entries of worktrees that are gone are dropped; ones still on disk
+        // are kept even when not collected this time.
+        let removed = td.path().join("removed").to_string_lossy().into_owned();
+        let dir = td.path().join("cache");
+        {
+            let mut cache = ActivityCache::lock(&cache);
+            let entry = cache.entries[wt.to_string_lossy().as_ref()];
+            cache.entries.insert(removed.clone(), entry);
+            cache.prune(&[]);
+            cache.save(&dir);
+        }
+        let loaded = ActivityCache::load(&dir);
+        assert!(!loaded.entries.contains_key(&removed));
+        assert_eq!(
+            loaded.entries.get(wt.to_string_lossy().as_ref()),
+            Some(&ActivityCacheEntry {
+                index_mtime: at(1500),
+                newest_dir: at(2000),
+            })
+        );
     }
 
     #[test]
@@ -500,6 +1371,14 @@ mod tests {
                 git_status: GitStatus::default(),
                 last_activity: "unknown".to_owned(),
                 is_current: false,
+                is_locked: false,
+                lock_reason: None,
+                processes: Vec::new(),
+                is_detached: false,
+                upstream: None,
+                base: None,
+                last_fetch: None,
+                fetch_error: None,
             },
             WorktreeStatus {
                 path: "/tmp/repo/feature/bar".to_owned(),
@@ -509,15 +1388,76 @@ mod tests {
                 git_status: GitStatus::default(),
                 last_activity: "unknown".to_owned(),
                 is_current: false,
+                is_locked: false,
+                lock_reason: None,
+                processes: Vec::new(),
+                is_detached: false,
+                upstream: None,
+                base: None,
+                last_fetch: None,
+                fetch_error: None,
             },
         ];
 
-        let out = filter_statuses(statuses.clone(), "foo");
+        let out = filter_statuses(statuses.clone(), "foo").expect("valid filter");
         assert_eq!(out.len(), 1);
         assert_eq!(out[0].branch, "feature/foo");
 
-        let out = filter_statuses(statuses.clone(), "modified");
+        let out = filter_statuses(statuses.clone(), "modified").expect("valid filter");
         assert_eq!(out.len(), 1);
         assert_eq!(out[0].branch, "feature/bar");
     }
+
+    #[test]
+    fn sorts_by_multiple_keys_with_reversal() {
+        let row = |branch: &str, status: WorktreeState, ahead: u32| WorktreeStatus {
+            path: format!("/tmp/repo/{branch}"),
+            branch: branch.to_owned(),
+            repository: "repo".to_owned(),
+            status,
+            git_status: GitStatus {
+                ahead,
+                ..GitStatus::default()
+            },
+            last_activity: "unknown".to_owned(),
+            is_current: false,
+            is_locked: false,
+            lock_reason: None,
+            processes: Vec::new(),
+            is_detached: false,
+            upstream: None,
+            base: None,
+            last_fetch: None,
+            fetch_error: None,
+        };
+        let mut statuses = vec![
+            row("a", WorktreeState::Clean, 1),
+            row("b", WorktreeState::Modified, 1),
+            row("c", WorktreeState::Modified, 4),
+            row("d", WorktreeState::Clean, 2),
+        ];
+        let branches =
+            |s: &[WorktreeStatus]| s.iter().map(|s| s.branch.clone()).collect::<Vec<_>>();
+
+        sort_statuses(&mut statuses, "status, -ahead").unwrap();
+        assert_eq!(branches(&statuses), ["c", "b", "d", "a"]);
+        sort_statuses(&mut statuses, "+ahead").unwrap();
+        assert_eq!(branches(&statuses), ["a", "b", "d", "c"]);
+        sort_statuses(&mut statuses, "ahead").unwrap();
+        assert_eq!(branches(&statuses), ["c", "d", "a", "b"]);
+        sort_statuses(&mut statuses, "+ahead").unwrap();
+        sort_statuses(&mut statuses, "-ahead").unwrap();
+        assert_eq!(branches(&statuses), ["c", "d", "a", "b"]);
+        sort_statuses(&mut statuses, "-branch").unwrap();
+        assert_eq!(branches(&statuses), ["d", "c", "b", "a"]);
+        assert!(sort_statuses(&mut statuses, "-size").is_err());
+
+        assert!(validate_sort("status,-activity").is_ok());
+        assert!(
+            validate_sort("status,size")
+                .expect_err("unknown key")
+                .to_string()
+                .contains("unknown sort key 'size'")
+        );
+    }
 }
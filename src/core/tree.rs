@@ -0,0 +1,265 @@
+#![forbid(unsafe_code)]
+
+use std::collections::{BTreeMap, HashSet};
+
+use serde::Serialize;
+
+use crate::core::naming::RepoId;
+use crate::core::status::{self, WorktreeStatus};
+
+/// Host and owner used for worktrees whose `origin` cannot be parsed.
+const NO_REMOTE: &str = "(no remote)";
+
+/// Totals over the worktrees below a group.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GroupStats {
+    pub worktrees: usize,
+    /// Worktrees with uncommitted or untracked changes.
+    pub dirty: usize,
+    pub ahead: u64,
+    pub behind: u64,
+}
+
+impl GroupStats {
+    fn add(&mut self, other: Self) {
+        self.worktrees += other.worktrees;
+        self.dirty += other.dirty;
+        self.ahead += other.ahead;
+        self.behind += other.behind;
+    }
+}
+
+/// A host, owner or repository. Hosts contain owners, owners contain
+/// repositories and repositories hold indices into the statuses the tree
+/// was built from, in their original order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RepoGroup {
+    pub name: String,
+    /// `host`, `host/owner` or `host/owner/repo`; identifies the group across
+    /// rebuilds, e.g. to remember which groups are collapsed.
+    pub key: String,
+    pub stats: GroupStats,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<RepoGroup>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub worktrees: Vec<usize>,
+}
+
+/// Groups `statuses` by host → owner → repository, sorted by name.
+/// `repo_of` gives each worktree's [`RepoId`]; worktrees without one are
+/// grouped under `(no remote)` by their repository name.
+pub fn build_tree(
+    statuses: &[WorktreeStatus],
+    repo_of: impl Fn(&WorktreeStatus) -> Option<RepoId>,
+) -> Vec<RepoGroup> {
+    let mut grouped: BTreeMap<String, BTreeMap<String, BTreeMap<String, Vec<usize>>>> =
+        BTreeMap::new();
+    for (i, s) in statuses.iter().enumerate() {
+        let id = repo_of(s).unwrap_or_else(|| RepoId {
+            host: NO_REMOTE.to_owned(),
+            owner: NO_REMOTE.to_owned(),
+            repo: s.repository.clone(),
+        });
+        grouped
+            .entry(id.host)
+            .or_default()
+            .entry(id.owner)
+            .or_default()
+            .entry(id.repo)
+            .or_default()
+            .push(i);
+    }
+
+    let group = |name: String, key: String, children: Vec<RepoGroup>, worktrees: Vec<usize>| {
+        let mut stats = GroupStats::default();
+        for child in &children {
+            stats.add(child.stats);
+        }
+        for &i in &worktrees {
+            stats.add(worktree_stats(&statuses[i]));
+        }
+        RepoGroup {
+            name,
+            key,
+            stats,
+            children,
+            worktrees,
+        }
+    };
+    grouped
+        .into_iter()
+        .map(|(host, owners)| {
+            let owners = owners
+                .into_iter()
+                .map(|(owner, repos)| {
+                    let repos = repos
+                        .into_iter()
+                        .map(|(repo, worktrees)| {
+                            let key = format!("{host}/{owner}/{repo}");
+                            group(repo, key, Vec::new(), worktrees)
+                        })
+                        .collect();
+                    group(owner.clone(), format!("{host}/{owner}"), repos, Vec::new())
+                })
+                .collect();
+            group(host.clone(), host, owners, Vec::new())
+        })
+        .collect()
+}
+
+fn worktree_stats(s: &WorktreeStatus) -> GroupStats {
+    GroupStats {
+        worktrees: 1,
+        dirty: usize::from(status::count_total_changes(&s.git_status) > 0),
+        ahead: u64::from(s.git_status.ahead),
+        behind: u64::from(s.git_status.behind),
+    }
+}
+
+/// One visible line of a tree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TreeRow {
+    Group {
+        depth: usize,
+        name: String,
+        key: String,
+        stats: GroupStats,
+        expanded: bool,
+    },
+    /// Index into the statuses the tree was built from.
+    Worktree { depth: usize, index: usize },
+}
+
+impl TreeRow {
+    #[must_use]
+    pub fn depth(&self) -> usize {
+        match self {
+            Self::Group { depth, .. } | Self::Worktree { depth, .. } => *depth,
+        }
+    }
+}
+
+/// The rows of `groups` in display order, hiding everything below the
+/// groups whose key is in `collapsed`.
+#[must_use]
+pub fn flatten(groups: &[RepoGroup], collapsed: &HashSet<String>) -> Vec<TreeRow> {
+    fn walk(
+        groups: &[RepoGroup],
+        depth: usize,
+        collapsed: &HashSet<String>,
+        out: &mut Vec<TreeRow>,
+    ) {
+        for g in groups {
+            let expanded = !collapsed.contains(&g.key);
+            out.push(TreeRow::Group {
+                depth,
+                name: g.name.clone(),
+                key: g.key.clone(),
+                stats: g.stats,
+                expanded,
+            });
+            if expanded {
+                walk(&g.children, depth + 1, collapsed, out);
+                out.extend(g.worktrees.iter().map(|&index| TreeRow::Worktree {
+                    depth: depth + 1,
+                    index,
+                }));
+            }
+        }
+    }
+    let mut out = Vec::new();
+    walk(groups, 0, collapsed, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::naming::parse_origin_url;
+    use crate::core::status::{GitStatus, WorktreeState};
+
+    fn status(
+        path: &str,
+        repository: &str,
+        changes: u32,
+        ahead: u32,
+        behind: u32,
+    ) -> WorktreeStatus {
+        WorktreeStatus {
+            path: path.to_owned(),
+            branch: "main".to_owned(),
+            repository: repository.to_owned(),
+            status: WorktreeState::Clean,
+            git_status: GitStatus {
+                modified: changes,
+                ahead,
+                behind,
+                ..GitStatus::default()
+            },
+            last_activity: "unknown".to_owned(),
+            is_current: false,
+            is_locked: false,
+            lock_reason: None,
+            processes: Vec::new(),
+            is_detached: false,
+            upstream: None,
+            base: None,
+            last_fetch: None,
+            fetch_error: None,
+        }
+    }
+
+    #[test]
+    fn groups_by_host_owner_repo_with_aggregates() {
+        let statuses = vec![
+            status("/w/gwtui-main", "gwtui", 0, 1, 0),
+            status("/w/local", "scratch", 3, 0, 0),
+            status("/w/gwtui-feat", "gwtui", 2, 2, 5),
+            status("/w/other", "other", 0, 0, 1),
+        ];
+        let urls = [
+            "git@github.com:imsakg/gwtui.git",
+            "",
+            "https://github.com/imsakg/gwtui",
+            "https://gitlab.com/team/other.git",
+        ];
+        let repo_of = |s: &WorktreeStatus| {
+            let i = statuses.iter().position(|x| x.path == s.path)?;
+            parse_origin_url(urls[i])
+        };
+        let tree = build_tree(&statuses, repo_of);
+
+        let names: Vec<&str> = tree.iter().map(|g| g.name.as_str()).collect();
+        assert_eq!(names, ["(no remote)", "github.com", "gitlab.com"]);
+        let github = &tree[1];
+        assert_eq!(
+            github.stats,
+            GroupStats {
+                worktrees: 2,
+                dirty: 1,
+                ahead: 3,
+                behind: 5
+            }
+        );
+        let repo = &github.children[0].children[0];
+        assert_eq!(repo.key, "github.com/imsakg/gwtui");
+        assert_eq!(repo.worktrees, [0, 2]);
+        assert_eq!(tree[0].children[0].children[0].name, "scratch");
+
+        let rows = flatten(&tree, &HashSet::new());
+        assert_eq!(rows.len(), 3 * 3 + 4);
+        assert_eq!(rows[3], TreeRow::Worktree { depth: 3, index: 1 });
+
+        let collapsed = HashSet::from(["github.com/imsakg".to_owned(), "gitlab.com".to_owned()]);
+        let rows = flatten(&tree, &collapsed);
+        let depths: Vec<usize> = rows.iter().map(TreeRow::depth).collect();
+        assert_eq!(depths, [0, 1, 2, 3, 0, 1, 0]);
+        assert!(matches!(
+            &rows[5],
+            TreeRow::Group {
+                expanded: false,
+                ..
+            }
+        ));
+    }
+}
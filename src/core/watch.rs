@@ -0,0 +1,270 @@
+#![forbid(unsafe_code)]
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver};
+use std::time::{Duration, Instant};
+
+use notify::event::ModifyKind;
+use notify::{Event, EventKind, PollWatcher, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::core::status;
+use crate::error::GwtuiError;
+
+/// Events arriving closer together than this are coalesced into one refresh.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// What changed since the last [`WorktreeWatcher::take_changes`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct WatchChanges {
+    /// Roots of worktrees whose index, HEAD or working files changed.
+    pub worktrees: Vec<PathBuf>,
+    /// A worktree was added to or removed from one of the repositories.
+    pub rescan: bool,
+}
+
+impl WatchChanges {
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.worktrees.is_empty() && !self.rescan
+    }
+}
+
+#[derive(Debug)]
+struct Target {
+    /// As passed in, which is what callers match statuses against.
+    path: PathBuf,
+    /// Canonical form, which is what event paths are reported under.
+    root: PathBuf,
+    git_dir: Option<PathBuf>,
+}
+
+/// Watches a set of worktrees: every working directory except the build and
+/// dependency directories status collection skips, each worktree's private
+/// git dir (index, HEAD, operation state) and its reflog, plus each
+/// repository's common dir and its `worktrees/` registry. Uses inotify/FSEvents/... where
+/// available and falls back to polling when the native watcher cannot be
+/// created or runs out of watches.
+pub struct WorktreeWatcher {
+    watcher: Box<dyn Watcher + Send>,
+    events: Receiver<notify::Result<Event>>,
+    targets: Vec<Target>,
+    common_dirs: Vec<PathBuf>,
+    polling: bool,
+    pending: BTreeSet<usize>,
+    rescan: bool,
+    last_event: Option<Instant>,
+}
+
+impl WorktreeWatcher {
+    pub fn new(roots: &[PathBuf], poll_interval: Duration) -> Result<Self, GwtuiError> {
+        let targets: Vec<Target> = roots
+            .iter()
+            .map(|root| Target {
+                path: root.clone(),
+                root: std::fs::canonicalize(root).unwrap_or_else(|_| root.clone()),
+                git_dir: status::worktree_git_dir(root)
+                    .map(|d| std::fs::canonicalize(&d).unwrap_or(d)),
+            })
+            .collect();
+        let mut common_dirs: Vec<PathBuf> = targets
+            .iter()
+            .filter_map(|t| t.git_dir.as_deref())
+            .map(status::common_git_dir)
+            .collect();
+        common_dirs.sort();
+        common_dirs.dedup();
+
+        let native = (|| {
+            let (tx, rx) = mpsc::channel();
+            let mut watcher: Box<dyn Watcher + Send> =
+                Box::new(RecommendedWatcher::new(tx, notify::Config::default())?);
+            watch_all(watcher.as_mut(), &targets, &common_dirs)?;
+            Ok::<_, notify::Error>((watcher, rx))
+        })();
+        let (watcher, events, polling) = match native {
+            Ok((watcher, rx)) => (watcher, rx, false),
+            Err(_) => {
+                let (tx, rx) = mpsc::channel();
+                let config = notify::Config::default().with_poll_interval(poll_interval);
+                let mut watcher: Box<dyn Watcher + Send> =
+                    Box::new(PollWatcher::new(tx, config).map_err(watch_error)?);
+                watch_all(watcher.as_mut(), &targets, &common_dirs).map_err(watch_error)?;
+                (watcher, rx, true)
+            }
+        };
+
+        Ok(Self {
+            watcher,
+            events,
+            targets,
+            common_dirs,
+            polling,
+            pending: BTreeSet::new(),
+            rescan: false,
+            last_event: None,
+        })
+    }
+
+    /// Whether the polling fallback is in use.
+    #[must_use]
+    pub fn is_polling(&self) -> bool {
+        self.polling
+    }
+
+    /// Drains pending notifications. Returns nothing until events have
+    /// settled for a moment, so a burst of writes triggers a single refresh.
+    pub fn take_changes(&mut self) -> WatchChanges {
+        while let Ok(res) = self.events.try_recv() {
+            let Ok(event) = res else { continue };
+            if matches!(event.kind, EventKind::Access(_)) {
+                continue;
+            }
+            for path in &event.paths {
+                self.record(path, &event.kind);
+            }
+        }
+
+        let settled = self.last_event.is_some_and(|t| t.elapsed() >= DEBOUNCE);
+        if !settled {
+            return WatchChanges::default();
+        }
+        self.last_event = None;
+        WatchChanges {
+            worktrees: std::mem::take(&mut self.pending)
+                .into_iter()
+                .map(|i| self.targets[i].path.clone())
+                .collect(),
+            rescan: std::mem::take(&mut self.rescan),
+        }
+    }
+
+    fn record(&mut self, path: &Path, kind: &EventKind) {
+        let Some(parent) = path.parent() else { return };
+        let registry = self
+            .common_dirs
+            .iter()
+            .map(|c| c.join("worktrees"))
+            .find(|r| r == parent || r == path);
+        if let Some(registry) = registry {
+            let added_or_removed = matches!(
+                kind,
+                EventKind::Create(_)
+                    | EventKind::Remove(_)
+                    | EventKind::Modify(ModifyKind::Name(_))
+            );
+            if !added_or_removed {
+                return;
+            }
+            // The registry only exists once the first linked worktree is added.
+            if path == registry && matches!(kind, EventKind::Create(_)) {
+                let _ = self.watcher.watch(&registry, RecursiveMode::NonRecursive);
+            }
+            self.rescan = true;
+            self.last_event = Some(Instant::now());
+            return;
+        }
+
+        let in_git_dir = self.targets.iter().position(|t| {
+            t.git_dir
+                .as_deref()
+                .is_some_and(|d| parent == d || parent == d.join("logs"))
+        });
+        let hit = match in_git_dir {
+            Some(i) => is_relevant_git_file(path).then_some(i),
+            None => self.owning_worktree(path),
+        };
+        let Some(i) = hit else { return };
+
+        // New directories inside a worktree need their own watch.
+        let skipped_dir = path
+            .file_name()
+            .is_some_and(|n| status::should_skip_status_dir(&n.to_string_lossy()));
+        if in_git_dir.is_none()
+            && matches!(kind, EventKind::Create(_))
+            && path.is_dir()
+            && !skipped_dir
+        {
+            let _ = watch_tree(self.watcher.as_mut(), path);
+        }
+        self.pending.insert(i);
+        self.last_event = Some(Instant::now());
+    }
+
+    /// The deepest worktree containing `path`, unless the path lies in a
+    /// directory status collection ignores (dependencies, build output, `.git`).
+    fn owning_worktree(&self, path: &Path) -> Option<usize> {
+        let (i, target) = self
+            .targets
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| path.starts_with(&t.root))
+            .max_by_key(|(_, t)| t.root.components().count())?;
+        let rel = path.strip_prefix(&target.root).ok()?;
+        let mut dirs = rel.parent().into_iter().flat_map(Path::components);
+        let skipped =
+            dirs.any(|c| status::should_skip_status_dir(&c.as_os_str().to_string_lossy()));
+        (!skipped).then_some(i)
+    }
+}
+
+fn watch_all(
+    watcher: &mut dyn Watcher,
+    targets: &[Target],
+    common_dirs: &[PathBuf],
+) -> notify::Result<()> {
+    for target in targets {
+        watch_tree(watcher, &target.root)?;
+        if let Some(git_dir) = &target.git_dir {
+            watcher.watch(git_dir, RecursiveMode::NonRecursive)?;
+            let logs = git_dir.join("logs");
+            if logs.is_dir() {
+                watcher.watch(&logs, RecursiveMode::NonRecursive)?;
+            }
+        }
+    }
+    for common in common_dirs {
+        watcher.watch(common, RecursiveMode::NonRecursive)?;
+        let registry = common.join("worktrees");
+        if registry.is_dir() {
+            watcher.watch(&registry, RecursiveMode::NonRecursive)?;
+        }
+    }
+    Ok(())
+}
+
+/// Watches `root` and every directory below it that status collection does
+/// not skip, one non-recursive watch each, so `node_modules/` and `target/`
+/// never use up watches.
+fn watch_tree(watcher: &mut dyn Watcher, root: &Path) -> notify::Result<()> {
+    let mut stack = vec![root.to_path_buf()];
+    while let Some(dir) = stack.pop() {
+        watcher.watch(&dir, RecursiveMode::NonRecursive)?;
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            if entry.file_type().is_ok_and(|t| t.is_dir())
+                && !status::should_skip_status_dir(&name.to_string_lossy())
+            {
+                stack.push(entry.path());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Index, HEAD, reflog and operation state files; lock files are transient
+/// and `FETCH_HEAD` is written by the status fetch itself.
+fn is_relevant_git_file(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    !name.ends_with(".lock") && name != "FETCH_HEAD"
+}
+
+fn watch_error(e: notify::Error) -> GwtuiError {
+    GwtuiError::Other(format!("failed to watch worktrees: {e}"))
+}
@@ -1,6 +1,9 @@
 #![forbid(unsafe_code)]
 
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use std::time::SystemTime;
 
 use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
@@ -9,28 +12,188 @@ use time::format_description::well_known::Rfc3339;
 use crate::config as config_util;
 use crate::config::Config;
 use crate::core::git::Git;
-use crate::core::naming::{RepoId, parse_origin_url, render_template, sanitize_all};
+use crate::core::glob::GlobSet;
+use crate::core::hooks::{self, HookContext, HookKind, HookOutput};
+use crate::core::local_files::{self, LocalFilesReport};
+use crate::core::naming::{
+    TemplateContext, render_template, review_number, sanitize_all, template_path,
+    template_variables,
+};
+use crate::core::status::{self, GitStatus};
 use crate::error::GwtuiError;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Worktree {
     pub path: String,
     pub branch: String,
     pub commit_hash: String,
     pub is_main: bool,
     pub created_at: String,
+    #[serde(default)]
+    pub is_locked: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lock_reason: Option<String>,
+    #[serde(default)]
+    pub is_prunable: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prunable_reason: Option<String>,
+    #[serde(default)]
+    pub is_bare: bool,
+    #[serde(default)]
+    pub is_detached: bool,
+}
+
+impl Worktree {
+    /// Porcelain attributes as short labels (`locked`, `prunable`, `bare`, `detached`).
+    #[must_use]
+    pub fn flags(&self) -> Vec<&'static str> {
+        let mut flags = Vec::new();
+        if self.is_locked {
+            flags.push("locked");
+        }
+        if self.is_prunable {
+            flags.push("prunable");
+        }
+        if self.is_bare {
+            flags.push("bare");
+        }
+        if self.is_detached {
+            flags.push("detached");
+        }
+        flags
+    }
+
+    /// Branch name for display; detached worktrees show their commit instead
+    /// of the bare `HEAD` git reports.
+    #[must_use]
+    pub fn display_branch(&self) -> String {
+        if self.is_detached && (self.branch.is_empty() || self.branch == "HEAD") {
+            detached_label(&self.commit_hash)
+        } else {
+            self.branch.clone()
+        }
+    }
+}
+
+/// `(detached 1a2b3c4)`.
+#[must_use]
+pub fn detached_label(commit_hash: &str) -> String {
+    let short = commit_hash.get(..7).unwrap_or(commit_hash);
+    if short.is_empty() {
+        "(detached)".to_owned()
+    } else {
+        format!("(detached {short})")
+    }
+}
+
+/// One entry of a re-layout plan: move `from` to `to`, unless `skip_reason`
+/// explains why the worktree has to stay where it is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayoutMove {
+    pub branch: String,
+    pub from: PathBuf,
+    pub to: PathBuf,
+    pub skip_reason: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CleanReason {
+    /// The branch is reachable from the default branch.
+    Merged,
+    /// The branch's upstream was deleted (typically after a squash merge).
+    UpstreamGone,
+}
+
+/// A worktree `clean --merged` would remove. `protected` explains why it is
+/// kept unless forced (dirty, unpushed or unmerged commits, locked, status
+/// unreadable).
+#[derive(Debug, Clone)]
+pub struct CleanCandidate {
+    pub worktree: Worktree,
+    pub reason: CleanReason,
+    pub git_status: GitStatus,
+    pub protected: Option<String>,
+    /// Whether every commit of the branch is known to exist elsewhere, so it
+    /// can be deleted with `git branch -D`. Forcing never deletes other
+    /// branches; only their worktree is removed.
+    pub branch_deletable: bool,
+}
+
+/// Where a code review lives on the remote: GitHub pull requests
+/// (`refs/pull/N/head`) or GitLab merge requests (`refs/merge-requests/N/head`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewKind {
+    PullRequest,
+    MergeRequest,
+}
+
+impl ReviewKind {
+    #[must_use]
+    pub fn remote_ref(self, number: u64) -> String {
+        match self {
+            ReviewKind::PullRequest => format!("refs/pull/{number}/head"),
+            ReviewKind::MergeRequest => format!("refs/merge-requests/{number}/head"),
+        }
+    }
+
+    /// Local branch the review is fetched into.
+    #[must_use]
+    pub fn branch(self, number: u64) -> String {
+        match self {
+            ReviewKind::PullRequest => format!("pr/{number}"),
+            ReviewKind::MergeRequest => format!("mr/{number}"),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct WorktreeManager {
     git: Git,
     cfg: Config,
+    /// Why the repository's overrides could not be layered over `cfg`;
+    /// reported by the operations that create worktrees or run hooks.
+    layer_error: Option<GwtuiError>,
+}
+
+/// A config resolved for one repository, reused while the config it was
+/// resolved from and the repository's `.gwtui.toml` stay the same.
+#[derive(Debug, Clone)]
+struct ResolvedConfig {
+    base: Config,
+    /// Modification time and length of `.gwtui.toml`, if it exists.
+    stamp: Option<(SystemTime, u64)>,
+    result: Result<Config, GwtuiError>,
 }
 
+/// Resolved configs by main worktree root, so that the managers created on
+/// every refresh neither re-read `.gwtui.toml` nor ask git for `origin`.
+static RESOLVED: Mutex<BTreeMap<PathBuf, ResolvedConfig>> = Mutex::new(BTreeMap::new());
+
 impl WorktreeManager {
+    /// Layers the `[[repos]]` entries matching the repository and its
+    /// `.gwtui.toml` over `cfg`.
     #[must_use]
     pub fn new(git: Git, cfg: Config) -> Self {
-        Self { git, cfg }
+        let result = resolve_cached(&git, &cfg);
+        match result {
+            Ok(cfg) => Self {
+                git,
+                cfg,
+                layer_error: None,
+            },
+            Err(e) => Self {
+                git,
+                cfg,
+                layer_error: Some(e),
+            },
+        }
+    }
+
+    fn check_layers(&self) -> Result<(), GwtuiError> {
+        match &self.layer_error {
+            Some(e) => Err(e.clone()),
+            None => Ok(()),
+        }
     }
 
     pub fn list(&self) -> Result<Vec<Worktree>, GwtuiError> {
@@ -46,6 +209,8 @@ impl WorktreeManager {
             let head = entry.head;
             let path_buf = PathBuf::from(&path);
             if branch.is_empty()
+                && !entry.bare
+                && !entry.prunable
                 && let Ok(out) = self
                     .git
                     .run_in_dir(&path_buf, &["rev-parse", "--abbrev-ref", "HEAD"])
@@ -65,6 +230,12 @@ impl WorktreeManager {
                 commit_hash: head,
                 is_main: path_buf == current_top,
                 created_at,
+                is_locked: entry.locked,
+                lock_reason: entry.lock_reason,
+                is_prunable: entry.prunable,
+                prunable_reason: entry.prunable_reason,
+                is_bare: entry.bare,
+                is_detached: entry.detached,
             });
         }
 
@@ -103,11 +274,13 @@ impl WorktreeManager {
         branch: &str,
         custom_path: Option<&Path>,
         create_branch: bool,
-    ) -> Result<(), GwtuiError> {
+    ) -> Result<PathBuf, GwtuiError> {
         let raw_path = if let Some(p) = custom_path {
             p.to_string_lossy().to_string()
         } else {
-            self.generate_worktree_path(branch)
+            // `worktree add -b` without a start point branches off HEAD.
+            let start = if create_branch { "HEAD" } else { branch };
+            self.generate_worktree_path(branch, start)?
         };
 
         let expanded =
@@ -127,7 +300,118 @@ impl WorktreeManager {
         }
 
         self.git.add_worktree(&expanded, branch, create_branch)?;
-        Ok(())
+        Ok(expanded)
+    }
+
+    /// Adds a worktree with a detached HEAD at `rev` (commit, tag or any ref).
+    /// The path is rendered from the naming template with `rev` as the
+    /// branch/`{{ref}}`.
+    pub fn add_detached(
+        &self,
+        rev: &str,
+        custom_path: Option<&Path>,
+    ) -> Result<PathBuf, GwtuiError> {
+        let raw_path = if let Some(p) = custom_path {
+            p.to_string_lossy().to_string()
+        } else {
+            self.generate_worktree_path(rev, rev)?
+        };
+
+        let expanded =
+            config_util::expand_path(&raw_path).map_err(|e| GwtuiError::Other(e.to_string()))?;
+
+        if self.cfg.worktree.auto_mkdir
+            && let Some(parent) = expanded.parent()
+        {
+            std::fs::create_dir_all(parent).map_err(|e| GwtuiError::IoPath {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+
+        self.git.add_worktree_detached(&expanded, rev)?;
+        Ok(expanded)
+    }
+
+    /// [`add`](Self::add) that moves the current worktree's uncommitted changes,
+    /// untracked files included, into the new worktree through a stash. When the
+    /// stash does not apply cleanly the new worktree (and the branch, if it was
+    /// created here) is removed again and the changes are restored in place.
+    pub fn add_carrying_changes(
+        &self,
+        branch: &str,
+        custom_path: Option<&Path>,
+        create_branch: bool,
+    ) -> Result<PathBuf, GwtuiError> {
+        let stash = self
+            .git
+            .stash_push_all(&format!("gwtui: carry into {branch}"))?;
+        let path = match self.add(branch, custom_path, create_branch) {
+            Ok(path) => path,
+            Err(e) => {
+                if let Some(sha) = &stash
+                    && let Err(pop) = self.git.stash_pop(sha)
+                {
+                    return Err(GwtuiError::Other(format!(
+                        "{e}; restoring the changes failed, they are kept in stash {sha} ({pop})"
+                    )));
+                }
+                return Err(e);
+            }
+        };
+        let Some(sha) = stash else {
+            return Ok(path);
+        };
+
+        if let Err(e) = self.git.stash_apply_in(&path, &sha) {
+            // Every step runs even when an earlier one fails; restoring the
+            // changes comes first since it does not depend on the others.
+            let mut problems = Vec::new();
+            if let Err(pop) = self.git.stash_pop(&sha) {
+                problems.push(format!(
+                    "restoring the changes failed, they are kept in stash {sha} ({pop})"
+                ));
+            }
+            if let Err(rm) = self.git.remove_worktree(&path, true) {
+                problems.push(format!("removing {} failed ({rm})", path.display()));
+            }
+            if create_branch && let Err(del) = self.git.delete_branch(branch, true) {
+                problems.push(format!("deleting branch '{branch}' failed ({del})"));
+            }
+            if problems.is_empty() {
+                return Err(GwtuiError::Other(format!(
+                    "changes do not apply cleanly on '{branch}'; worktree removed and changes restored ({e})"
+                )));
+            }
+            return Err(GwtuiError::Other(format!(
+                "changes do not apply cleanly on '{branch}' ({e}); rollback incomplete: {}",
+                problems.join("; ")
+            )));
+        }
+        self.git.stash_drop(&sha)?;
+        Ok(path)
+    }
+
+    /// Fetches a pull/merge request from `remote` (default
+    /// `worktree.review_remote`) into its local review branch and returns the
+    /// branch name. An existing branch is only fast-forwarded, so local commits
+    /// on it are never lost.
+    pub fn fetch_review(
+        &self,
+        kind: ReviewKind,
+        number: u64,
+        remote: Option<&str>,
+    ) -> Result<String, GwtuiError> {
+        let remote = remote.unwrap_or(&self.cfg.worktree.review_remote);
+        let branch = kind.branch(number);
+        self.git
+            .fetch_ref(remote, &kind.remote_ref(number), &branch)
+            .map_err(|e| {
+                GwtuiError::Other(format!(
+                    "could not update '{branch}' (it is only fast-forwarded; delete it to fetch a rewritten review): {e}"
+                ))
+            })?;
+        Ok(branch)
     }
 
     pub fn add_from_base(
@@ -139,7 +423,7 @@ impl WorktreeManager {
         let raw_path = if let Some(p) = custom_path {
             p.to_string_lossy().to_string()
         } else {
-            self.generate_worktree_path(branch)
+            self.generate_worktree_path(branch, base_branch.unwrap_or("HEAD"))?
         };
 
         let expanded =
@@ -160,9 +444,39 @@ impl WorktreeManager {
     }
 
     pub fn remove(&self, path: &Path, force: bool) -> Result<(), GwtuiError> {
+        self.ensure_not_locked(path, force)?;
         self.git.remove_worktree(path, force)
     }
 
+    /// Fails when `git worktree remove` would refuse `path`: the main
+    /// worktree, or without `force` a locked worktree or one with modified or
+    /// untracked files. Lets callers bail out before running `pre_remove`
+    /// hooks.
+    pub fn check_removable(&self, path: &Path, force: bool) -> Result<(), GwtuiError> {
+        if same_path(&self.main_root(), path) {
+            return Err(GwtuiError::Other(format!(
+                "{} is the main worktree and cannot be removed",
+                path.display()
+            )));
+        }
+        if force {
+            return Ok(());
+        }
+        self.ensure_not_locked(path, false)?;
+        if path.is_dir() {
+            let changes = Git::new(path.to_path_buf()).run(&["status", "--porcelain"])?;
+            if !changes.trim().is_empty() {
+                return Err(GwtuiError::Other(format!(
+                    "{} has modified or untracked files; use --force to remove it anyway",
+                    path.display()
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Removes the worktree and, when asked, its branch. Returns whether a
+    /// branch was deleted; detached worktrees have none, so nothing is.
     pub fn remove_with_branch(
         &self,
         path: &Path,
@@ -170,16 +484,64 @@ impl WorktreeManager {
         force_worktree: bool,
         delete_branch: bool,
         force_branch: bool,
-    ) -> Result<(), GwtuiError> {
+    ) -> Result<bool, GwtuiError> {
+        let entry = self.find_by_path(path)?;
+        if let Some(wt) = &entry
+            && !force_worktree
+        {
+            check_unlocked(wt)?;
+        }
         self.git.remove_worktree(path, force_worktree)?;
-        if delete_branch && !branch.is_empty() {
+
+        let detached = branch == "HEAD" || entry.as_ref().is_some_and(|wt| wt.is_detached);
+        if delete_branch && !branch.is_empty() && !detached {
             self.git.delete_branch(branch, force_branch)?;
+            return Ok(true);
         }
-        Ok(())
+        Ok(false)
     }
 
-    pub fn prune(&self) -> Result<(), GwtuiError> {
-        self.git.prune_worktrees()
+    /// Prunes stale worktree metadata. Git never prunes locked entries, so they
+    /// are returned to the caller; with `force` they are unlocked first and
+    /// pruned along with the rest.
+    pub fn prune(&self, force: bool) -> Result<Vec<Worktree>, GwtuiError> {
+        let locked: Vec<Worktree> = self
+            .list()?
+            .into_iter()
+            .filter(|wt| wt.is_locked && wt.is_prunable)
+            .collect();
+        if force {
+            for wt in &locked {
+                self.git.unlock_worktree(Path::new(&wt.path))?;
+            }
+        }
+        self.git.prune_worktrees()?;
+        Ok(locked)
+    }
+
+    pub fn lock(&self, path: &Path, reason: Option<&str>) -> Result<(), GwtuiError> {
+        self.git.lock_worktree(path, reason)
+    }
+
+    pub fn unlock(&self, path: &Path) -> Result<(), GwtuiError> {
+        self.git.unlock_worktree(path)
+    }
+
+    fn ensure_not_locked(&self, path: &Path, force: bool) -> Result<(), GwtuiError> {
+        if force {
+            return Ok(());
+        }
+        match self.find_by_path(path)? {
+            Some(wt) => check_unlocked(&wt),
+            None => Ok(()),
+        }
+    }
+
+    fn find_by_path(&self, path: &Path) -> Result<Option<Worktree>, GwtuiError> {
+        Ok(self
+            .list()?
+            .into_iter()
+            .find(|wt| same_path(Path::new(&wt.path), path)))
     }
 
     pub fn get_matching_worktrees(&self, pattern: &str) -> Result<Vec<Worktree>, GwtuiError> {
@@ -194,87 +556,440 @@ impl WorktreeManager {
         Ok(matches)
     }
 
+    /// Root of the repository's main worktree, falling back to this manager's
+    /// repo root when it cannot be resolved.
     #[must_use]
-    pub fn resolve_path_from_worktree(&self, wt: &Worktree) -> PathBuf {
-        PathBuf::from(&wt.path)
+    pub fn main_root(&self) -> PathBuf {
+        self.git
+            .main_worktree_root()
+            .unwrap_or_else(|_| self.git.repo_root().to_path_buf())
     }
 
-    fn generate_worktree_path(&self, branch: &str) -> String {
-        let repo_id = match self
-            .git
-            .get_repository_url()
-            .ok()
-            .and_then(|url| parse_origin_url(&url))
+    /// Runs the configured `kind` hooks for a worktree. `repo_root` should be
+    /// resolved before a removal, since the worktree cannot be asked afterwards.
+    pub fn run_hooks(
+        &self,
+        kind: HookKind,
+        branch: &str,
+        worktree_path: &Path,
+        repo_root: &Path,
+        output: HookOutput<'_>,
+    ) -> Result<(), GwtuiError> {
+        self.check_layers()?;
+        let commands = hooks::commands_for(&self.cfg, repo_root, kind)?;
+        if commands.is_empty() {
+            return Ok(());
+        }
+        let ctx = HookContext {
+            branch,
+            worktree_path,
+            repo_root,
+        };
+        hooks::run_hooks(kind, &ctx, &commands, output)
+    }
+
+    /// Copies/links the configured `worktree.copy_files` / `link_files` from the
+    /// main worktree into `dest`.
+    pub fn copy_local_files(&self, dest: &Path) -> Result<LocalFilesReport, GwtuiError> {
+        self.check_layers()?;
+        let copy = GlobSet::new(&self.cfg.worktree.copy_files)?;
+        let link = GlobSet::new(&self.cfg.worktree.link_files)?;
+        local_files::apply_local_files(&self.main_root(), dest, &copy, &link)
+    }
+
+    /// Path the naming template renders for `branch`, with `~` expanded.
+    /// Templates using `{{date}}` or `{{short_hash}}` are rejected: they render
+    /// differently over time, so they cannot say where a worktree belongs.
+    pub fn expected_path(&self, branch: &str) -> Result<PathBuf, GwtuiError> {
+        self.check_stable_template()?;
+        config_util::expand_path(&self.generate_worktree_path(branch, branch)?)
+            .map_err(|e| GwtuiError::Other(e.to_string()))
+    }
+
+    pub fn move_worktree(&self, from: &Path, to: &Path) -> Result<(), GwtuiError> {
+        if to.exists() {
+            return Err(GwtuiError::Other(format!(
+                "destination already exists: {}",
+                to.display()
+            )));
+        }
+        if self.cfg.worktree.auto_mkdir
+            && let Some(parent) = to.parent()
         {
-            Some(id) => id,
-            None => RepoId {
-                host: "local".to_owned(),
-                owner: "local".to_owned(),
-                repo: self
+            std::fs::create_dir_all(parent).map_err(|e| GwtuiError::IoPath {
+                path: parent.to_path_buf(),
+                source: e,
+            })?;
+        }
+        self.git.move_worktree(from, to)?;
+
+        if let Ok(base) = config_util::expand_path(&self.cfg.worktree.base_dir) {
+            remove_empty_parents(from, &base);
+        }
+        Ok(())
+    }
+
+    /// Plans moving every managed worktree (one below `worktree.base_dir`) to
+    /// the path the current naming template renders for its branch.
+    pub fn plan_relayout(&self) -> Result<Vec<RelayoutMove>, GwtuiError> {
+        self.check_stable_template()?;
+        let base = config_util::expand_path(&self.cfg.worktree.base_dir)
+            .map_err(|e| GwtuiError::Other(e.to_string()))?;
+        let base = std::fs::canonicalize(&base).unwrap_or(base);
+        let worktrees = self.list()?;
+        Ok(plan_relayout(&worktrees, &base, |branch| {
+            self.expected_path(branch).ok()
+        }))
+    }
+
+    /// Linked worktrees whose branch is merged into `default_branch` or whose
+    /// upstream is gone. The default branch itself and detached worktrees are
+    /// never candidates.
+    pub fn clean_candidates(
+        &self,
+        default_branch: &str,
+    ) -> Result<Vec<CleanCandidate>, GwtuiError> {
+        let merged = self.git.merged_branches(default_branch)?;
+        let gone = self.git.gone_branches()?;
+        let default_local = default_branch
+            .strip_prefix("origin/")
+            .unwrap_or(default_branch);
+
+        let mut out = Vec::new();
+        for wt in self.list()? {
+            if wt.is_main || wt.is_bare || wt.is_detached || wt.branch.is_empty() {
+                continue;
+            }
+            if wt.branch == default_branch || wt.branch == default_local {
+                continue;
+            }
+            let reason = if merged.contains(&wt.branch) {
+                CleanReason::Merged
+            } else if gone.contains(&wt.branch) {
+                CleanReason::UpstreamGone
+            } else {
+                continue;
+            };
+
+            // A status that cannot be read says nothing about local work, so
+            // such worktrees are kept.
+            let (git_status, status_error) =
+                match status::collect_git_status(&Git::new(PathBuf::from(&wt.path)), true) {
+                    Ok(s) => (s, false),
+                    Err(_) => (GitStatus::default(), true),
+                };
+            let dirty = git_status.modified
+                + git_status.added
+                + git_status.deleted
+                + git_status.untracked
+                + git_status.staged
+                + git_status.renamed
+                + git_status.copied
+                + git_status.typechange
+                + git_status.conflicts
+                > 0;
+            // Without an upstream there is no ahead count; the commits of a
+            // gone branch are only safe if some remote branch still has them or
+            // they were squash-merged into the default branch.
+            let landed = reason == CleanReason::Merged
+                || self.git.remote_contains(&wt.branch).unwrap_or(false)
+                || self
                     .git
-                    .repo_root()
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    .unwrap_or("repo")
-                    .to_owned(),
-            },
+                    .squash_merged(&wt.branch, default_branch)
+                    .unwrap_or(false);
+            let protected = if wt.is_locked {
+                Some("locked".to_owned())
+            } else if status_error {
+                Some("status unavailable".to_owned())
+            } else if dirty {
+                Some("uncommitted changes".to_owned())
+            } else if git_status.ahead > 0 {
+                Some(format!("{} unpushed commit(s)", git_status.ahead))
+            } else if !landed {
+                Some("not merged".to_owned())
+            } else {
+                None
+            };
+            out.push(CleanCandidate {
+                branch_deletable: landed && git_status.ahead == 0,
+                worktree: wt,
+                reason,
+                git_status,
+                protected,
+            });
+        }
+        Ok(out)
+    }
+
+    pub fn default_branch(&self) -> Result<String, GwtuiError> {
+        self.git.default_branch()
+    }
+
+    #[must_use]
+    pub fn resolve_path_from_worktree(&self, wt: &Worktree) -> PathBuf {
+        PathBuf::from(&wt.path)
+    }
+
+    fn check_stable_template(&self) -> Result<(), GwtuiError> {
+        let template = &self.cfg.worktree.naming_template;
+        let unstable: Vec<_> = template_variables(template)?
+            .into_iter()
+            .filter(|v| matches!(*v, "date" | "short_hash"))
+            .map(|v| format!("{{{{{v}}}}}"))
+            .collect();
+        if unstable.is_empty() {
+            return Ok(());
+        }
+        Err(GwtuiError::InvalidTemplate(format!(
+            "naming template `{template}` uses {}, which renders differently over time; \
+             it cannot tell where an existing worktree belongs",
+            unstable.join(" and ")
+        )))
+    }
+
+    /// Renders the naming template for `branch`; `start` is the revision the
+    /// worktree will check out, which `{{short_hash}}` abbreviates.
+    fn generate_worktree_path(&self, branch: &str, start: &str) -> Result<String, GwtuiError> {
+        self.check_layers()?;
+        let repo_id = repo_target(&self.git, true).id;
+
+        let template = &self.cfg.worktree.naming_template;
+        let sanitize = &self.cfg.worktree.sanitize;
+        let used = template_variables(template)?;
+        let mut ctx = TemplateContext::new(repo_id, branch).sanitized(sanitize);
+        ctx.pr = review_number(branch).map(str::to_owned);
+        ctx.date = {
+            let today = time::OffsetDateTime::now_utc().date();
+            format!(
+                "{:04}-{:02}-{:02}",
+                today.year(),
+                u8::from(today.month()),
+                today.day()
+            )
         };
+        let user = std::env::var("USER")
+            .or_else(|_| std::env::var("USERNAME"))
+            .unwrap_or_default();
+        ctx.user = sanitize_all(user, sanitize);
+        // Both need git, so they are only looked up when the template uses them.
+        if used.contains(&"short_hash") {
+            let rev = format!("{start}^{{commit}}");
+            ctx.short_hash = self
+                .git
+                .run(&["rev-parse", "--short", "--verify", "-q", &rev])
+                .or_else(|_| self.git.run(&["rev-parse", "--short", "HEAD"]))
+                .map(|s| s.trim().to_owned())
+                .unwrap_or_default();
+        }
+        if used.contains(&"base") {
+            let base = self.git.default_branch().unwrap_or_default();
+            let base = base.strip_prefix("origin/").unwrap_or(&base).to_owned();
+            ctx.base = sanitize_all(base, sanitize);
+        }
 
-        let sanitized_branch = sanitize_all(branch.to_owned(), &self.cfg.worktree.sanitize);
-        let rel = render_template(
-            &self.cfg.worktree.naming_template,
-            &repo_id,
-            &sanitized_branch,
-        );
-        PathBuf::from(&self.cfg.worktree.base_dir)
+        let rel = template_path(&render_template(template, &ctx)?)?;
+        Ok(PathBuf::from(&self.cfg.worktree.base_dir)
             .join(rel)
             .to_string_lossy()
-            .to_string()
+            .to_string())
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// The repository `git` belongs to, found without running git; the `origin`
+/// URL is only asked for when `with_url` is set.
+#[must_use]
+pub fn repo_target(git: &Git, with_url: bool) -> config_util::RepoTarget {
+    let url = if with_url {
+        git.get_repository_url().ok()
+    } else {
+        None
+    };
+    config_util::RepoTarget::new(main_root_on_disk(git), url)
+}
+
+/// The main worktree root, read from the `.git` files instead of asking git.
+fn main_root_on_disk(git: &Git) -> PathBuf {
+    status::worktree_git_dir(git.repo_root())
+        .map(|dir| status::common_git_dir(&dir))
+        .and_then(|common| {
+            let parent = common.parent()?;
+            common
+                .file_name()
+                .is_some_and(|n| n == ".git")
+                .then(|| parent.to_path_buf())
+        })
+        .unwrap_or_else(|| git.repo_root().to_path_buf())
+}
+
+/// [`config_util::resolve_for_repo`] through the [`RESOLVED`] cache. Git is
+/// only asked for the `origin` URL when `[[repos]]` entries need matching.
+fn resolve_cached(git: &Git, cfg: &Config) -> Result<Config, GwtuiError> {
+    let root = main_root_on_disk(git);
+    let stamp = std::fs::metadata(root.join(config_util::REPO_CONFIG_FILE))
+        .ok()
+        .and_then(|m| Some((m.modified().ok()?, m.len())));
+    let cached = RESOLVED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get(&root)
+        .filter(|c| c.base == *cfg && c.stamp == stamp)
+        .map(|c| c.result.clone());
+    if let Some(result) = cached {
+        return result;
+    }
+
+    let url = if cfg.repos.is_empty() {
+        None
+    } else {
+        git.get_repository_url().ok()
+    };
+    let target = config_util::RepoTarget::new(root.clone(), url);
+    let result = config_util::resolve_for_repo(cfg, &target).map(|(cfg, _)| cfg);
+    RESOLVED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .insert(
+            root,
+            ResolvedConfig {
+                base: cfg.clone(),
+                stamp,
+                result: result.clone(),
+            },
+        );
+    result
+}
+
+fn check_unlocked(wt: &Worktree) -> Result<(), GwtuiError> {
+    if !wt.is_locked {
+        return Ok(());
+    }
+    let msg = match wt.lock_reason.as_deref() {
+        Some(reason) => format!("{} ({reason}); use --force to remove it anyway", wt.path),
+        None => format!("{}; use --force to remove it anyway", wt.path),
+    };
+    Err(GwtuiError::WorktreeLocked(msg))
+}
+
+fn plan_relayout(
+    worktrees: &[Worktree],
+    base: &Path,
+    expected: impl Fn(&str) -> Option<PathBuf>,
+) -> Vec<RelayoutMove> {
+    let mut plan: Vec<RelayoutMove> = Vec::new();
+    for wt in worktrees {
+        let from = PathBuf::from(&wt.path);
+        if wt.is_main || wt.is_bare || wt.is_prunable || wt.is_detached || !from.starts_with(base) {
+            continue;
+        }
+        if wt.branch.is_empty() || wt.branch == "HEAD" {
+            continue;
+        }
+        let Some(to) = expected(&wt.branch) else {
+            continue;
+        };
+        if same_path(&from, &to) {
+            continue;
+        }
+
+        let skip_reason = if wt.is_locked {
+            Some("worktree is locked".to_owned())
+        } else if plan.iter().any(|m| m.to == to && m.skip_reason.is_none()) {
+            Some("another worktree maps to the same path".to_owned())
+        } else if to.exists() {
+            Some("destination already exists".to_owned())
+        } else {
+            None
+        };
+        plan.push(RelayoutMove {
+            branch: wt.branch.clone(),
+            from,
+            to,
+            skip_reason,
+        });
+    }
+    plan
+}
+
+/// Removes directories left empty by a move, walking up until `base`.
+fn remove_empty_parents(moved_from: &Path, base: &Path) {
+    let mut dir = moved_from.parent();
+    while let Some(d) = dir {
+        if !d.starts_with(base) || d == base || std::fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    if a == b {
+        return true;
+    }
+    match (std::fs::canonicalize(a), std::fs::canonicalize(b)) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 struct PorcelainEntry {
     path: String,
     branch: String,
     head: String,
+    locked: bool,
+    lock_reason: Option<String>,
+    prunable: bool,
+    prunable_reason: Option<String>,
+    bare: bool,
+    detached: bool,
 }
 
 fn parse_worktree_porcelain(out: &str) -> Vec<PorcelainEntry> {
     let mut entries: Vec<PorcelainEntry> = Vec::new();
-
-    let mut cur_path: Option<String> = None;
-    let mut cur_branch = String::new();
-    let mut cur_head = String::new();
+    let mut cur: Option<PorcelainEntry> = None;
 
     for line in out.lines() {
         let line = line.trim_end();
         if let Some(path) = line.strip_prefix("worktree ") {
-            if let Some(p) = cur_path.take() {
-                entries.push(PorcelainEntry {
-                    path: p,
-                    branch: cur_branch.clone(),
-                    head: cur_head.clone(),
-                });
-                cur_branch.clear();
-                cur_head.clear();
+            if let Some(entry) = cur.take() {
+                entries.push(entry);
             }
-            cur_path = Some(path.to_owned());
-        } else if let Some(branch) = line.strip_prefix("branch ") {
-            branch
-                .trim()
-                .trim_start_matches("refs/heads/")
-                .clone_into(&mut cur_branch);
-        } else if let Some(head) = line.strip_prefix("HEAD ") {
-            head.trim().clone_into(&mut cur_head);
-        }
-    }
-    if let Some(p) = cur_path.take() {
-        entries.push(PorcelainEntry {
-            path: p,
-            branch: cur_branch,
-            head: cur_head,
-        });
+            cur = Some(PorcelainEntry {
+                path: path.to_owned(),
+                ..PorcelainEntry::default()
+            });
+            continue;
+        }
+        let Some(entry) = cur.as_mut() else {
+            continue;
+        };
+        let (key, value) = match line.split_once(' ') {
+            Some((k, v)) => (k, Some(v.trim())),
+            None => (line, None),
+        };
+        match key {
+            "branch" => {
+                value
+                    .unwrap_or_default()
+                    .trim_start_matches("refs/heads/")
+                    .clone_into(&mut entry.branch);
+            }
+            "HEAD" => value.unwrap_or_default().clone_into(&mut entry.head),
+            "locked" => {
+                entry.locked = true;
+                entry.lock_reason = value.filter(|v| !v.is_empty()).map(str::to_owned);
+            }
+            "prunable" => {
+                entry.prunable = true;
+                entry.prunable_reason = value.filter(|v| !v.is_empty()).map(str::to_owned);
+            }
+            "bare" => entry.bare = true,
+            "detached" => entry.detached = true,
+            _ => {}
+        }
+    }
+    if let Some(entry) = cur.take() {
+        entries.push(entry);
     }
 
     entries
@@ -308,10 +1023,86 @@ detached
                 path: "/repo".to_owned(),
                 branch: "main".to_owned(),
                 head: "1111111111111111111111111111111111111111".to_owned(),
+                ..PorcelainEntry::default()
             }
         );
         assert_eq!(entries[1].branch, "feature/test");
         assert_eq!(entries[2].branch, "");
+        assert!(entries[2].detached);
+    }
+
+    #[test]
+    fn plans_relayout_for_managed_worktrees_only() {
+        let wt = |path: &str, branch: &str| Worktree {
+            path: path.to_owned(),
+            branch: branch.to_owned(),
+            ..Worktree::default()
+        };
+        let worktrees = vec![
+            Worktree {
+                is_main: true,
+                ..wt("/base/main", "main")
+            },
+            wt("/base/old/feature-a", "feature/a"),
+            wt("/base/new/feature-b", "feature/b"),
+            wt("/elsewhere/feature-c", "feature/c"),
+            Worktree {
+                is_locked: true,
+                ..wt("/base/old/feature-d", "feature/d")
+            },
+            Worktree {
+                is_detached: true,
+                ..wt("/base/old/detached", "HEAD")
+            },
+        ];
+
+        let plan = plan_relayout(&worktrees, Path::new("/base"), |branch| {
+            Some(PathBuf::from("/base/new").join(branch.replace('/', "-")))
+        });
+
+        assert_eq!(plan.len(), 2);
+        assert_eq!(plan[0].branch, "feature/a");
+        assert_eq!(plan[0].to, PathBuf::from("/base/new/feature-a"));
+        assert_eq!(plan[0].skip_reason, None);
+        assert_eq!(plan[1].branch, "feature/d");
+        assert!(plan[1].skip_reason.is_some());
+    }
+
+    #[test]
+    fn parses_porcelain_flags() {
+        let out = r#"worktree /repo.git
+bare
+
+worktree /wt/locked
+HEAD 1111111111111111111111111111111111111111
+branch refs/heads/locked
+locked waiting on review
+
+worktree /wt/locked-no-reason
+HEAD 2222222222222222222222222222222222222222
+branch refs/heads/other
+locked
+
+worktree /wt/gone
+HEAD 3333333333333333333333333333333333333333
+detached
+prunable gitdir file points to non-existent location
+"#;
+
+        let entries = parse_worktree_porcelain(out);
+        assert_eq!(entries.len(), 4);
+        assert!(entries[0].bare);
+        assert!(entries[1].locked);
+        assert_eq!(entries[1].lock_reason.as_deref(), Some("waiting on review"));
+        assert!(entries[2].locked);
+        assert_eq!(entries[2].lock_reason, None);
+        assert!(entries[3].detached);
+        assert!(entries[3].prunable);
+        assert_eq!(
+            entries[3].prunable_reason.as_deref(),
+            Some("gitdir file points to non-existent location")
+        );
+        assert!(!entries[3].locked);
     }
 }
 
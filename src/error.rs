@@ -30,6 +30,22 @@ pub enum GwtuiError {
     #[error("multiple worktrees match pattern: {0}")]
     AmbiguousWorktree(String),
 
+    #[error("worktree is locked: {0}")]
+    WorktreeLocked(String),
+
+    #[error("{hook} hook `{command}` failed: {detail}")]
+    HookFailed {
+        hook: String,
+        command: String,
+        detail: String,
+    },
+
+    #[error("invalid filter at column {column}: {msg}")]
+    InvalidQuery { column: usize, msg: String },
+
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+
     #[error("operation cancelled")]
     Cancelled,
 
@@ -43,3 +59,43 @@ pub enum GwtuiError {
     #[error("{0}")]
     Other(String),
 }
+
+// `std::io::Error` is not `Clone`; a copy keeps its kind and message.
+impl Clone for GwtuiError {
+    fn clone(&self) -> Self {
+        match self {
+            Self::NotInGitRepo => Self::NotInGitRepo,
+            Self::GitNotFound => Self::GitNotFound,
+            Self::ZellijNotFound => Self::ZellijNotFound,
+            Self::Config(msg) => Self::Config(msg.clone()),
+            Self::InvalidConfigKey(key) => Self::InvalidConfigKey(key.clone()),
+            Self::InvalidConfigValue { key, msg } => Self::InvalidConfigValue {
+                key: key.clone(),
+                msg: msg.clone(),
+            },
+            Self::WorktreeNotFound(p) => Self::WorktreeNotFound(p.clone()),
+            Self::AmbiguousWorktree(p) => Self::AmbiguousWorktree(p.clone()),
+            Self::WorktreeLocked(msg) => Self::WorktreeLocked(msg.clone()),
+            Self::HookFailed {
+                hook,
+                command,
+                detail,
+            } => Self::HookFailed {
+                hook: hook.clone(),
+                command: command.clone(),
+                detail: detail.clone(),
+            },
+            Self::InvalidQuery { column, msg } => Self::InvalidQuery {
+                column: *column,
+                msg: msg.clone(),
+            },
+            Self::InvalidTemplate(msg) => Self::InvalidTemplate(msg.clone()),
+            Self::Cancelled => Self::Cancelled,
+            Self::IoPath { path, source } => Self::IoPath {
+                path: path.clone(),
+                source: std::io::Error::new(source.kind(), source.to_string()),
+            },
+            Self::Other(msg) => Self::Other(msg.clone()),
+        }
+    }
+}
@@ -1,5 +1,6 @@
 #![forbid(unsafe_code)]
 
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
@@ -15,8 +16,12 @@ use ratatui::widgets::{
 use crate::config;
 use crate::core::discovery;
 use crate::core::git::Git;
+use crate::core::hooks::{HookKind, HookOutput};
+use crate::core::naming::{RepoId, parse_origin_url};
+use crate::core::query::StatusQuery;
 use crate::core::status::{self, StatusCollector, StatusCollectorOptions, WorktreeStatus};
-use crate::core::worktree::{Worktree, WorktreeManager};
+use crate::core::tree::{self, TreeRow};
+use crate::core::worktree::{self, Worktree, WorktreeManager};
 use crate::mux::Mux as _;
 use crate::mux::zellij::ZellijMux;
 use crate::task::execution::{ExecutionManager, ExecutionMetadata};
@@ -24,6 +29,7 @@ use crate::task::model::Task;
 use crate::task::storage::TaskStorage;
 use crate::task::worker;
 use crate::tui;
+use crate::tui::status_feed::{FeedUpdate, StatusFeed};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TabId {
@@ -173,6 +179,7 @@ struct RemoveWorktreeOptions {
     dry_run: bool,
     delete_branch: bool,
     force_delete_branch: bool,
+    run_hooks: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -292,6 +299,9 @@ struct AddWorktreeDialog {
     path: TextInput,
     create_branch: bool,
     force: bool,
+    copy_files: bool,
+    carry: bool,
+    run_hooks: bool,
     field: AddWorktreeField,
     error: Option<String>,
 }
@@ -304,6 +314,9 @@ impl AddWorktreeDialog {
             path: TextInput::new(""),
             create_branch: false,
             force: false,
+            copy_files: true,
+            carry: false,
+            run_hooks: true,
             field: AddWorktreeField::Branch,
             error: None,
         }
@@ -427,7 +440,14 @@ struct AppState {
     status_auto_refresh: bool,
     status_refresh_interval_override_ms: Option<u64>,
 
+    status_feed: StatusFeed,
     statuses: Vec<WorktreeStatus>,
+    /// Repository of each worktree (by path), for the tree view.
+    repo_ids: HashMap<String, RepoId>,
+    status_tree: bool,
+    /// Tree rows when `status_tree` is set; `status_state` then selects a row.
+    status_rows: Vec<TreeRow>,
+    collapsed_groups: HashSet<String>,
     status_state: TableState,
     needs_status_refresh: bool,
     last_status_refresh: Instant,
@@ -490,7 +510,12 @@ impl AppState {
             status_stale_days: 14,
             status_auto_refresh: true,
             status_refresh_interval_override_ms: None,
+            status_feed: StatusFeed::default(),
             statuses: Vec::new(),
+            repo_ids: HashMap::new(),
+            status_tree: false,
+            status_rows: Vec::new(),
+            collapsed_groups: HashSet::new(),
             status_state,
             needs_status_refresh: true,
             last_status_refresh: Instant::now(),
@@ -523,21 +548,47 @@ impl AppState {
         self.status_state.selected().unwrap_or(0)
     }
 
+    /// Rows in the status table: one per worktree, or the tree rows.
+    fn status_row_count(&self) -> usize {
+        if self.status_tree {
+            self.status_rows.len()
+        } else {
+            self.statuses.len()
+        }
+    }
+
+    /// The worktree on the selected row; `None` on a group row of the tree.
+    fn selected_status(&self) -> Option<&WorktreeStatus> {
+        if self.status_tree {
+            match self.status_rows.get(self.selected_status_index())? {
+                TreeRow::Worktree { index, .. } => self.statuses.get(*index),
+                TreeRow::Group { .. } => None,
+            }
+        } else {
+            let idx = self
+                .selected_status_index()
+                .min(self.statuses.len().checked_sub(1)?);
+            self.statuses.get(idx)
+        }
+    }
+
     fn clamp_status_selection(&mut self) {
-        if self.statuses.is_empty() {
+        let rows = self.status_row_count();
+        if rows == 0 {
             self.status_state.select(Some(0));
             return;
         }
-        let idx = self.selected_status_index().min(self.statuses.len() - 1);
+        let idx = self.selected_status_index().min(rows - 1);
         self.status_state.select(Some(idx));
     }
 
     fn move_status_selection(&mut self, delta: i64) {
-        if self.statuses.is_empty() {
+        let rows = self.status_row_count();
+        if rows == 0 {
             return;
         }
         let cur = i64::try_from(self.selected_status_index()).unwrap_or(0);
-        let max = i64::try_from(self.statuses.len().saturating_sub(1)).unwrap_or(0);
+        let max = i64::try_from(rows.saturating_sub(1)).unwrap_or(0);
         let next = (cur + delta).clamp(0, max);
         let next = usize::try_from(next).unwrap_or(0);
         self.status_state.select(Some(next));
@@ -629,13 +680,26 @@ pub async fn run(cfg: crate::config::Config) -> anyhow::Result<()> {
             break;
         }
 
-        let refresh_interval_ms = app
-            .status_refresh_interval_override_ms
-            .unwrap_or(app.cfg.status.refresh_interval_ms)
-            .max(100);
-        let refresh_interval = Duration::from_millis(refresh_interval_ms);
-        if app.status_auto_refresh && app.last_status_refresh.elapsed() >= refresh_interval {
-            app.needs_status_refresh = true;
+        // With a watcher, changed worktrees are re-collected as they change and
+        // full refreshes are only needed to fetch remotes again.
+        let full_refresh_every = if app.status_feed.is_watching() {
+            app.status_fetch_remote
+                .then(|| Duration::from_secs(app.cfg.status.fetch_interval_seconds))
+        } else {
+            Some(status_refresh_interval(&app))
+        };
+        if app.status_auto_refresh {
+            if full_refresh_every.is_some_and(|every| app.last_status_refresh.elapsed() >= every) {
+                app.needs_status_refresh = true;
+            } else if !app.needs_status_refresh {
+                let collector = status_collector(&app);
+                match app.status_feed.update(&collector).await {
+                    Ok(FeedUpdate::Idle) => {}
+                    Ok(FeedUpdate::Updated) => apply_status_view(&mut app),
+                    Ok(FeedUpdate::Rescan) => app.needs_status_refresh = true,
+                    Err(e) => app.last_error = Some(e.to_string()),
+                }
+            }
         }
 
         if app.needs_status_refresh {
@@ -663,7 +727,7 @@ pub async fn run(cfg: crate::config::Config) -> anyhow::Result<()> {
         }
 
         if app.needs_config_refresh {
-            app.config_text = config::list_resolved_toml().unwrap_or_else(|e| e.to_string());
+            app.config_text = config_text(app.global);
             app.needs_config_refresh = false;
         }
 
@@ -681,33 +745,131 @@ async fn refresh_all(app: &mut AppState) {
     let _ = refresh_statuses(app).await;
     let _ = refresh_tasks(app);
     let _ = refresh_sessions(app);
-    app.config_text = config::list_resolved_toml().unwrap_or_else(|e| e.to_string());
+    app.config_text = config_text(app.global);
     app.needs_config_refresh = false;
 }
 
+/// The config tab's text: resolved for the current repository unless the
+/// view is global.
+fn config_text(global: bool) -> String {
+    let target = (!global)
+        .then(Git::from_cwd)
+        .and_then(Result::ok)
+        .map(|git| worktree::repo_target(&git, true));
+    config::list_resolved_toml(target.as_ref()).unwrap_or_else(|e| e.to_string())
+}
+
 async fn refresh_statuses(app: &mut AppState) -> anyhow::Result<()> {
-    let worktrees = load_worktrees(&app.cfg, app.global)?;
+    let (worktrees, repo_ids) = load_worktrees(&app.cfg, app.global)?;
+    app.repo_ids = repo_ids;
+    let collector = status_collector(app);
+    let poll_interval = status_refresh_interval(app);
+    app.status_feed
+        .reload(worktrees, &collector, app.cfg.status.watch, poll_interval)
+        .await?;
+    apply_status_view(app);
+    Ok(())
+}
 
-    let collector = StatusCollector::new(StatusCollectorOptions {
+fn status_collector(app: &AppState) -> StatusCollector {
+    StatusCollector::new(StatusCollectorOptions {
         include_process: app.status_show_processes,
         fetch_remote: app.status_fetch_remote,
+        include_base: true,
+        fetch_interval: Duration::from_secs(app.cfg.status.fetch_interval_seconds),
         stale_threshold: Duration::from_secs(app.status_stale_days.saturating_mul(24 * 60 * 60)),
         base_dir: app.cfg.worktree.base_dir.clone(),
         concurrency: app.cfg.status.concurrency,
-    });
+        activity: app.cfg.status.activity,
+        cache_dir: app.cfg.status.cache_path(),
+    })
+}
 
-    let mut statuses = collector.collect_all(&worktrees).await?;
+fn status_refresh_interval(app: &AppState) -> Duration {
+    let ms = app
+        .status_refresh_interval_override_ms
+        .unwrap_or(app.cfg.status.refresh_interval_ms)
+        .max(100);
+    Duration::from_millis(ms)
+}
 
+/// Rebuilds the visible status rows from the feed with the current filter and sort.
+fn apply_status_view(app: &mut AppState) {
+    let mut statuses = app.status_feed.statuses().to_vec();
     if !app.filter.trim().is_empty() && app.filter.trim() != "all" {
-        statuses = status::filter_statuses(statuses, &app.filter);
+        match status::filter_statuses(statuses.clone(), &app.filter) {
+            Ok(filtered) => statuses = filtered,
+            Err(e) => app.last_error = Some(e.to_string()),
+        }
     }
-    if !app.sort.trim().is_empty() {
-        status::sort_statuses(&mut statuses, &app.sort);
+    if let Err(e) = status::sort_statuses(&mut statuses, &app.sort) {
+        app.last_error = Some(e.to_string());
     }
-
+    let selected = selected_tree_row_key(app);
     app.statuses = statuses;
+    rebuild_status_tree(app, selected);
+}
+
+/// The group key or worktree path on the selected tree row.
+fn selected_tree_row_key(app: &AppState) -> Option<String> {
+    match app.status_rows.get(app.selected_status_index())? {
+        TreeRow::Group { key, .. } => Some(key.clone()),
+        TreeRow::Worktree { index, .. } => app.statuses.get(*index).map(|s| s.path.clone()),
+    }
+}
+
+/// Recomputes the tree rows from `statuses`, moving the selection to the
+/// row of `selected` (see [`selected_tree_row_key`]) where it still exists.
+fn rebuild_status_tree(app: &mut AppState, selected: Option<String>) {
+    if !app.status_tree {
+        app.status_rows.clear();
+        app.clamp_status_selection();
+        return;
+    }
+    let groups = tree::build_tree(&app.statuses, |s| app.repo_ids.get(&s.path).cloned());
+    app.status_rows = tree::flatten(&groups, &app.collapsed_groups);
+    let position = selected.and_then(|sel| {
+        app.status_rows.iter().position(|row| match row {
+            TreeRow::Group { key, .. } => *key == sel,
+            TreeRow::Worktree { index, .. } => app.statuses[*index].path == sel,
+        })
+    });
+    if let Some(i) = position {
+        app.status_state.select(Some(i));
+    }
     app.clamp_status_selection();
-    Ok(())
+}
+
+/// Expands or collapses the group on the selected row (`None` toggles).
+/// Collapsing from a worktree row collapses its repository and selects it.
+fn set_selected_group_expanded(app: &mut AppState, expand: Option<bool>) {
+    let idx = app.selected_status_index();
+    let Some(row) = app.status_rows.get(idx) else {
+        return;
+    };
+    let (key, expanded) = match row {
+        TreeRow::Group { key, expanded, .. } => (key.clone(), *expanded),
+        TreeRow::Worktree { depth, .. } => {
+            if expand == Some(true) {
+                return;
+            }
+            let depth = *depth;
+            let Some(parent) = app.status_rows[..idx]
+                .iter()
+                .rposition(|r| r.depth() < depth)
+            else {
+                return;
+            };
+            app.status_state.select(Some(parent));
+            return set_selected_group_expanded(app, Some(false));
+        }
+    };
+    if expand.unwrap_or(!expanded) {
+        app.collapsed_groups.remove(&key);
+    } else {
+        app.collapsed_groups.insert(key.clone());
+    }
+    rebuild_status_tree(app, Some(key));
 }
 
 fn refresh_tasks(app: &mut AppState) -> anyhow::Result<()> {
@@ -749,26 +911,44 @@ fn refresh_sessions(app: &mut AppState) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn load_worktrees(cfg: &crate::config::Config, global: bool) -> anyhow::Result<Vec<Worktree>> {
+/// The worktrees to show and the repository each belongs to, by path.
+fn load_worktrees(
+    cfg: &crate::config::Config,
+    global: bool,
+) -> anyhow::Result<(Vec<Worktree>, HashMap<String, RepoId>)> {
     if !global && let Ok(git) = Git::from_cwd() {
-        let wm = WorktreeManager::new(git, cfg.clone());
-        return Ok(wm.list()?);
-    }
-
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
-    Ok(entries
+        let id = git
+            .get_repository_url()
+            .ok()
+            .and_then(|url| parse_origin_url(&url));
+        let worktrees = WorktreeManager::new(git, cfg.clone()).list()?;
+        let ids = worktrees
+            .iter()
+            .filter_map(|w| Some((w.path.clone(), id.clone()?)))
+            .collect();
+        return Ok((worktrees, ids));
+    }
+
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
+    let ids = entries
+        .iter()
+        .filter_map(|e| Some((e.path.clone(), parse_origin_url(&e.repository_url)?)))
+        .collect();
+    let worktrees = entries
         .into_iter()
         .map(|e| Worktree {
+            is_detached: e.branch == "HEAD",
             path: e.path,
             branch: e.branch,
             commit_hash: e.commit_hash,
-            is_main: false,
+            is_main: e.is_main,
             created_at: "0001-01-01T00:00:00Z".to_owned(),
+            is_locked: e.is_locked,
+            lock_reason: e.lock_reason,
+            ..Worktree::default()
         })
-        .collect())
+        .collect();
+    Ok((worktrees, ids))
 }
 
 #[allow(clippy::too_many_lines)]
@@ -1005,7 +1185,7 @@ fn draw_footer(f: &mut Frame<'_>, area: Rect, app: &AppState) {
 
     let mut left = match effective_mode {
         Mode::Normal => match app.tab {
-            TabId::Status => "q quit • 1-5 tabs • j/k move • a add • / filter/search • s sort • v verbose • g local/global • w watch • i interval • n fetch • t stale • T task • r refresh • p prune • d remove • e exec • o exec-stay • : command".to_owned(),
+            TabId::Status => "q quit • 1-5 tabs • j/k move • a add • / filter/search • s sort • v verbose • g local/global • z tree • w watch • i interval • n fetch • t stale • T task • r refresh • p prune • L lock • d remove • e exec • o exec-stay • : command".to_owned(),
             TabId::Tasks => "q quit • 1-5 tabs • j/k move • / search • n new • Enter logs • W start-worker • S stop-worker • R reset • D delete • l execs • w worker • : command".to_owned(),
             TabId::Mux => "q quit • 1-5 tabs • j/k move • a attach • x kill • r refresh • : command".to_owned(),
             TabId::Config => "q quit • 1-5 tabs • j/k scroll • r reload • e set • : command".to_owned(),
@@ -1016,7 +1196,7 @@ fn draw_footer(f: &mut Frame<'_>, area: Rect, app: &AppState) {
         Mode::NewTask | Mode::ConfigEdit => "Enter next/apply • Tab switch field • Esc cancel".to_owned(),
         Mode::Confirm => match app.confirm.as_ref().map(|c| &c.action) {
             Some(ConfirmAction::RemoveSelectedWorktree(_)) => {
-                "y confirm • n cancel • f force • d dry-run • b delete-branch • B force-delete-branch"
+                "y confirm • n cancel • f force • d dry-run • b delete-branch • B force-delete-branch • h hooks"
                     .to_owned()
             }
             Some(ConfirmAction::KillSelectedSession) => "y kill • n cancel".to_owned(),
@@ -1024,7 +1204,7 @@ fn draw_footer(f: &mut Frame<'_>, area: Rect, app: &AppState) {
             None => "y confirm • n cancel".to_owned(),
         },
         Mode::Output => "q/Esc close • j/k scroll • PgUp/PgDn • g/G top/bottom • r refresh • f follow • p pretty".to_owned(),
-        Mode::AddWorktree => "Enter apply • Tab switch field • Esc cancel • b create-branch • f force".to_owned(),
+        Mode::AddWorktree => "Enter apply • Tab switch field • Esc cancel • b create-branch • f force • c copy-files • C carry-changes • h hooks".to_owned(),
     };
 
     if let Some(err) = &app.last_error {
@@ -1035,14 +1215,19 @@ fn draw_footer(f: &mut Frame<'_>, area: Rect, app: &AppState) {
 
     let mut right = String::new();
     if app.tab == TabId::Status {
-        let interval_ms = app
-            .status_refresh_interval_override_ms
-            .unwrap_or(app.cfg.status.refresh_interval_ms)
-            .max(100);
-        let interval = format_interval_ms(interval_ms);
+        let interval = if app.status_feed.is_watching() {
+            app.status_feed.mode_label().to_owned()
+        } else {
+            let interval_ms = app
+                .status_refresh_interval_override_ms
+                .unwrap_or(app.cfg.status.refresh_interval_ms)
+                .max(100);
+            format_interval_ms(interval_ms)
+        };
         right = format!(
-            "{} • Filter: {} • Sort: {} • Watch: {} ({interval}) • Fetch: {} • Proc: {} • Stale: {}d • Verbose: {}",
+            "{}{} • Filter: {} • Sort: {} • Watch: {} ({interval}) • Fetch: {} • Proc: {} • Stale: {}d • Verbose: {}",
             if app.global { "Global" } else { "Local" },
+            if app.status_tree { " (tree)" } else { "" },
             if effective_mode == Mode::Filtering {
                 app.filter_input.as_str()
             } else {
@@ -1099,6 +1284,7 @@ fn draw_status_table(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
             "STATUS",
             "CHANGES",
             "AHEAD/BEHIND",
+            "BASE",
             "ACTIVITY",
         ])
     } else {
@@ -1106,14 +1292,19 @@ fn draw_status_table(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
     }
     .style(Style::default().add_modifier(Modifier::BOLD));
 
-    let rows = app.statuses.iter().map(|s| {
+    let worktree_row = |s: &WorktreeStatus, indent: &str| {
         let marker = if s.is_current && app.cfg.ui.icons {
             "● "
         } else {
             "  "
         };
-        let branch = format!("{marker}{}", s.branch);
-        let changes = format_changes(s.git_status);
+        let lock = match (s.is_locked, app.cfg.ui.icons) {
+            (true, true) => " 🔒",
+            (true, false) => " [locked]",
+            (false, _) => "",
+        };
+        let branch = format!("{indent}{marker}{}{lock}", s.branch);
+        let changes = format_changes(&s.git_status);
         let activity = format_activity(&s.last_activity);
         let status_cell = Cell::from(status::format_status_for_table(s.status))
             .style(worktree_state_style(s.status));
@@ -1122,7 +1313,12 @@ fn draw_status_table(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
                 Cell::from(branch),
                 status_cell,
                 Cell::from(changes),
-                Cell::from(format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind)),
+                if s.fetch_error.is_some() {
+                    Cell::from("fetch failed").style(Style::default().fg(Color::Red))
+                } else {
+                    Cell::from(format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind))
+                },
+                Cell::from(status::format_base_divergence(s)),
                 Cell::from(activity),
             ])
         } else {
@@ -1133,14 +1329,52 @@ fn draw_status_table(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
                 Cell::from(activity),
             ])
         }
-    });
+    };
+
+    let rows: Vec<Row<'_>> = if app.status_tree {
+        app.status_rows
+            .iter()
+            .map(|row| {
+                let indent = "  ".repeat(row.depth());
+                match row {
+                    TreeRow::Worktree { index, .. } => worktree_row(&app.statuses[*index], &indent),
+                    TreeRow::Group {
+                        name,
+                        stats,
+                        expanded,
+                        ..
+                    } => {
+                        let arrow = if *expanded { "▾" } else { "▸" };
+                        let mut cells = vec![
+                            Cell::from(format!("{indent}{arrow} {name}"))
+                                .style(Style::default().add_modifier(Modifier::BOLD)),
+                            Cell::from(format!("{} wt", stats.worktrees)),
+                            Cell::from(if stats.dirty > 0 {
+                                format!("{} dirty", stats.dirty)
+                            } else {
+                                String::new()
+                            }),
+                        ];
+                        cells.push(Cell::from(format!("↑{} ↓{}", stats.ahead, stats.behind)));
+                        if app.status_verbose {
+                            cells.extend([Cell::from(""), Cell::from("")]);
+                        }
+                        Row::new(cells)
+                    }
+                }
+            })
+            .collect()
+    } else {
+        app.statuses.iter().map(|s| worktree_row(s, "")).collect()
+    };
 
     let widths = if app.status_verbose {
         vec![
-            Constraint::Percentage(30),
+            Constraint::Percentage(26),
             Constraint::Length(12),
-            Constraint::Percentage(30),
+            Constraint::Percentage(26),
             Constraint::Length(14),
+            Constraint::Length(10),
             Constraint::Length(14),
         ]
     } else {
@@ -1171,21 +1405,24 @@ fn draw_status_detail(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    if app.statuses.is_empty() {
-        let p = Paragraph::new("No worktrees.").wrap(Wrap { trim: true });
-        f.render_widget(p, inner);
+    let Some(s) = app.selected_status() else {
+        let text = match app.status_rows.get(app.selected_status_index()) {
+            Some(TreeRow::Group { name, stats, .. }) => format!(
+                "{name}\n\nWorktrees: {}\nDirty: {}\nAhead/behind: ↑{} ↓{}",
+                stats.worktrees, stats.dirty, stats.ahead, stats.behind
+            ),
+            _ => "No worktrees.".to_owned(),
+        };
+        f.render_widget(Paragraph::new(text).wrap(Wrap { trim: true }), inner);
         return;
-    }
-
-    let idx = app.selected_status_index().min(app.statuses.len() - 1);
-    let s = &app.statuses[idx];
+    };
 
     let mut path = s.path.clone();
     if app.cfg.ui.tilde_home {
         path = config::tilde_path(&path);
     }
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
             Span::styled("Branch: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(&s.branch),
@@ -1206,27 +1443,69 @@ fn draw_status_detail(f: &mut Frame<'_>, area: Rect, app: &mut AppState) {
             Span::styled("Status: ", Style::default().add_modifier(Modifier::BOLD)),
             Span::raw(status::format_status_for_table(s.status)),
         ]),
-        Line::from(format!(
-            "Changes: {} added, {} modified, {} deleted, {} untracked",
-            s.git_status.added, s.git_status.modified, s.git_status.deleted, s.git_status.untracked
-        )),
+        Line::from(if s.is_locked {
+            format!("Locked: {}", s.lock_reason.as_deref().unwrap_or("yes"))
+        } else {
+            "Locked: no".to_owned()
+        }),
+        Line::from(format!("Changes: {}", format_changes(&s.git_status))),
         Line::from(format!(
             "Ahead/Behind: ↑{} ↓{}",
             s.git_status.ahead, s.git_status.behind
         )),
+        Line::from(format!(
+            "Base ({}): {}",
+            s.base.as_deref().unwrap_or("unknown"),
+            status::format_base_divergence(s)
+        )),
+        Line::from(format!(
+            "Last fetch: {}",
+            s.last_fetch
+                .as_deref()
+                .map_or_else(|| "never".to_owned(), format_activity)
+        )),
         Line::from(format!(
             "Last activity: {}",
             format_activity(&s.last_activity)
         )),
-        Line::from(""),
-        Line::from(vec![
-            Span::styled("Tip: ", Style::default().add_modifier(Modifier::BOLD)),
-            Span::raw(
-                "Press 'T' to queue a task for this worktree, or ':' to run any CLI command.",
-            ),
-        ]),
     ];
 
+    if let Some(err) = &s.fetch_error {
+        lines.push(Line::from(Span::styled(
+            format!("Fetch failed: {err}"),
+            Style::default().fg(Color::Red),
+        )));
+    }
+
+    if !s.git_status.files.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(
+            status::format_file_changes(&s.git_status.files, 15)
+                .into_iter()
+                .map(Line::from),
+        );
+    }
+
+    if app.status_show_processes {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            format!("Processes ({}):", s.processes.len()),
+            Style::default().add_modifier(Modifier::BOLD),
+        )));
+        if s.processes.is_empty() {
+            lines.push(Line::from("  none"));
+        }
+        for p in &s.processes {
+            lines.push(Line::from(format!("  {:>7}  {}", p.pid, p.command)));
+        }
+    }
+
+    lines.push(Line::from(""));
+    lines.push(Line::from(vec![
+        Span::styled("Tip: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw("Press 'T' to queue a task for this worktree, or ':' to run any CLI command."),
+    ]));
+
     let p = Paragraph::new(lines).wrap(Wrap { trim: false });
     f.render_widget(p, inner);
 }
@@ -1575,8 +1854,12 @@ fn draw_help_tab(f: &mut Frame<'_>, area: Rect, _app: &mut AppState) {
         Line::from("  T           New task for selected worktree"),
         Line::from("  P           Toggle process detection"),
         Line::from("  g           Toggle local/global"),
+        Line::from("  z           Toggle tree (host → owner → repo)"),
+        Line::from("  Enter/Space Expand/collapse group (tree)"),
+        Line::from("  ←/→         Collapse/expand group (tree)"),
         Line::from("  r           Refresh"),
         Line::from("  p           Prune worktrees (current repo)"),
+        Line::from("  L           Lock/unlock selected worktree"),
         Line::from("  d           Remove selected worktree"),
         Line::from("  e           Exec in selected worktree"),
         Line::from("  o           Exec+stay in selected worktree"),
@@ -1634,6 +1917,10 @@ fn draw_confirm(f: &mut Frame<'_>, confirm: &ConfirmDialog) {
                 "off"
             }
         )));
+        lines.push(Line::from(format!(
+            "  [h] hooks: {}",
+            if opts.run_hooks { "on" } else { "off" }
+        )));
         lines.push(Line::from(""));
     }
 
@@ -1841,9 +2128,12 @@ fn draw_add_worktree_popup(f: &mut Frame<'_>, app: &AppState, dialog: &AddWorktr
         ]),
         Line::from(""),
         Line::from(format!(
-            "[Tab] switch field   [b] create-branch: {}   [f] force: {}",
+            "[Tab] switch field   [b] create-branch: {}   [f] force: {}   [c] copy files: {}   [C] carry changes: {}   [h] hooks: {}",
             if dialog.create_branch { "on" } else { "off" },
-            if dialog.force { "on" } else { "off" }
+            if dialog.force { "on" } else { "off" },
+            if dialog.copy_files { "on" } else { "off" },
+            if dialog.carry { "on" } else { "off" },
+            if dialog.run_hooks { "on" } else { "off" }
         )),
         Line::from("Enter apply • Esc cancel"),
     ];
@@ -1992,6 +2282,11 @@ fn handle_key(key: KeyEvent, app: &mut AppState, guard: &mut TerminalGuard) -> a
                 match app.filter_target {
                     FilterTarget::Status => {
                         let next = app.filter_input.text.trim();
+                        if let Err(e) = StatusQuery::parse(next) {
+                            app.last_error = Some(e.to_string());
+                            return Ok(());
+                        }
+                        app.last_error = None;
                         app.filter = if next.is_empty() {
                             "all".to_owned()
                         } else {
@@ -2128,9 +2423,6 @@ fn handle_status_tab_key(key: KeyEvent, app: &mut AppState, guard: &mut Terminal
         KeyCode::Char('P') => {
             app.status_show_processes = !app.status_show_processes;
             app.needs_status_refresh = true;
-            if app.status_show_processes {
-                app.last_error = Some("process detection not implemented yet".to_owned());
-            }
         }
         KeyCode::Char('i') => {
             app.prompt = Some(PromptDialog::status_refresh_interval(app));
@@ -2141,17 +2433,33 @@ fn handle_status_tab_key(key: KeyEvent, app: &mut AppState, guard: &mut Terminal
             app.mode = Mode::Prompt;
         }
         KeyCode::Char('T') => {
-            if app.statuses.is_empty() {
+            let Some(s) = app.selected_status() else {
                 return;
-            }
-            let idx = app.selected_status_index().min(app.statuses.len() - 1);
-            let branch = app.statuses[idx].branch.clone();
+            };
+            let branch = s.branch.clone();
             open_new_task(app, &branch);
         }
         KeyCode::Char('g') => {
             app.global = !app.global;
             app.needs_status_refresh = true;
         }
+        KeyCode::Char('z') => {
+            let selected = app.selected_status().map(|s| s.path.clone());
+            app.status_tree = !app.status_tree;
+            match selected {
+                Some(path) if !app.status_tree => {
+                    let idx = app.statuses.iter().position(|s| s.path == path);
+                    app.status_state.select(idx);
+                    app.clamp_status_selection();
+                }
+                selected => rebuild_status_tree(app, selected),
+            }
+        }
+        KeyCode::Enter | KeyCode::Char(' ') if app.status_tree => {
+            set_selected_group_expanded(app, None);
+        }
+        KeyCode::Left if app.status_tree => set_selected_group_expanded(app, Some(false)),
+        KeyCode::Right if app.status_tree => set_selected_group_expanded(app, Some(true)),
         KeyCode::Char('s') => {
             app.sort = next_sort(&app.sort).to_owned();
             app.needs_status_refresh = true;
@@ -2164,22 +2472,40 @@ fn handle_status_tab_key(key: KeyEvent, app: &mut AppState, guard: &mut Terminal
             // prune current repo if possible
             if let Ok(git) = Git::from_cwd() {
                 let wm = WorktreeManager::new(git, app.cfg.clone());
-                if let Err(e) = wm.prune() {
-                    app.last_error = Some(e.to_string());
-                } else {
-                    app.last_error = None;
-                    app.needs_status_refresh = true;
+                match wm.prune(false) {
+                    Ok(skipped) => {
+                        app.last_error = None;
+                        app.needs_status_refresh = true;
+                        if !skipped.is_empty() {
+                            app.toast = Some(Toast::info(format!(
+                                "Pruned; skipped {} locked worktree(s) (use `prune --force`)",
+                                skipped.len()
+                            )));
+                        }
+                    }
+                    Err(e) => app.last_error = Some(e.to_string()),
                 }
             } else {
                 app.last_error = Some("prune requires being inside a git repo".to_owned());
             }
         }
-        KeyCode::Char('d') => {
-            if app.statuses.is_empty() {
+        KeyCode::Char('L') => {
+            if app.selected_status().is_none() {
                 return;
             }
-            let idx = app.selected_status_index().min(app.statuses.len() - 1);
-            let s = &app.statuses[idx];
+            match toggle_selected_worktree_lock(app) {
+                Ok(msg) => {
+                    app.last_error = None;
+                    app.toast = Some(Toast::info(msg));
+                    app.needs_status_refresh = true;
+                }
+                Err(e) => app.last_error = Some(e.to_string()),
+            }
+        }
+        KeyCode::Char('d') => {
+            let Some(s) = app.selected_status() else {
+                return;
+            };
             app.confirm = Some(ConfirmDialog {
                 title: "Remove worktree".to_owned(),
                 message: format!(
@@ -2193,25 +2519,22 @@ fn handle_status_tab_key(key: KeyEvent, app: &mut AppState, guard: &mut Terminal
                     dry_run: false,
                     delete_branch: false,
                     force_delete_branch: false,
+                    run_hooks: true,
                 }),
             });
         }
         KeyCode::Char('e') => {
-            if app.statuses.is_empty() {
+            let Some(s) = app.selected_status() else {
                 return;
-            }
-            let idx = app.selected_status_index().min(app.statuses.len() - 1);
-            let s = &app.statuses[idx];
+            };
             let p = quote_arg(&s.path);
             app.command_input = TextInput::new(format!("exec {p} -- "));
             app.mode = Mode::Command;
         }
         KeyCode::Char('o') => {
-            if app.statuses.is_empty() {
+            let Some(s) = app.selected_status() else {
                 return;
-            }
-            let idx = app.selected_status_index().min(app.statuses.len() - 1);
-            let s = &app.statuses[idx];
+            };
             let p = quote_arg(&s.path);
             app.command_input = TextInput::new(format!("exec -s {p} -- "));
             app.mode = Mode::Command;
@@ -2457,6 +2780,13 @@ fn handle_confirm_key(key: KeyEvent, app: &mut AppState, guard: &mut TerminalGua
                 }
             }
         }
+        KeyCode::Char('h') => {
+            if let Some(confirm) = app.confirm.as_mut()
+                && let ConfirmAction::RemoveSelectedWorktree(opts) = &mut confirm.action
+            {
+                opts.run_hooks = !opts.run_hooks;
+            }
+        }
         _ => {}
     }
 }
@@ -2659,12 +2989,18 @@ fn handle_add_worktree_key(key: KeyEvent, app: &mut AppState) {
         }
         KeyCode::Char('b') => dialog.create_branch = !dialog.create_branch,
         KeyCode::Char('f') => dialog.force = !dialog.force,
+        KeyCode::Char('c') => dialog.copy_files = !dialog.copy_files,
+        KeyCode::Char('C') => dialog.carry = !dialog.carry,
+        KeyCode::Char('h') => dialog.run_hooks = !dialog.run_hooks,
         KeyCode::Enter => {
             let repo_dir = dialog.repo_dir.clone();
             let branch = dialog.branch.text.trim().to_owned();
             let path_raw = dialog.path.text.trim().to_owned();
             let create_branch = dialog.create_branch;
             let force = dialog.force;
+            let copy_files = dialog.copy_files;
+            let carry = dialog.carry;
+            let run_hooks = dialog.run_hooks;
 
             if branch.is_empty() {
                 dialog.error = Some("branch is required".to_owned());
@@ -2684,12 +3020,25 @@ fn handle_add_worktree_key(key: KeyEvent, app: &mut AppState) {
                         return;
                     }
 
-                    match wm.add(&branch, custom_path.as_deref(), create_branch) {
-                        Ok(()) => {
+                    let added = if carry {
+                        wm.add_carrying_changes(&branch, custom_path.as_deref(), create_branch)
+                    } else {
+                        wm.add(&branch, custom_path.as_deref(), create_branch)
+                    };
+                    match added {
+                        Ok(path) => {
                             app.add_worktree = None;
                             app.mode = Mode::Normal;
                             app.last_error = None;
                             app.needs_status_refresh = true;
+                            let (out, result) =
+                                finish_added_worktree(&wm, &branch, &path, copy_files, run_hooks);
+                            if !out.is_empty() {
+                                app.output = Some(OutputViewer::new("Add worktree", out));
+                            }
+                            if let Err(e) = result {
+                                app.last_error = Some(e.to_string());
+                            }
                         }
                         Err(e) => dialog.error = Some(e.to_string()),
                     }
@@ -2704,6 +3053,49 @@ fn handle_add_worktree_key(key: KeyEvent, app: &mut AppState) {
     }
 }
 
+/// Copies local files and runs `post_add` hooks for a freshly added worktree,
+/// returning the combined report and hook output, which is kept up to the
+/// point of a failure.
+fn finish_added_worktree(
+    wm: &WorktreeManager,
+    branch: &str,
+    path: &std::path::Path,
+    copy_files: bool,
+    run_hooks: bool,
+) -> (String, anyhow::Result<()>) {
+    use std::fmt::Write as _;
+
+    let mut out = String::new();
+    if copy_files {
+        let report = match wm.copy_local_files(path) {
+            Ok(report) => report,
+            Err(e) => return (out, Err(e.into())),
+        };
+        for f in &report.copied {
+            let _ = writeln!(&mut out, "Copied: {f}");
+        }
+        for f in &report.linked {
+            let _ = writeln!(&mut out, "Linked: {f}");
+        }
+        for (f, reason) in &report.skipped {
+            let _ = writeln!(&mut out, "Skipped: {f} ({reason})");
+        }
+    }
+    let result = if run_hooks {
+        wm.run_hooks(
+            HookKind::PostAdd,
+            branch,
+            path,
+            &wm.main_root(),
+            HookOutput::Capture(&mut out),
+        )
+        .map_err(Into::into)
+    } else {
+        Ok(())
+    };
+    (out, result)
+}
+
 fn handle_config_edit_key(key: KeyEvent, app: &mut AppState) {
     let Some(dialog) = app.config_edit.as_mut() else {
         app.mode = Mode::Normal;
@@ -2884,12 +3276,11 @@ fn handle_text_input_key(key: KeyEvent, input: &mut TextInput) {
         KeyCode::Right => input.move_right(),
         KeyCode::Home => input.move_home(),
         KeyCode::End => input.move_end(),
-        KeyCode::Char(c) => {
+        KeyCode::Char(c)
             if !key.modifiers.contains(KeyModifiers::CONTROL)
-                && !key.modifiers.contains(KeyModifiers::ALT)
-            {
-                input.insert_char(c);
-            }
+                && !key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            input.insert_char(c);
         }
         _ => {}
     }
@@ -3048,19 +3439,16 @@ fn selected_worktree_dir_for_command(app: &AppState) -> anyhow::Result<PathBuf>
     if Git::from_cwd().is_ok() {
         return Ok(std::env::current_dir()?);
     }
-    if app.statuses.is_empty() {
-        anyhow::bail!("no worktrees available");
-    }
-    let idx = app.selected_status_index().min(app.statuses.len() - 1);
-    Ok(PathBuf::from(app.statuses[idx].path.clone()))
+    let Some(s) = app.selected_status() else {
+        anyhow::bail!("no worktree selected");
+    };
+    Ok(PathBuf::from(s.path.clone()))
 }
 
 fn remove_selected_worktree(app: &mut AppState, opts: RemoveWorktreeOptions) -> anyhow::Result<()> {
-    if app.statuses.is_empty() {
+    let Some(s) = app.selected_status() else {
         return Ok(());
-    }
-    let idx = app.selected_status_index().min(app.statuses.len() - 1);
-    let s = &app.statuses[idx];
+    };
     if s.is_current {
         anyhow::bail!(
             "refusing to remove the current worktree (cd to another directory and retry)"
@@ -3070,22 +3458,10 @@ fn remove_selected_worktree(app: &mut AppState, opts: RemoveWorktreeOptions) ->
     let wt_root = PathBuf::from(&s.path);
     let git = Git::from_dir(&wt_root)?;
 
-    // Run the removal from the repo's common dir parent (main worktree) to avoid
-    // edge-cases when invoked from within other worktrees.
-    let common_dir_raw = git.run(&["rev-parse", "--git-common-dir"])?;
-    let common_dir_raw = common_dir_raw.trim();
-    let common_dir_path = PathBuf::from(common_dir_raw);
-    let common_dir_abs = if common_dir_path.is_absolute() {
-        common_dir_path
-    } else {
-        wt_root.join(common_dir_path)
-    };
-    let main_root = common_dir_abs
-        .parent()
-        .ok_or_else(|| anyhow::anyhow!("failed to resolve main worktree dir"))?
-        .to_path_buf();
-
-    let main_git = Git::new(main_root);
+    // Run the removal from the main worktree to avoid edge-cases when invoked
+    // from within other worktrees.
+    let main_root = git.main_worktree_root()?;
+    let main_git = Git::new(main_root.clone());
     if opts.dry_run {
         use std::fmt::Write as _;
 
@@ -3102,7 +3478,7 @@ fn remove_selected_worktree(app: &mut AppState, opts: RemoveWorktreeOptions) ->
             if opts.force { " --force" } else { "" },
             wt_root.display()
         );
-        if opts.delete_branch && !s.branch.trim().is_empty() {
+        if opts.delete_branch && !s.branch.trim().is_empty() && !s.is_detached {
             let _ = writeln!(
                 &mut out,
                 "  git branch {} {}",
@@ -3114,13 +3490,64 @@ fn remove_selected_worktree(app: &mut AppState, opts: RemoveWorktreeOptions) ->
         return Ok(());
     }
 
-    main_git.remove_worktree(&wt_root, opts.force)?;
-    if opts.delete_branch && !s.branch.trim().is_empty() {
-        main_git.delete_branch(&s.branch, opts.force_delete_branch)?;
+    let wm = WorktreeManager::new(main_git, app.cfg.clone());
+    let mut hook_output = String::new();
+    if opts.run_hooks {
+        wm.check_removable(&wt_root, opts.force)?;
+        if let Err(e) = wm.run_hooks(
+            HookKind::PreRemove,
+            &s.branch,
+            &wt_root,
+            &main_root,
+            HookOutput::Capture(&mut hook_output),
+        ) {
+            if !hook_output.is_empty() {
+                app.output = Some(OutputViewer::new("Remove worktree hooks", hook_output));
+            }
+            return Err(e.into());
+        }
+    }
+    wm.remove_with_branch(
+        &wt_root,
+        &s.branch,
+        opts.force,
+        opts.delete_branch && !s.branch.trim().is_empty(),
+        opts.force_delete_branch,
+    )?;
+    // The worktree is gone by now, so a failing `post_remove` hook is only
+    // reported, as the CLI does.
+    if opts.run_hooks
+        && let Err(e) = wm.run_hooks(
+            HookKind::PostRemove,
+            &s.branch,
+            &wt_root,
+            &main_root,
+            HookOutput::Capture(&mut hook_output),
+        )
+    {
+        hook_output.push_str(&format!("Error: {e}\n"));
+    }
+    if !hook_output.is_empty() {
+        app.output = Some(OutputViewer::new("Remove worktree hooks", hook_output));
     }
     Ok(())
 }
 
+fn toggle_selected_worktree_lock(app: &AppState) -> anyhow::Result<String> {
+    let Some(s) = app.selected_status() else {
+        anyhow::bail!("no worktree selected");
+    };
+    let wt_root = PathBuf::from(&s.path);
+    let git = Git::from_dir(&wt_root)?;
+    if s.is_locked {
+        git.unlock_worktree(&wt_root)?;
+        Ok(format!("Unlocked {}", s.branch))
+    } else {
+        git.lock_worktree(&wt_root, None)?;
+        Ok(format!("Locked {}", s.branch))
+    }
+}
+
 fn kill_selected_session(app: &mut AppState, _guard: &mut TerminalGuard) -> anyhow::Result<()> {
     if app.cfg.mux.backend == crate::config::MuxBackend::None {
         return Ok(());
@@ -3295,6 +3722,13 @@ fn worktree_state_style(state: status::WorktreeState) -> Style {
         status::WorktreeState::Modified => Style::default().fg(Color::Yellow),
         status::WorktreeState::Staged => Style::default().fg(Color::Cyan),
         status::WorktreeState::Conflict => Style::default().fg(Color::Red),
+        status::WorktreeState::Rebasing
+        | status::WorktreeState::Merging
+        | status::WorktreeState::CherryPicking
+        | status::WorktreeState::Reverting => Style::default()
+            .fg(Color::Magenta)
+            .add_modifier(Modifier::BOLD),
+        status::WorktreeState::Bisecting => Style::default().fg(Color::Blue),
         status::WorktreeState::Stale | status::WorktreeState::Unknown => {
             Style::default().fg(Color::DarkGray)
         }
@@ -3531,27 +3965,29 @@ fn next_sort(current: &str) -> &'static str {
         "activity" => "modified",
         "modified" | "changes" => "branch",
         "branch" | "name" => "status",
+        "status" => "base",
         _ => "activity",
     }
 }
 
-fn format_changes(gs: status::GitStatus) -> String {
-    if gs.modified == 0 && gs.added == 0 && gs.deleted == 0 && gs.untracked == 0 {
+fn format_changes(gs: &status::GitStatus) -> String {
+    let counts = [
+        (gs.added, "added"),
+        (gs.modified, "modified"),
+        (gs.deleted, "deleted"),
+        (gs.renamed, "renamed"),
+        (gs.copied, "copied"),
+        (gs.typechange, "typechange"),
+        (gs.untracked, "untracked"),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{n} {label}"))
+        .collect();
+    if parts.is_empty() {
         return "-".to_owned();
     }
-    let mut parts = Vec::new();
-    if gs.added > 0 {
-        parts.push(format!("{} added", gs.added));
-    }
-    if gs.modified > 0 {
-        parts.push(format!("{} modified", gs.modified));
-    }
-    if gs.deleted > 0 {
-        parts.push(format!("{} deleted", gs.deleted));
-    }
-    if gs.untracked > 0 {
-        parts.push(format!("{} untracked", gs.untracked));
-    }
     parts.join(", ")
 }
 
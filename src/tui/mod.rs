@@ -4,6 +4,7 @@ pub mod app;
 pub mod log_viewer;
 pub mod picker;
 pub mod status_dashboard;
+pub mod status_feed;
 
 use std::io;
 use std::io::IsTerminal as _;
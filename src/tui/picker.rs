@@ -141,12 +141,16 @@ fn handle_key(
                 multi_selected.remove(&idx);
             }
         }
+        // Guards would let `k`/`j` fall through into the query at the list
+        // edges, so these arms keep the nested `if`.
+        #[allow(clippy::collapsible_match)]
         KeyCode::Up | KeyCode::Char('k') => {
             if *selected > 0 {
                 *selected -= 1;
                 list_state.select(Some(*selected));
             }
         }
+        #[allow(clippy::collapsible_match)]
         KeyCode::Down | KeyCode::Char('j') => {
             if *selected + 1 < filtered.len() {
                 *selected += 1;
@@ -167,13 +171,12 @@ fn handle_key(
             query.pop();
             recompute_filter(query, lower_titles, filtered, selected, list_state);
         }
-        KeyCode::Char(c) => {
+        KeyCode::Char(c)
             if !key.modifiers.contains(KeyModifiers::CONTROL)
-                && !key.modifiers.contains(KeyModifiers::ALT)
-            {
-                query.push(c);
-                recompute_filter(query, lower_titles, filtered, selected, list_state);
-            }
+                && !key.modifiers.contains(KeyModifiers::ALT) =>
+        {
+            query.push(c);
+            recompute_filter(query, lower_titles, filtered, selected, list_state);
         }
         _ => {}
     }
@@ -11,9 +11,11 @@ use ratatui::widgets::{Block, Borders, Clear, Paragraph, Row, Table, TableState,
 
 use crate::core::discovery;
 use crate::core::git::Git;
+use crate::core::query::StatusQuery;
 use crate::core::status::{self, StatusCollector, StatusCollectorOptions, WorktreeStatus};
 use crate::core::worktree::{Worktree, WorktreeManager};
 use crate::tui;
+use crate::tui::status_feed::{FeedUpdate, StatusFeed};
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone)]
@@ -46,8 +48,16 @@ pub async fn run(opts: StatusDashboardOptions) -> anyhow::Result<()> {
             terminal.draw(|f| draw(f, &mut app, &opts))?;
         }
 
-        let should_refresh = app.needs_refresh || last_refresh.elapsed() >= opts.refresh_interval;
-        if should_refresh {
+        // With a watcher, changed worktrees are re-collected as they change and
+        // full refreshes are only needed to fetch remotes again.
+        let full_refresh_every = if app.feed.is_watching() {
+            opts.fetch_remote
+                .then(|| Duration::from_secs(opts.cfg.status.fetch_interval_seconds))
+        } else {
+            Some(opts.refresh_interval)
+        };
+        let due = full_refresh_every.is_some_and(|every| last_refresh.elapsed() >= every);
+        if app.needs_refresh || due {
             app.needs_refresh = false;
             if let Err(e) = refresh(&mut app, &opts).await {
                 app.last_error = Some(e.to_string());
@@ -55,6 +65,13 @@ pub async fn run(opts: StatusDashboardOptions) -> anyhow::Result<()> {
                 app.last_error = None;
             }
             last_refresh = Instant::now();
+        } else {
+            match app.feed.update(&collector(&opts)).await {
+                Ok(FeedUpdate::Idle) => {}
+                Ok(FeedUpdate::Updated) => app.apply_view(),
+                Ok(FeedUpdate::Rescan) => app.needs_refresh = true,
+                Err(e) => app.last_error = Some(e.to_string()),
+            }
         }
 
         if event::poll(Duration::from_millis(50))?
@@ -85,6 +102,7 @@ struct AppState {
     mode: Mode,
     filter_input: String,
 
+    feed: StatusFeed,
     statuses: Vec<WorktreeStatus>,
     table_state: TableState,
 
@@ -104,6 +122,7 @@ impl AppState {
             verbose: opts.verbose,
             mode: Mode::Normal,
             filter_input: String::new(),
+            feed: StatusFeed::default(),
             statuses: Vec::new(),
             table_state,
             show_detail: false,
@@ -116,6 +135,22 @@ impl AppState {
         self.table_state.selected().unwrap_or(0)
     }
 
+    /// Rebuilds the visible rows from the feed with the current filter and sort.
+    fn apply_view(&mut self) {
+        let mut statuses = self.feed.statuses().to_vec();
+        if !self.filter.trim().is_empty() {
+            match status::filter_statuses(statuses.clone(), &self.filter) {
+                Ok(filtered) => statuses = filtered,
+                Err(e) => self.last_error = Some(e.to_string()),
+            }
+        }
+        if let Err(e) = status::sort_statuses(&mut statuses, &self.sort) {
+            self.last_error = Some(e.to_string());
+        }
+        self.statuses = statuses;
+        self.clamp_selection();
+    }
+
     fn clamp_selection(&mut self) {
         if self.statuses.is_empty() {
             self.table_state.select(Some(0));
@@ -139,27 +174,30 @@ impl AppState {
 
 async fn refresh(app: &mut AppState, opts: &StatusDashboardOptions) -> anyhow::Result<()> {
     let worktrees = load_worktrees(&opts.cfg, app.global)?;
+    app.feed
+        .reload(
+            worktrees,
+            &collector(opts),
+            opts.cfg.status.watch,
+            opts.refresh_interval,
+        )
+        .await?;
+    app.apply_view();
+    Ok(())
+}
 
-    let collector = StatusCollector::new(StatusCollectorOptions {
+fn collector(opts: &StatusDashboardOptions) -> StatusCollector {
+    StatusCollector::new(StatusCollectorOptions {
         include_process: opts.show_processes,
         fetch_remote: opts.fetch_remote,
+        include_base: true,
+        fetch_interval: Duration::from_secs(opts.cfg.status.fetch_interval_seconds),
         stale_threshold: Duration::from_secs(opts.stale_days * 24 * 60 * 60),
         base_dir: opts.cfg.worktree.base_dir.clone(),
         concurrency: opts.cfg.status.concurrency,
-    });
-
-    let mut statuses = collector.collect_all(&worktrees).await?;
-
-    if !app.filter.trim().is_empty() {
-        statuses = status::filter_statuses(statuses, &app.filter);
-    }
-    if !app.sort.trim().is_empty() {
-        status::sort_statuses(&mut statuses, &app.sort);
-    }
-
-    app.statuses = statuses;
-    app.clamp_selection();
-    Ok(())
+        activity: opts.cfg.status.activity,
+        cache_dir: opts.cfg.status.cache_path(),
+    })
 }
 
 fn load_worktrees(cfg: &crate::config::Config, global: bool) -> anyhow::Result<Vec<Worktree>> {
@@ -168,18 +206,19 @@ fn load_worktrees(cfg: &crate::config::Config, global: bool) -> anyhow::Result<V
         return Ok(wm.list()?);
     }
 
-    let entries = discovery::discover_global_worktrees(
-        &cfg.worktree.base_dir,
-        cfg.discovery.global_scan_depth,
-    )?;
+    let entries = discovery::discover_global_worktrees_cached(cfg)?;
     Ok(entries
         .into_iter()
         .map(|e| Worktree {
+            is_detached: e.branch == "HEAD",
             path: e.path,
             branch: e.branch,
             commit_hash: e.commit_hash,
-            is_main: false,
+            is_main: e.is_main,
             created_at: "0001-01-01T00:00:00Z".to_owned(),
+            is_locked: e.is_locked,
+            lock_reason: e.lock_reason,
+            ..Worktree::default()
         })
         .collect())
 }
@@ -208,7 +247,7 @@ fn handle_key(key: KeyEvent, app: &mut AppState) -> bool {
             }
             KeyCode::Char('s') => {
                 app.sort = next_sort(&app.sort).to_owned();
-                app.needs_refresh = true;
+                app.apply_view();
             }
             KeyCode::Up | KeyCode::Char('k') => app.move_selection(-1),
             KeyCode::Down | KeyCode::Char('j') => app.move_selection(1),
@@ -221,23 +260,27 @@ fn handle_key(key: KeyEvent, app: &mut AppState) -> bool {
             KeyCode::Esc => app.mode = Mode::Normal,
             KeyCode::Enter => {
                 let next = app.filter_input.trim();
+                if let Err(e) = StatusQuery::parse(next) {
+                    app.last_error = Some(e.to_string());
+                    return false;
+                }
+                app.last_error = None;
                 app.filter = if next.is_empty() {
                     "all".to_owned()
                 } else {
                     next.to_owned()
                 };
                 app.mode = Mode::Normal;
-                app.needs_refresh = true;
+                app.apply_view();
             }
             KeyCode::Backspace => {
                 app.filter_input.pop();
             }
-            KeyCode::Char(c) => {
+            KeyCode::Char(c)
                 if !key.modifiers.contains(KeyModifiers::CONTROL)
-                    && !key.modifiers.contains(KeyModifiers::ALT)
-                {
-                    app.filter_input.push(c);
-                }
+                    && !key.modifiers.contains(KeyModifiers::ALT) =>
+            {
+                app.filter_input.push(c);
             }
             _ => {}
         },
@@ -251,6 +294,7 @@ fn next_sort(current: &str) -> &'static str {
         "activity" => "modified",
         "modified" | "changes" => "branch",
         "branch" | "name" => "status",
+        "status" => "base",
         _ => "activity",
     }
 }
@@ -286,6 +330,7 @@ fn draw_table(
             "STATUS",
             "CHANGES",
             "AHEAD/BEHIND",
+            "BASE",
             "ACTIVITY",
         ])
     } else {
@@ -301,7 +346,7 @@ fn draw_table(
         };
         let branch = format!("{marker}{}", s.branch);
         let status_txt = status::format_status_for_table(s.status);
-        let changes = format_changes(s.git_status);
+        let changes = format_changes(&s.git_status);
         let activity = format_activity(&s.last_activity);
 
         if app.verbose {
@@ -309,7 +354,12 @@ fn draw_table(
                 branch,
                 status_txt.to_owned(),
                 changes,
-                format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind),
+                if s.fetch_error.is_some() {
+                    "fetch failed".to_owned()
+                } else {
+                    format!("↑{} ↓{}", s.git_status.ahead, s.git_status.behind)
+                },
+                status::format_base_divergence(s),
                 activity,
             ])
         } else {
@@ -319,10 +369,11 @@ fn draw_table(
 
     let widths = if app.verbose {
         vec![
-            Constraint::Percentage(30),
+            Constraint::Percentage(26),
             Constraint::Length(12),
-            Constraint::Percentage(30),
+            Constraint::Percentage(26),
             Constraint::Length(14),
+            Constraint::Length(10),
             Constraint::Length(14),
         ]
     } else {
@@ -376,6 +427,9 @@ fn draw_footer(f: &mut Frame<'_>, area: ratatui::layout::Rect, app: &AppState) {
         } else {
             &app.sort
         }),
+        Span::raw("   "),
+        Span::styled("Refresh: ", Style::default().add_modifier(Modifier::BOLD)),
+        Span::raw(app.feed.mode_label()),
     ]));
     if let Some(err) = &app.last_error {
         lines.push(Line::from(vec![Span::styled(
@@ -403,7 +457,7 @@ fn draw_detail_popup(f: &mut Frame<'_>, app: &AppState) {
     let popup_area = centered_rect(80, 70, f.area());
     f.render_widget(Clear, popup_area);
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(format!("Branch: {}", s.branch)),
         Line::from(format!("Repository: {}", s.repository)),
         Line::from(format!("Path: {}", s.path)),
@@ -412,21 +466,47 @@ fn draw_detail_popup(f: &mut Frame<'_>, app: &AppState) {
             "Status: {}",
             status::format_status_for_table(s.status)
         )),
-        Line::from(format!(
-            "Changes: {} added, {} modified, {} deleted, {} untracked",
-            s.git_status.added, s.git_status.modified, s.git_status.deleted, s.git_status.untracked
-        )),
+        Line::from(format!("Changes: {}", format_changes(&s.git_status))),
         Line::from(format!(
             "Ahead/Behind: ↑{} ↓{}",
             s.git_status.ahead, s.git_status.behind
         )),
+        Line::from(format!(
+            "Base ({}): {}",
+            s.base.as_deref().unwrap_or("unknown"),
+            status::format_base_divergence(s)
+        )),
+        Line::from(format!(
+            "Last fetch: {}",
+            s.last_fetch
+                .as_deref()
+                .map_or_else(|| "never".to_owned(), format_activity)
+        )),
         Line::from(format!(
             "Last activity: {}",
             format_activity(&s.last_activity)
         )),
-        Line::from(""),
-        Line::from("Press Enter to close."),
     ];
+    if let Some(err) = &s.fetch_error {
+        lines.push(Line::from(format!("Fetch failed: {err}")));
+    }
+    if !s.git_status.files.is_empty() {
+        lines.push(Line::from(""));
+        lines.extend(
+            status::format_file_changes(&s.git_status.files, 15)
+                .into_iter()
+                .map(Line::from),
+        );
+    }
+    if !s.processes.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!("Processes ({}):", s.processes.len())));
+        for p in &s.processes {
+            lines.push(Line::from(format!("  {:>7}  {}", p.pid, p.command)));
+        }
+    }
+    lines.push(Line::from(""));
+    lines.push(Line::from("Press Enter to close."));
 
     let p = Paragraph::new(lines)
         .block(Block::default().borders(Borders::ALL).title("Details"))
@@ -458,23 +538,24 @@ fn centered_rect(
         .split(popup_layout[1])[1]
 }
 
-fn format_changes(gs: status::GitStatus) -> String {
-    if gs.modified == 0 && gs.added == 0 && gs.deleted == 0 && gs.untracked == 0 {
+fn format_changes(gs: &status::GitStatus) -> String {
+    let counts = [
+        (gs.added, "added"),
+        (gs.modified, "modified"),
+        (gs.deleted, "deleted"),
+        (gs.renamed, "renamed"),
+        (gs.copied, "copied"),
+        (gs.typechange, "typechange"),
+        (gs.untracked, "untracked"),
+    ];
+    let parts: Vec<String> = counts
+        .iter()
+        .filter(|(n, _)| *n > 0)
+        .map(|(n, label)| format!("{n} {label}"))
+        .collect();
+    if parts.is_empty() {
         return "-".to_owned();
     }
-    let mut parts = Vec::new();
-    if gs.added > 0 {
-        parts.push(format!("{} added", gs.added));
-    }
-    if gs.modified > 0 {
-        parts.push(format!("{} modified", gs.modified));
-    }
-    if gs.deleted > 0 {
-        parts.push(format!("{} deleted", gs.deleted));
-    }
-    if gs.untracked > 0 {
-        parts.push(format!("{} untracked", gs.untracked));
-    }
     parts.join(", ")
 }
 
@@ -0,0 +1,125 @@
+#![forbid(unsafe_code)]
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::core::status::{StatusCollector, WorktreeStatus};
+use crate::core::watch::WorktreeWatcher;
+use crate::core::worktree::Worktree;
+
+/// What [`StatusFeed::update`] did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeedUpdate {
+    Idle,
+    Updated,
+    /// Worktrees were added or removed; the caller should `reload`.
+    Rescan,
+}
+
+/// Unfiltered statuses of the worktrees on screen plus the watcher that says
+/// which of them are out of date, shared by the status tab and `status --watch`.
+#[derive(Default)]
+pub struct StatusFeed {
+    worktrees: Vec<Worktree>,
+    statuses: Vec<WorktreeStatus>,
+    watcher: Option<WorktreeWatcher>,
+}
+
+impl std::fmt::Debug for StatusFeed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("StatusFeed")
+            .field("worktrees", &self.worktrees.len())
+            .field("watching", &self.watcher.is_some())
+            .finish_non_exhaustive()
+    }
+}
+
+impl StatusFeed {
+    #[must_use]
+    pub fn statuses(&self) -> &[WorktreeStatus] {
+        &self.statuses
+    }
+
+    /// Whether changes arrive from the watcher rather than periodic polling.
+    #[must_use]
+    pub fn is_watching(&self) -> bool {
+        self.watcher.is_some()
+    }
+
+    /// `fs`, `fs-poll` (watcher fell back to polling) or `interval`.
+    #[must_use]
+    pub fn mode_label(&self) -> &'static str {
+        match &self.watcher {
+            Some(w) if w.is_polling() => "fs-poll",
+            Some(_) => "fs",
+            None => "interval",
+        }
+    }
+
+    /// Collects every worktree and (re)starts the watcher when `watch` is set
+    /// and the set of worktrees changed. A watcher that cannot be started
+    /// leaves the caller on interval polling.
+    pub async fn reload(
+        &mut self,
+        worktrees: Vec<Worktree>,
+        collector: &StatusCollector,
+        watch: bool,
+        poll_interval: Duration,
+    ) -> anyhow::Result<()> {
+        self.statuses = collector.collect_all(&worktrees).await?;
+
+        let same_set = self.worktrees.len() == worktrees.len()
+            && self
+                .worktrees
+                .iter()
+                .zip(&worktrees)
+                .all(|(a, b)| a.path == b.path);
+        self.worktrees = worktrees;
+        if !watch {
+            self.watcher = None;
+        } else if self.watcher.is_none() || !same_set {
+            let roots: Vec<PathBuf> = self
+                .worktrees
+                .iter()
+                .map(|w| PathBuf::from(&w.path))
+                .collect();
+            self.watcher = tokio::task::spawn_blocking(move || {
+                WorktreeWatcher::new(&roots, poll_interval).ok()
+            })
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// Re-collects only the worktrees the watcher reported as changed.
+    pub async fn update(&mut self, collector: &StatusCollector) -> anyhow::Result<FeedUpdate> {
+        let Some(watcher) = self.watcher.as_mut() else {
+            return Ok(FeedUpdate::Idle);
+        };
+        let changes = watcher.take_changes();
+        if changes.rescan {
+            return Ok(FeedUpdate::Rescan);
+        }
+        if changes.worktrees.is_empty() {
+            return Ok(FeedUpdate::Idle);
+        }
+
+        let changed: Vec<Worktree> = self
+            .worktrees
+            .iter()
+            .filter(|w| {
+                changes
+                    .worktrees
+                    .iter()
+                    .any(|p| p.as_path() == std::path::Path::new(&w.path))
+            })
+            .cloned()
+            .collect();
+        for fresh in collector.collect_all(&changed).await? {
+            if let Some(slot) = self.statuses.iter_mut().find(|s| s.path == fresh.path) {
+                *slot = fresh;
+            }
+        }
+        Ok(FeedUpdate::Updated)
+    }
+}
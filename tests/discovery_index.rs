@@ -0,0 +1,93 @@
+use std::process::Command;
+
+use gwtui::config::Config;
+use gwtui::core::discovery;
+use gwtui::core::git;
+
+// Kept in its own test binary: the git spawn counter is process-wide, so no
+// other test may run git concurrently.
+#[test]
+fn discovery_index_is_reused_and_revalidated_incrementally() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let base = td.path().join("base");
+    let repo = base.join("repo");
+    std::fs::create_dir_all(&repo).expect("mkdir repo");
+    run(&repo, &["init", "-q"]);
+    run(&repo, &["config", "user.email", "test@example.com"]);
+    run(&repo, &["config", "user.name", "Test"]);
+    run(
+        &repo,
+        &["remote", "add", "origin", "https://example.com/me/repo.git"],
+    );
+    std::fs::write(repo.join("README.md"), "hello\n").expect("write");
+    run(&repo, &["add", "."]);
+    run(&repo, &["commit", "-qm", "init"]);
+    run(&repo, &["worktree", "add", "-q", "-b", "feature/a", "../a"]);
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = base.to_string_lossy().to_string();
+    cfg.status.cache_dir = td.path().join("cache").to_string_lossy().to_string();
+    cfg.discovery.cache_ttl_seconds = 3600;
+
+    let branches = |cfg: &Config| -> Vec<String> {
+        let mut b: Vec<String> = discovery::discover_global_worktrees_cached(cfg)
+            .expect("discover")
+            .into_iter()
+            .filter(|e| !e.is_main)
+            .map(|e| e.branch)
+            .collect();
+        b.sort();
+        b
+    };
+
+    assert_eq!(branches(&cfg), ["feature/a"]);
+    assert!(td.path().join("cache/discovery.json").is_file());
+    let entries = discovery::discover_global_worktrees_cached(&cfg).expect("discover");
+    assert!(
+        entries[0].is_main,
+        "main checkout is listed first: {entries:?}"
+    );
+
+    // Within the TTL the index is served without walking or running git,
+    // so a worktree added in the meantime is not seen yet.
+    run(&repo, &["worktree", "add", "-q", "-b", "feature/b", "../b"]);
+    let before = git::spawn_count();
+    assert_eq!(branches(&cfg), ["feature/a"]);
+    assert_eq!(git::spawn_count(), before);
+
+    // Once expired, only the new worktree and the one whose HEAD moved are
+    // asked for their branch again.
+    cfg.discovery.cache_ttl_seconds = 0;
+    run(
+        &base.join("a"),
+        &["checkout", "-q", "-b", "feature/renamed"],
+    );
+    assert_eq!(branches(&cfg), ["feature/b", "feature/renamed"]);
+
+    let before = git::spawn_count();
+    assert_eq!(branches(&cfg), ["feature/b", "feature/renamed"]);
+    assert_eq!(git::spawn_count(), before);
+
+    let removed = discovery::clear_caches(&td.path().join("cache")).expect("clear");
+    assert_eq!(removed, [td.path().join("cache/discovery.json")]);
+}
+
+fn run(dir: &std::path::Path, args: &[&str]) {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git command");
+    if !out.status.success() {
+        panic!(
+            "git {:?} failed: {}",
+            args,
+            String::from_utf8_lossy(&out.stderr)
+        );
+    }
+}
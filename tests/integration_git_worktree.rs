@@ -1,8 +1,11 @@
 use std::process::Command;
 
-use gwtui::config::Config;
+use gwtui::config::{ActivityMode, Config};
+use gwtui::core::discovery;
 use gwtui::core::git::Git;
-use gwtui::core::worktree::WorktreeManager;
+use gwtui::core::status::{StatusCollector, StatusCollectorOptions, WorktreeState};
+use gwtui::core::watch::{WatchChanges, WorktreeWatcher};
+use gwtui::core::worktree::{CleanReason, ReviewKind, WorktreeManager};
 
 #[test]
 fn git_worktree_add_and_list_smoke() {
@@ -36,6 +39,1139 @@ fn git_worktree_add_and_list_smoke() {
     assert!(listed.iter().any(|w| w.branch == "feature/test"));
 }
 
+#[test]
+fn locked_worktree_requires_force_to_remove() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+
+    let worktree_path = wm
+        .add_from_base("feature/locked", None, Some(&td.path().join("wt-locked")))
+        .expect("add worktree");
+    wm.lock(&worktree_path, Some("on a usb drive"))
+        .expect("lock");
+
+    let listed = wm.list().expect("list");
+    let wt = listed
+        .iter()
+        .find(|w| w.branch == "feature/locked")
+        .expect("listed");
+    assert!(wt.is_locked);
+    assert_eq!(wt.lock_reason.as_deref(), Some("on a usb drive"));
+
+    assert!(wm.remove(&worktree_path, false).is_err());
+    assert!(worktree_path.exists());
+
+    wm.remove(&worktree_path, true).expect("forced remove");
+    assert!(!worktree_path.exists());
+}
+
+#[test]
+fn locked_worktree_is_refused_before_pre_remove_hooks() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+    let worktree_path = wm
+        .add_from_base("feature/locked", None, Some(&td.path().join("wt-locked")))
+        .expect("add worktree");
+    wm.lock(&worktree_path, None).expect("lock");
+
+    let home = td.path().join("home");
+    let marker = td.path().join("pre-remove-ran");
+    let config_dir = home.join(".config").join("gwtui");
+    std::fs::create_dir_all(&config_dir).expect("mkdir config");
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!("[hooks]\npre_remove = [\"touch '{}'\"]\n", marker.display()),
+    )
+    .expect("write config");
+
+    let out = Command::new(env!("CARGO_BIN_EXE_gwtui"))
+        .args(["remove", "feature/locked"])
+        .current_dir(&repo)
+        .env("HOME", &home)
+        .env("XDG_CONFIG_HOME", home.join(".config"))
+        .env("XDG_CACHE_HOME", home.join(".cache"))
+        .output()
+        .expect("run gwtui");
+    assert!(
+        String::from_utf8_lossy(&out.stderr).contains("locked"),
+        "{}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+    assert!(worktree_path.exists());
+    assert!(
+        !marker.exists(),
+        "pre_remove hook ran for a locked worktree"
+    );
+}
+
+#[test]
+fn relayout_moves_worktrees_to_template_path() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let base = td.path().join("worktrees");
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = base.to_string_lossy().to_string();
+    cfg.worktree.naming_template = "old/{{branch}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg.clone());
+    let old_path = wm
+        .add_from_base("feature/move", None, None)
+        .expect("add worktree");
+    assert!(old_path.starts_with(base.join("old")));
+
+    cfg.worktree.naming_template = "new/{{repo}}/{{branch}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg.clone());
+    let plan = wm.plan_relayout().expect("plan");
+    assert_eq!(plan.len(), 1);
+    assert_eq!(plan[0].skip_reason, None);
+
+    wm.move_worktree(&plan[0].from, &plan[0].to).expect("move");
+    assert!(!old_path.exists());
+    // The emptied template directories are cleaned up.
+    assert!(!base.join("old").exists());
+
+    let listed = wm.list().expect("list");
+    let moved = listed
+        .iter()
+        .find(|w| w.branch == "feature/move")
+        .expect("listed");
+    assert!(std::path::Path::new(&moved.path).starts_with(base.join("new").join("repo")));
+    assert!(wm.plan_relayout().expect("plan").is_empty());
+
+    cfg.worktree.naming_template = "{{repo}}/{{date}}-{{branch}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+    let err = wm.plan_relayout().expect_err("unstable template");
+    assert!(err.to_string().contains("{{date}}"), "{err}");
+    assert!(wm.expected_path("feature/move").is_err());
+}
+
+#[test]
+fn copies_and_links_local_files_into_new_worktree() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    std::fs::write(repo.join(".gitignore"), ".env\n.vscode/\nnode_modules/\n").expect("write");
+    run(&repo, &["add", ".gitignore"]);
+    run(&repo, &["commit", "-m", "ignore"]);
+
+    std::fs::write(repo.join(".env"), "SECRET=1\n").expect("write .env");
+    std::fs::create_dir_all(repo.join(".vscode")).expect("mkdir");
+    std::fs::write(repo.join(".vscode/settings.json"), "{}").expect("write");
+    std::fs::write(repo.join(".vscode/launch.json"), "{}").expect("write");
+    std::fs::create_dir_all(repo.join("node_modules/pkg")).expect("mkdir");
+
+    let mut cfg = Config::default();
+    cfg.worktree.copy_files = vec![".env".to_owned(), ".vscode/settings.json".to_owned()];
+    cfg.worktree.link_files = vec!["node_modules".to_owned()];
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+
+    let wt = wm
+        .add_from_base("feature/files", None, Some(&td.path().join("wt-files")))
+        .expect("add worktree");
+    std::fs::write(wt.join(".env"), "LOCAL=1\n").expect("pre-existing .env");
+
+    let report = wm.copy_local_files(&wt).expect("copy");
+    assert_eq!(report.copied, vec![".vscode/settings.json"]);
+    assert_eq!(report.linked, vec!["node_modules"]);
+    assert_eq!(report.skipped.len(), 1);
+    assert_eq!(report.skipped[0].0, ".env");
+
+    assert!(wt.join(".vscode/settings.json").is_file());
+    assert!(!wt.join(".vscode/launch.json").exists());
+    assert_eq!(
+        std::fs::read_to_string(wt.join(".env")).expect("read"),
+        "LOCAL=1\n"
+    );
+    let link = std::fs::symlink_metadata(wt.join("node_modules")).expect("link");
+    assert!(link.file_type().is_symlink());
+}
+
+#[test]
+fn leading_double_star_does_not_walk_ignored_directories() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    std::fs::write(repo.join(".gitignore"), ".env\nnode_modules/\n").expect("write");
+    std::fs::create_dir_all(repo.join("services/api")).expect("mkdir");
+    std::fs::write(repo.join("services/api/main.rs"), "").expect("write");
+    run(&repo, &["add", "."]);
+    run(&repo, &["commit", "-m", "layout"]);
+
+    std::fs::write(repo.join(".env"), "ROOT=1\n").expect("write");
+    std::fs::write(repo.join("services/api/.env"), "API=1\n").expect("write");
+    std::fs::create_dir_all(repo.join("node_modules/pkg")).expect("mkdir");
+    std::fs::write(repo.join("node_modules/pkg/.env"), "DEP=1\n").expect("write");
+    // A checkout nested in the main one, as worktrees under it would be.
+    std::fs::create_dir_all(repo.join("nested")).expect("mkdir");
+    std::fs::write(repo.join("nested/.git"), "gitdir: /elsewhere\n").expect("write");
+    std::fs::write(repo.join("nested/.env"), "NESTED=1\n").expect("write");
+
+    let mut cfg = Config::default();
+    cfg.worktree.copy_files = vec!["**/.env".to_owned()];
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+    let wt = wm
+        .add_from_base("feature/env", None, Some(&td.path().join("wt-env")))
+        .expect("add worktree");
+
+    let mut copied = wm.copy_local_files(&wt).expect("copy").copied;
+    copied.sort();
+    assert_eq!(copied, vec![".env", "services/api/.env"]);
+    assert!(!wt.join("node_modules").exists());
+    assert!(!wt.join("nested").exists());
+}
+
+#[test]
+fn repo_overrides_apply_to_matching_repositories() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    run(
+        &repo,
+        &["remote", "add", "origin", "git@github.com:acme/app.git"],
+    );
+    std::fs::write(repo.join(".gitignore"), ".env\n.gwtui.toml\n").expect("write");
+    run(&repo, &["add", ".gitignore"]);
+    run(&repo, &["commit", "-m", "ignore"]);
+    std::fs::write(repo.join(".env"), "SECRET=1\n").expect("write .env");
+    std::fs::write(
+        repo.join(".gwtui.toml"),
+        "[worktree]\ncopy_files = [\".env\"]\n",
+    )
+    .expect("write .gwtui.toml");
+
+    let cfg: Config = toml::from_str(&format!(
+        "[worktree]\nbase_dir = '{}'\n\n\
+         [[repos]]\nowner = 'acme'\n\
+         worktree = {{ base_dir = '{}', naming_template = '{{{{repo}}}}-{{{{branch_leaf}}}}' }}\n",
+        td.path().join("default").display(),
+        td.path().join("acme").display(),
+    ))
+    .expect("config");
+    cfg.validate().expect("valid");
+
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg.clone());
+    let wt = wm
+        .add_from_base("feature/x", None, None)
+        .expect("add worktree");
+    assert_eq!(wt, td.path().join("acme/app-x"));
+    let report = wm.copy_local_files(&wt).expect("copy");
+    assert_eq!(report.copied, vec![".env"]);
+
+    // Opened from the linked worktree, the same repository settings apply.
+    let wm = WorktreeManager::new(Git::from_dir(&wt).expect("git"), cfg.clone());
+    assert_eq!(
+        wm.expected_path("feature/y").expect("path"),
+        td.path().join("acme/app-y")
+    );
+
+    // Other `[worktree]` keys in `.gwtui.toml` need `worktree.allow_repo_local`.
+    let elsewhere = td.path().join("elsewhere");
+    std::fs::write(
+        repo.join(".gwtui.toml"),
+        format!("[worktree]\nbase_dir = '{}'\n", elsewhere.display()),
+    )
+    .expect("write");
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg.clone());
+    assert_eq!(
+        wm.expected_path("feature/z").expect("path"),
+        td.path().join("acme/app-z")
+    );
+    let mut trusting = cfg;
+    trusting.repos[0]
+        .worktree
+        .insert("allow_repo_local".to_owned(), toml::Value::Boolean(true));
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), trusting.clone());
+    assert_eq!(
+        wm.expected_path("feature/z").expect("path"),
+        elsewhere.join("app-z")
+    );
+
+    std::fs::write(repo.join(".gwtui.toml"), "[worktree]\nauto_mkdir = 1\n").expect("write");
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), trusting);
+    let err = wm
+        .add_from_base("feature/z", None, None)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains(".gwtui.toml"), "{err}");
+}
+
+#[test]
+fn carry_moves_changes_and_rolls_back_on_conflict() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+
+    std::fs::write(repo.join("README.md"), "hello\nwip\n").expect("modify");
+    run(&repo, &["add", "README.md"]);
+    std::fs::write(repo.join("notes.txt"), "untracked\n").expect("untracked");
+    let wt = wm
+        .add_carrying_changes("feature/carry", Some(&td.path().join("wt-carry")), true)
+        .expect("carry");
+    assert_eq!(
+        std::fs::read_to_string(wt.join("README.md")).expect("read"),
+        "hello\nwip\n"
+    );
+    assert!(wt.join("notes.txt").is_file());
+    // What was staged stays staged.
+    assert_eq!(
+        git_stdout(&wt, &["diff", "--cached", "--name-only"]),
+        "README.md"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.join("README.md")).expect("read"),
+        "hello\n"
+    );
+    assert!(!repo.join("notes.txt").exists());
+    assert!(git_stdout(&repo, &["stash", "list"]).is_empty());
+
+    // A branch whose README diverged makes the carried edit conflict.
+    run(&repo, &["branch", "diverged"]);
+    run(&repo, &["checkout", "-q", "diverged"]);
+    std::fs::write(repo.join("README.md"), "other\n").expect("write");
+    run(&repo, &["commit", "-qam", "diverge"]);
+    run(&repo, &["checkout", "-q", "-"]);
+
+    std::fs::write(repo.join("README.md"), "hello\nmine\n").expect("modify");
+    let dest = td.path().join("wt-conflict");
+    let err = wm
+        .add_carrying_changes("diverged", Some(&dest), false)
+        .expect_err("conflict");
+    assert!(err.to_string().contains("restored"));
+    assert!(!dest.exists());
+    assert_eq!(
+        std::fs::read_to_string(repo.join("README.md")).expect("read"),
+        "hello\nmine\n"
+    );
+    assert!(git_stdout(&repo, &["stash", "list"]).is_empty());
+    assert!(!git_stdout(&repo, &["worktree", "list"]).contains("wt-conflict"));
+}
+
+#[test]
+fn carry_conflict_restores_staged_and_untracked_changes() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+
+    run(&repo, &["branch", "diverged"]);
+    run(&repo, &["checkout", "-q", "diverged"]);
+    std::fs::write(repo.join("README.md"), "other\n").expect("write");
+    run(&repo, &["commit", "-qam", "diverge"]);
+    run(&repo, &["checkout", "-q", "-"]);
+
+    std::fs::write(repo.join("README.md"), "hello\nstaged\n").expect("modify");
+    run(&repo, &["add", "README.md"]);
+    std::fs::write(repo.join("notes.txt"), "untracked\n").expect("untracked");
+
+    let dest = td.path().join("wt-conflict");
+    let err = wm
+        .add_carrying_changes("diverged", Some(&dest), false)
+        .expect_err("conflict");
+    assert!(err.to_string().contains("changes restored"), "{err}");
+    assert!(!dest.exists());
+
+    assert_eq!(
+        std::fs::read_to_string(repo.join("README.md")).expect("read"),
+        "hello\nstaged\n"
+    );
+    assert_eq!(
+        git_stdout(&repo, &["diff", "--cached", "--name-only"]),
+        "README.md"
+    );
+    assert_eq!(
+        std::fs::read_to_string(repo.join("notes.txt")).expect("read"),
+        "untracked\n"
+    );
+    assert_eq!(
+        git_stdout(&repo, &["status", "--porcelain", "--", "notes.txt"]),
+        "?? notes.txt"
+    );
+    assert!(git_stdout(&repo, &["stash", "list"]).is_empty());
+    assert!(git_stdout(&repo, &["branch", "--list", "diverged"]).contains("diverged"));
+}
+
+#[test]
+fn clean_candidates_cover_merged_and_gone_branches() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+    let default = wm.default_branch().expect("default branch");
+
+    let commit_in = |wt: &std::path::Path, file: &str| {
+        std::fs::write(wt.join(file), "x\n").expect("write");
+        run(wt, &["add", file]);
+        run(wt, &["commit", "-qm", file]);
+    };
+
+    let merged = wm
+        .add("feature/merged", Some(&td.path().join("wt-merged")), true)
+        .expect("add");
+    commit_in(&merged, "merged.txt");
+    run(
+        &repo,
+        &["merge", "-q", "--no-ff", "-m", "merge", "feature/merged"],
+    );
+
+    let dirty = wm
+        .add("feature/dirty", Some(&td.path().join("wt-dirty")), true)
+        .expect("add");
+    std::fs::write(dirty.join("scratch.txt"), "wip\n").expect("write");
+
+    let open = wm
+        .add("feature/open", Some(&td.path().join("wt-open")), true)
+        .expect("add");
+    commit_in(&open, "open.txt");
+
+    // A pushed branch whose remote branch was deleted without landing anywhere.
+    let remote = td.path().join("remote.git");
+    run(
+        td.path(),
+        &["init", "-q", "--bare", remote.to_str().expect("utf8")],
+    );
+    run(
+        &repo,
+        &["remote", "add", "origin", remote.to_str().expect("utf8")],
+    );
+    let gone = wm
+        .add("feature/gone", Some(&td.path().join("wt-gone")), true)
+        .expect("add");
+    commit_in(&gone, "gone.txt");
+    run(&gone, &["push", "-q", "-u", "origin", "feature/gone"]);
+    run(&repo, &["push", "-q", "origin", "--delete", "feature/gone"]);
+
+    // Gone as well, but its commits live on in another remote branch.
+    let landed = wm
+        .add("feature/landed", Some(&td.path().join("wt-landed")), true)
+        .expect("add");
+    commit_in(&landed, "landed.txt");
+    run(&landed, &["push", "-q", "-u", "origin", "feature/landed"]);
+    run(&landed, &["push", "-q", "origin", "feature/landed:release"]);
+    run(
+        &repo,
+        &["push", "-q", "origin", "--delete", "feature/landed"],
+    );
+
+    // Gone after a squash merge into the default branch.
+    let squashed = wm
+        .add(
+            "feature/squashed",
+            Some(&td.path().join("wt-squashed")),
+            true,
+        )
+        .expect("add");
+    commit_in(&squashed, "squashed-1.txt");
+    commit_in(&squashed, "squashed-2.txt");
+    run(
+        &squashed,
+        &["push", "-q", "-u", "origin", "feature/squashed"],
+    );
+    run(&repo, &["merge", "-q", "--squash", "feature/squashed"]);
+    run(&repo, &["commit", "-qm", "squashed"]);
+    run(
+        &repo,
+        &["push", "-q", "origin", "--delete", "feature/squashed"],
+    );
+    run(&repo, &["fetch", "-q", "--prune", "origin"]);
+
+    let mut candidates = wm.clean_candidates(&default).expect("candidates");
+    candidates.sort_by(|a, b| a.worktree.branch.cmp(&b.worktree.branch));
+    let summary: Vec<(&str, CleanReason, bool, bool)> = candidates
+        .iter()
+        .map(|c| {
+            (
+                c.worktree.branch.as_str(),
+                c.reason,
+                c.protected.is_some(),
+                c.branch_deletable,
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            ("feature/dirty", CleanReason::Merged, true, true),
+            ("feature/gone", CleanReason::UpstreamGone, true, false),
+            ("feature/landed", CleanReason::UpstreamGone, false, true),
+            ("feature/merged", CleanReason::Merged, false, true),
+            ("feature/squashed", CleanReason::UpstreamGone, false, true),
+        ]
+    );
+    let gone = candidates
+        .iter()
+        .find(|c| c.worktree.branch == "feature/gone")
+        .expect("gone");
+    assert_eq!(gone.protected.as_deref(), Some("not merged"));
+}
+
+#[test]
+fn clean_merged_removes_worktrees_and_branches() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+    let remote = td.path().join("remote.git");
+    run(
+        td.path(),
+        &["init", "-q", "--bare", remote.to_str().expect("utf8")],
+    );
+    run(
+        &repo,
+        &["remote", "add", "origin", remote.to_str().expect("utf8")],
+    );
+
+    let commit_in = |wt: &std::path::Path, file: &str| {
+        std::fs::write(wt.join(file), "x\n").expect("write");
+        run(wt, &["add", file]);
+        run(wt, &["commit", "-qm", file]);
+    };
+
+    // Neither branch is merged into HEAD, so `git branch -d` would refuse both.
+    let landed = wm
+        .add("feature/landed", Some(&td.path().join("wt-landed")), true)
+        .expect("add");
+    commit_in(&landed, "landed.txt");
+    run(&landed, &["push", "-q", "-u", "origin", "feature/landed"]);
+    run(&landed, &["push", "-q", "origin", "feature/landed:release"]);
+    run(
+        &repo,
+        &["push", "-q", "origin", "--delete", "feature/landed"],
+    );
+
+    let squashed = wm
+        .add(
+            "feature/squashed",
+            Some(&td.path().join("wt-squashed")),
+            true,
+        )
+        .expect("add");
+    commit_in(&squashed, "squashed.txt");
+    run(
+        &squashed,
+        &["push", "-q", "-u", "origin", "feature/squashed"],
+    );
+    run(&repo, &["merge", "-q", "--squash", "feature/squashed"]);
+    run(&repo, &["commit", "-qm", "squashed"]);
+    run(
+        &repo,
+        &["push", "-q", "origin", "--delete", "feature/squashed"],
+    );
+
+    // Gone without landing anywhere: protected as "not merged".
+    let lost = wm
+        .add("feature/lost", Some(&td.path().join("wt-lost")), true)
+        .expect("add");
+    commit_in(&lost, "lost.txt");
+    run(&lost, &["push", "-q", "-u", "origin", "feature/lost"]);
+    run(&repo, &["push", "-q", "origin", "--delete", "feature/lost"]);
+    run(&repo, &["fetch", "-q", "--prune", "origin"]);
+
+    let home = td.path().join("home");
+    let clean = |extra: &[&str]| {
+        let out = Command::new(env!("CARGO_BIN_EXE_gwtui"))
+            .args(["clean", "--merged", "-y"])
+            .args(extra)
+            .current_dir(&repo)
+            .env("HOME", &home)
+            .env("XDG_CONFIG_HOME", home.join(".config"))
+            .env("XDG_CACHE_HOME", home.join(".cache"))
+            .output()
+            .expect("run gwtui");
+        assert!(
+            out.status.success(),
+            "clean failed: {}",
+            String::from_utf8_lossy(&out.stderr)
+        );
+    };
+    let branches = || git_stdout(&repo, &["branch", "--format=%(refname:short)"]);
+
+    clean(&[]);
+    assert!(!landed.exists());
+    assert!(!squashed.exists());
+    assert!(lost.exists());
+    let listed = branches();
+    assert!(!listed.contains("feature/landed"), "{listed}");
+    assert!(!listed.contains("feature/squashed"), "{listed}");
+
+    // --force removes the worktree, but the unmerged branch is kept.
+    clean(&["--force"]);
+    assert!(!lost.exists());
+    assert!(branches().contains("feature/lost"));
+}
+
+#[test]
+fn fetches_pull_and_merge_requests_into_worktrees() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let remote = td.path().join("remote.git");
+    let remote_str = remote.to_str().expect("utf8");
+    run(td.path(), &["init", "-q", "--bare", remote_str]);
+    run(&repo, &["remote", "add", "origin", remote_str]);
+
+    // Publish two review refs the way GitHub/GitLab expose them, then forget
+    // the local branch so only the remote has the commits.
+    run(&repo, &["checkout", "-q", "-b", "contrib"]);
+    std::fs::write(repo.join("review.txt"), "please review\n").expect("write");
+    run(&repo, &["add", "review.txt"]);
+    run(&repo, &["commit", "-qm", "review me"]);
+    run(&repo, &["push", "-q", "origin", "contrib:refs/pull/7/head"]);
+    run(
+        &repo,
+        &["push", "-q", "origin", "contrib:refs/merge-requests/3/head"],
+    );
+    run(&repo, &["checkout", "-q", "-"]);
+    run(&repo, &["branch", "-q", "-D", "contrib"]);
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = td.path().join("wts").to_string_lossy().to_string();
+    cfg.worktree.naming_template = "{{repo}}/review-{{pr}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+
+    let branch = wm
+        .fetch_review(ReviewKind::PullRequest, 7, None)
+        .expect("fetch pr");
+    assert_eq!(branch, "pr/7");
+    let wt = wm.add(&branch, None, false).expect("add pr worktree");
+    assert_eq!(wt, td.path().join("wts/repo/review-7"));
+    assert!(wt.join("review.txt").is_file());
+
+    let branch = wm
+        .fetch_review(ReviewKind::MergeRequest, 3, Some("origin"))
+        .expect("fetch mr");
+    assert_eq!(branch, "mr/3");
+    assert_eq!(
+        wm.expected_path(&branch).expect("path"),
+        td.path().join("wts/repo/review-3")
+    );
+
+    // Fetching again fast-forwards the review branch but never drops local
+    // commits on it.
+    let head = git_stdout(&repo, &["rev-parse", "mr/3"]);
+    run(&repo, &["branch", "-f", "mr/3", "HEAD"]);
+    wm.fetch_review(ReviewKind::MergeRequest, 3, None)
+        .expect("fast-forward");
+    assert_eq!(git_stdout(&repo, &["rev-parse", "mr/3"]), head);
+    run(&repo, &["checkout", "-q", "mr/3"]);
+    std::fs::write(repo.join("review.txt"), "my fixup\n").expect("write");
+    run(&repo, &["commit", "-qam", "local fixup"]);
+    run(&repo, &["checkout", "-q", "-"]);
+    let local = git_stdout(&repo, &["rev-parse", "mr/3"]);
+    let err = wm
+        .fetch_review(ReviewKind::MergeRequest, 3, None)
+        .expect_err("would drop the local commit");
+    assert!(err.to_string().contains("fast-forwarded"), "{err}");
+    assert_eq!(git_stdout(&repo, &["rev-parse", "mr/3"]), local);
+
+    assert!(wm.fetch_review(ReviewKind::PullRequest, 99, None).is_err());
+}
+
+#[test]
+fn detached_worktrees_are_named_from_rev_and_removed_without_branch() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    run(&repo, &["tag", "v1.0.0"]);
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = td.path().join("wts").to_string_lossy().to_string();
+    cfg.worktree.naming_template = "{{repo}}/at-{{ref}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+
+    let path = wm.add_detached("v1.0.0", None).expect("add detached");
+    assert_eq!(path, td.path().join("wts/repo/at-v1.0.0"));
+
+    let wt = wm
+        .list()
+        .expect("list")
+        .into_iter()
+        .find(|w| !w.is_main)
+        .expect("detached worktree listed");
+    assert!(wt.is_detached);
+    assert_eq!(
+        wt.display_branch(),
+        format!("(detached {})", &wt.commit_hash[..7])
+    );
+
+    let branches_before = git_stdout(&repo, &["branch", "--list"]);
+    let deleted = wm
+        .remove_with_branch(&path, &wt.branch, false, true, false)
+        .expect("remove detached");
+    assert!(!deleted);
+    assert!(!path.exists());
+    assert_eq!(git_stdout(&repo, &["branch", "--list"]), branches_before);
+
+    // `{{short_hash}}` names the commit a new branch starts at, not HEAD.
+    std::fs::write(repo.join("README.md"), "later\n").expect("write");
+    run(&repo, &["commit", "-am", "later"]);
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = td.path().join("wts").to_string_lossy().to_string();
+    cfg.worktree.naming_template = "{{short_hash}}-{{branch_leaf}}".to_owned();
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg);
+    let path = wm
+        .add_from_base("feature/old", Some("v1.0.0"), None)
+        .expect("add from tag");
+    let tag = git_stdout(&repo, &["rev-parse", "--short", "v1.0.0"]);
+    assert_eq!(path, td.path().join(format!("wts/{tag}-old")));
+}
+
+#[tokio::test]
+async fn status_reports_unfinished_rebase_merge_and_bisect() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let main = git_stdout(&repo, &["branch", "--show-current"]);
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+
+    let mut paths = Vec::new();
+    for name in ["merging", "rebasing"] {
+        let path = wm
+            .add_from_base(
+                &format!("feature/{name}"),
+                None,
+                Some(&td.path().join(name)),
+            )
+            .expect("add worktree");
+        std::fs::write(path.join("README.md"), format!("{name}\n")).expect("write");
+        run(&path, &["commit", "-qam", name]);
+        paths.push(path);
+    }
+    std::fs::write(repo.join("README.md"), "upstream\n").expect("write");
+    run(&repo, &["commit", "-qam", "upstream"]);
+
+    // Both stop on the README conflict; the exit status is expected to be non-zero.
+    git_stdout(&paths[0], &["merge", &main]);
+    git_stdout(&paths[1], &["rebase", &main]);
+    run(&repo, &["bisect", "start"]);
+
+    let collector = StatusCollector::new(StatusCollectorOptions {
+        include_process: false,
+        fetch_remote: false,
+        include_base: false,
+        fetch_interval: std::time::Duration::from_secs(300),
+        stale_threshold: std::time::Duration::from_secs(60 * 60 * 24 * 365),
+        base_dir: String::new(),
+        concurrency: 4,
+        activity: ActivityMode::Fast,
+        cache_dir: None,
+    });
+    let statuses = collector
+        .collect_all(&wm.list().expect("list"))
+        .await
+        .expect("collect");
+    let state_of = |path: &std::path::Path| {
+        let path = std::fs::canonicalize(path).expect("canonicalize");
+        statuses
+            .iter()
+            .find(|s| std::fs::canonicalize(&s.path).ok().as_ref() == Some(&path))
+            .map(|s| s.status)
+            .expect("status for worktree")
+    };
+    assert_eq!(state_of(&paths[0]), WorktreeState::Merging);
+    assert_eq!(state_of(&paths[1]), WorktreeState::Rebasing);
+    assert_eq!(state_of(&repo), WorktreeState::Bisecting);
+
+    run(&paths[0], &["merge", "--abort"]);
+    let statuses = collector
+        .collect_all(&wm.list().expect("list"))
+        .await
+        .expect("collect");
+    assert!(statuses.iter().all(|s| s.status != WorktreeState::Merging));
+}
+
+#[tokio::test]
+async fn status_fetches_each_repository_once_and_reports_failures() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let upstream = init_repo(td.path());
+    let main = git_stdout(&upstream, &["branch", "--show-current"]);
+    let clone = td.path().join("clone");
+    run(
+        td.path(),
+        &["clone", "-q", &upstream.to_string_lossy(), "clone"],
+    );
+    let wm = WorktreeManager::new(Git::from_dir(&clone).expect("git"), Config::default());
+    let linked = wm
+        .add_from_base("feature/x", None, Some(&td.path().join("wt-x")))
+        .expect("add worktree");
+
+    std::fs::write(upstream.join("README.md"), "upstream\n").expect("write");
+    run(&upstream, &["commit", "-qam", "upstream"]);
+
+    let collector = StatusCollector::new(StatusCollectorOptions {
+        include_process: false,
+        fetch_remote: true,
+        include_base: true,
+        fetch_interval: std::time::Duration::from_secs(300),
+        stale_threshold: std::time::Duration::from_secs(60 * 60 * 24 * 365),
+        base_dir: String::new(),
+        concurrency: 4,
+        activity: ActivityMode::Fast,
+        cache_dir: None,
+    });
+    let statuses = collector
+        .collect_all(&wm.list().expect("list"))
+        .await
+        .expect("collect");
+    let main_row = statuses
+        .iter()
+        .find(|s| s.branch == main)
+        .expect("main row");
+    assert_eq!(main_row.git_status.behind, 1);
+    // The new branch has no upstream, but is compared against the default branch.
+    let feature = statuses
+        .iter()
+        .find(|s| s.branch == "feature/x")
+        .expect("feature row");
+    assert_eq!(feature.upstream, None);
+    assert_eq!(
+        feature.base.as_deref(),
+        Some(format!("origin/{main}").as_str())
+    );
+    assert_eq!(
+        (
+            feature.git_status.base_ahead,
+            feature.git_status.base_behind
+        ),
+        (0, 1)
+    );
+    assert!(statuses.iter().all(|s| s.fetch_error.is_none()));
+    assert!(statuses.iter().all(|s| s.last_fetch.is_some()));
+    assert_eq!(
+        git_stdout(&linked, &["rev-parse", &format!("origin/{main}")]),
+        git_stdout(&upstream, &["rev-parse", "HEAD"])
+    );
+
+    // Without fetching, the base column still comes from the local refs.
+    std::fs::write(upstream.join("README.md"), "upstream again\n").expect("write");
+    run(&upstream, &["commit", "-qam", "upstream again"]);
+    let statuses = StatusCollector::new(StatusCollectorOptions {
+        include_process: false,
+        fetch_remote: false,
+        include_base: true,
+        fetch_interval: std::time::Duration::from_secs(300),
+        stale_threshold: std::time::Duration::from_secs(60 * 60 * 24 * 365),
+        base_dir: String::new(),
+        concurrency: 4,
+        activity: ActivityMode::Fast,
+        cache_dir: None,
+    })
+    .collect_all(&wm.list().expect("list"))
+    .await
+    .expect("collect");
+    let feature = statuses
+        .iter()
+        .find(|s| s.branch == "feature/x")
+        .expect("feature row");
+    assert_eq!(
+        feature.base.as_deref(),
+        Some(format!("origin/{main}").as_str())
+    );
+    assert_eq!(feature.git_status.base_behind, 1);
+    assert!(statuses.iter().all(|s| s.last_fetch.is_none()));
+
+    // A broken remote is reported on every row of the repository.
+    let broken = td.path().join("broken");
+    std::fs::create_dir_all(&broken).expect("mkdir");
+    run(&broken, &["init", "-q"]);
+    run(
+        &broken,
+        &["remote", "add", "origin", "/nonexistent/remote.git"],
+    );
+    let statuses = collector
+        .collect_all(
+            &WorktreeManager::new(Git::from_dir(&broken).expect("git"), Config::default())
+                .list()
+                .expect("list"),
+        )
+        .await
+        .expect("collect");
+    assert_eq!(statuses.len(), 1);
+    assert!(
+        statuses[0]
+            .fetch_error
+            .as_deref()
+            .is_some_and(|e| e.contains("fetch")),
+        "{:?}",
+        statuses[0].fetch_error
+    );
+}
+
+#[test]
+fn watcher_reports_only_the_changed_worktree_and_new_worktrees() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = init_repo(td.path());
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+    let other = wm
+        .add_from_base("feature/other", None, Some(&td.path().join("other")))
+        .expect("add worktree");
+    std::fs::create_dir_all(repo.join("target")).expect("mkdir target");
+
+    let mut watcher = WorktreeWatcher::new(
+        &[repo.clone(), other.clone()],
+        std::time::Duration::from_millis(50),
+    )
+    .expect("watcher");
+
+    // Build output is ignored the same way status collection ignores it.
+    std::fs::write(repo.join("target/out.o"), "bin").expect("write");
+    std::thread::sleep(std::time::Duration::from_millis(300));
+    assert_eq!(watcher.take_changes(), WatchChanges::default());
+
+    std::fs::write(other.join("new.txt"), "x").expect("write");
+    let changes = wait_for_changes(&mut watcher);
+    assert_eq!(changes.worktrees, vec![other.clone()]);
+    assert!(!changes.rescan);
+
+    run(
+        &repo,
+        &["worktree", "add", "-b", "feature/third", "../third"],
+    );
+    assert!(wait_for_changes(&mut watcher).rescan);
+}
+
+#[cfg(unix)]
+#[test]
+fn global_discovery_groups_by_main_repo_and_dedupes() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let base = td.path().join("base");
+    let repo = init_repo(&base);
+    run(
+        &repo,
+        &["remote", "add", "origin", "https://example.com/me/repo.git"],
+    );
+    run(
+        &repo,
+        &["worktree", "add", "-q", "-b", "feature/x", "../wt-x"],
+    );
+    std::os::unix::fs::symlink(base.join("wt-x"), base.join("link-x")).expect("symlink");
+    // A worktree whose own config points `origin` at another URL.
+    run(&repo, &["config", "extensions.worktreeConfig", "true"]);
+    run(
+        &base.join("wt-x"),
+        &[
+            "config",
+            "--worktree",
+            "remote.origin.url",
+            "git@example.com:me/repo.git",
+        ],
+    );
+
+    // The main checkout of this one lives outside the base directory.
+    let outside = init_repo(&td.path().join("outside"));
+    run(
+        &outside,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://example.com/me/other.git",
+        ],
+    );
+    run(
+        &outside,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature/y",
+            base.join("wt-y").to_str().expect("utf-8"),
+        ],
+    );
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = base.to_string_lossy().to_string();
+    cfg.status.cache_dir = String::new();
+
+    let entries = discovery::discover_global_worktrees_cached(&cfg).expect("discover");
+    let summary: Vec<(bool, String, Option<String>)> = entries
+        .iter()
+        .map(|e| (e.is_main, e.branch.clone(), e.repository.clone()))
+        .collect();
+    let main = git_stdout(&repo, &["branch", "--show-current"]);
+    let main_y = git_stdout(&outside, &["branch", "--show-current"]);
+    let expected = vec![
+        (true, main, Some("repo".to_owned())),
+        (false, "feature/x".to_owned(), Some("repo".to_owned())),
+        (true, main_y, Some("other".to_owned())),
+        (false, "feature/y".to_owned(), Some("other".to_owned())),
+    ];
+    assert_eq!(summary, expected);
+    assert_eq!(entries[1].repository_url, "https://example.com/me/repo.git");
+    assert_eq!(entries[0].main_repo, entries[1].main_repo);
+
+    cfg.discovery.dedupe_by_main_repo = false;
+    let entries = discovery::discover_global_worktrees_cached(&cfg).expect("discover");
+    assert_eq!(
+        entries.iter().filter(|e| e.branch == "feature/x").count(),
+        2
+    );
+}
+
+#[test]
+fn global_discovery_covers_extra_roots_and_registered_repositories() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let base = td.path().join("base");
+    let in_base = init_repo(&base);
+    let in_root = init_repo(&td.path().join("src"));
+    let registered = init_repo(&td.path().join("elsewhere"));
+    for (repo, name) in [(&in_base, "a"), (&in_root, "b"), (&registered, "c")] {
+        let url = format!("https://example.com/me/{name}.git");
+        run(repo, &["remote", "add", "origin", &url]);
+    }
+    run(
+        &in_root,
+        &["worktree", "add", "-q", "-b", "feature/root", "../wt-root"],
+    );
+    // Registered, with its worktree in an unrelated directory.
+    let scattered = td.path().join("scattered/wt-reg");
+    run(
+        &registered,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature/reg",
+            scattered.to_str().expect("utf-8"),
+        ],
+    );
+
+    let mut cfg = Config::default();
+    cfg.worktree.base_dir = base.to_string_lossy().to_string();
+    cfg.status.cache_dir = td.path().join("cache").to_string_lossy().to_string();
+    cfg.discovery.cache_ttl_seconds = 0;
+    cfg.discovery.roots = vec![td.path().join("src").to_string_lossy().to_string()];
+    cfg.discovery.repositories = vec![
+        registered.to_string_lossy().to_string(),
+        // Also found by the walk; must not be listed twice.
+        in_base.to_string_lossy().to_string(),
+    ];
+
+    let branches = |cfg: &Config| -> Vec<String> {
+        let mut b: Vec<String> = discovery::discover_global_worktrees_cached(cfg)
+            .expect("discover")
+            .into_iter()
+            .filter(|e| !e.is_main)
+            .map(|e| e.branch)
+            .collect();
+        b.sort();
+        b
+    };
+    assert_eq!(branches(&cfg), ["feature/reg", "feature/root"]);
+    let entries = discovery::discover_global_worktrees_cached(&cfg).expect("discover");
+    assert_eq!(entries.iter().filter(|e| e.is_main).count(), 3);
+
+    // The registry entry is refreshed when the repository gains a worktree.
+    run(
+        &registered,
+        &[
+            "worktree",
+            "add",
+            "-q",
+            "-b",
+            "feature/reg2",
+            td.path().join("scattered/wt-reg2").to_str().expect("utf-8"),
+        ],
+    );
+    assert_eq!(
+        branches(&cfg),
+        ["feature/reg", "feature/reg2", "feature/root"]
+    );
+}
+
+fn init_repo(root: &std::path::Path) -> std::path::PathBuf {
+    let repo = root.join("repo");
+    std::fs::create_dir_all(&repo).expect("mkdir repo");
+
+    run(&repo, &["init"]);
+    run(&repo, &["config", "user.email", "test@example.com"]);
+    run(&repo, &["config", "user.name", "Test"]);
+
+    std::fs::write(repo.join("README.md"), "hello\n").expect("write");
+    run(&repo, &["add", "."]);
+    run(&repo, &["commit", "-m", "init"]);
+    repo
+}
+
 fn run(dir: &std::path::Path, args: &[&str]) {
     let out = Command::new("git")
         .args(args)
@@ -50,3 +1186,23 @@ fn run(dir: &std::path::Path, args: &[&str]) {
         );
     }
 }
+
+fn git_stdout(dir: &std::path::Path, args: &[&str]) -> String {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git command");
+    String::from_utf8_lossy(&out.stdout).trim().to_owned()
+}
+
+fn wait_for_changes(watcher: &mut WorktreeWatcher) -> WatchChanges {
+    let deadline = std::time::Instant::now() + std::time::Duration::from_secs(10);
+    loop {
+        let changes = watcher.take_changes();
+        if !changes.is_empty() || std::time::Instant::now() > deadline {
+            return changes;
+        }
+        std::thread::sleep(std::time::Duration::from_millis(50));
+    }
+}
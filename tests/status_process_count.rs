@@ -0,0 +1,139 @@
+use std::process::Command;
+use std::time::Duration;
+
+use gwtui::config::{ActivityMode, Config};
+use gwtui::core::git::{self, Git};
+use gwtui::core::status::{StatusCollector, StatusCollectorOptions, WorktreeState};
+use gwtui::core::worktree::WorktreeManager;
+
+// Kept in its own test binary: the git spawn counter is process-wide, so no
+// other test may run git concurrently.
+#[tokio::test]
+async fn status_collection_spawns_a_bounded_number_of_git_processes() {
+    if Command::new("git").arg("--version").output().is_err() {
+        eprintln!("skipping: git not found");
+        return;
+    }
+
+    let td = tempfile::tempdir().expect("tempdir");
+    let repo = td.path().join("repo");
+    std::fs::create_dir_all(&repo).expect("mkdir repo");
+    run(&repo, &["init", "-q"]);
+    run(&repo, &["config", "user.email", "test@example.com"]);
+    run(&repo, &["config", "user.name", "Test"]);
+    std::fs::write(repo.join("README.md"), "hello\n").expect("write");
+    run(&repo, &["add", "."]);
+    run(&repo, &["commit", "-qm", "init"]);
+
+    let wm = WorktreeManager::new(Git::from_dir(&repo).expect("git"), Config::default());
+    for i in 0..4 {
+        let wt = wm
+            .add(
+                &format!("feature/{i}"),
+                Some(&td.path().join(format!("wt-{i}"))),
+                true,
+            )
+            .expect("add worktree");
+        std::fs::write(wt.join("README.md"), "changed\n").expect("modify");
+        std::fs::write(wt.join("new.txt"), "new\n").expect("untracked");
+    }
+    let worktrees = wm.list().expect("list");
+    assert_eq!(worktrees.len(), 5);
+
+    let cache_dir = td.path().join("cache");
+    let options = |fetch_remote, activity| StatusCollectorOptions {
+        include_process: false,
+        fetch_remote,
+        include_base: fetch_remote,
+        fetch_interval: Duration::from_secs(300),
+        stale_threshold: Duration::from_secs(60 * 60 * 24 * 365),
+        base_dir: String::new(),
+        concurrency: 4,
+        activity,
+        cache_dir: Some(cache_dir.clone()),
+    };
+    let n = worktrees.len();
+
+    // The previous design spawned up to eight git processes per worktree
+    // (status, ls-files x3, rev-parse x2, rev-list x2). Fast activity needs
+    // no git at all; the exhaustive mode adds one `ls-files`.
+    let before = git::spawn_count();
+    let statuses = StatusCollector::new(options(false, ActivityMode::Fast))
+        .collect_all(&worktrees)
+        .await
+        .expect("collect");
+    let spawned = git::spawn_count() - before;
+    eprintln!(
+        "git processes for {n} worktrees: {spawned} (previously up to {})",
+        n * 8
+    );
+    assert_eq!(spawned, n);
+    assert!(cache_dir.join("activity.json").is_file());
+
+    let before = git::spawn_count();
+    let exhaustive = StatusCollector::new(options(false, ActivityMode::Exhaustive))
+        .collect_all(&worktrees)
+        .await
+        .expect("collect");
+    assert_eq!(git::spawn_count() - before, n * 2);
+    assert!(exhaustive.iter().all(|s| s.last_activity != "unknown"));
+
+    // Remote checks add one rev-list per worktree against the base branch;
+    // fetching and default-branch detection happen once for the shared
+    // repository, so their cost does not grow with the worktree count.
+    let collector = StatusCollector::new(options(true, ActivityMode::Fast));
+    let before = git::spawn_count();
+    let fetched = collector.collect_all(&worktrees).await.expect("collect");
+    let first_overhead = (git::spawn_count() - before) - n * 2;
+    assert!(
+        first_overhead <= 5,
+        "per-repository spawns: {first_overhead}"
+    );
+    assert!(fetched.iter().all(|s| s.fetch_error.is_none()));
+    assert!(fetched.iter().all(|s| s.last_fetch.is_some()));
+    assert!(fetched.iter().all(|s| s.base.is_some()));
+
+    // Within the fetch interval the repository is not fetched again.
+    let before = git::spawn_count();
+    collector.collect_all(&worktrees).await.expect("collect");
+    assert_eq!((git::spawn_count() - before) - n * 2, first_overhead - 1);
+
+    let features: Vec<_> = statuses
+        .iter()
+        .filter(|s| s.branch.starts_with("feature/"))
+        .collect();
+    assert_eq!(features.len(), 4);
+    for s in features {
+        assert_eq!(s.status, WorktreeState::Modified);
+        assert_eq!(s.git_status.modified, 1);
+        assert_eq!(s.git_status.untracked, 1);
+    }
+
+    // Matching `[[repos]]` entries needs `origin`, but only once per
+    // repository: later managers reuse the resolved config.
+    let cfg = Config {
+        repos: vec![toml::from_str("owner = 'acme'").expect("entry")],
+        ..Config::default()
+    };
+    let before = git::spawn_count();
+    let _ = WorktreeManager::new(Git::from_dir(&repo).expect("git"), cfg.clone());
+    assert_eq!(git::spawn_count() - before, 1);
+    let before = git::spawn_count();
+    for w in &worktrees {
+        let _ = WorktreeManager::new(Git::new(w.path.clone().into()), cfg.clone());
+    }
+    assert_eq!(git::spawn_count(), before);
+}
+
+fn run(dir: &std::path::Path, args: &[&str]) {
+    let out = Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .expect("git command");
+    assert!(
+        out.status.success(),
+        "git {args:?} failed: {}",
+        String::from_utf8_lossy(&out.stderr)
+    );
+}